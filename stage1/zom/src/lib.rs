@@ -6,11 +6,15 @@
 //! Zom repository, <https://github.com/zom-lang/zom>
 
 mod ops;
+mod pipeline;
+mod project;
 
 use std::{error::Error, ffi::OsString};
 
-use clap::{Parser, Subcommand};
-use ops::{bobj, gettarget::gettarget, version};
+use clap::{Parser, Subcommand, ValueEnum};
+use ops::{bobj, build, check, dump, gettarget, new, run, version};
+use termcolor::ColorChoice;
+use zom_errors::prelude::{BuiltLog, LogLevel, LogOutput, LogStream, MessageFormat};
 
 #[derive(Debug)]
 struct SError {
@@ -51,30 +55,90 @@ macro_rules! err {
 #[derive(Parser)]
 #[clap()]
 struct Args {
+    /// Format of the diagnostics, `json` prints a JSON object per diagnostic,
+    /// each on its own line
+    #[clap(long, value_enum, global = true, default_value_t = MessageFormatArg::Human)]
+    message_format: MessageFormatArg,
+
+    /// When to color the diagnostics
+    #[clap(long, value_enum, global = true, default_value_t = ColorArg::Auto)]
+    color: ColorArg,
+
     #[clap(subcommand)]
     command: Command,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum MessageFormatArg {
+    Human,
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum ColorArg {
+    /// Only if the output is a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+impl Args {
+    fn log_output(&self) -> LogOutput {
+        LogOutput {
+            color: match self.color {
+                ColorArg::Auto => ColorChoice::Auto,
+                ColorArg::Always => ColorChoice::Always,
+                ColorArg::Never => ColorChoice::Never,
+            },
+            format: match self.message_format {
+                MessageFormatArg::Human => MessageFormat::Human,
+                MessageFormatArg::Json => MessageFormat::Json,
+            },
+            stderr: false,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Command {
     /// Builds a given file into an object file
     Bobj(bobj::Args),
 
+    /// Builds the given files, or the current project, into an executable
+    Build(build::Args),
+
+    /// Compiles the given file, or the current project, and runs it right
+    /// away, without an executable
+    Run(run::Args),
+
+    /// Checks the given files, or the current project, for errors, without
+    /// generating any code
+    Check(check::Args),
+
+    /// Creates a new project, with a hello world
+    New(new::Args),
+
+    /// Creates a project in the current directory
+    Init,
+
     /// Get the current version of Zom
     Version,
 
-    /// Get the current target detected by LLVM.
-    GetTarget,
+    /// Get the current target detected by LLVM, and the ones Zom can compile
+    /// for with `--list`
+    GetTarget(gettarget::Args),
 
-    /// A subcommand used when devlopment, to quickly access a function or thing like that.
-    #[cfg(debug_assertions)]
-    Dev,
+    /// Prints the tokens, the AST or the LLVM IR of a source file, to debug
+    /// the compiler
+    Dump(dump::Args),
 }
 
 #[derive(Copy, Debug, Clone, PartialEq, Eq)]
 pub enum ExitStatus {
     Success,
     Error,
+    /// Exit code of a program ran by the compiler, e.g: with `zom run`.
+    Code(i32),
 }
 
 impl From<bool> for ExitStatus {
@@ -93,11 +157,27 @@ where
     T: Into<OsString> + Clone,
 {
     let args = Args::parse_from(args);
-    match args.command {
-        Command::Bobj(args) => bobj::build(args),
+    let output = args.log_output();
+    let res = match args.command {
+        Command::Bobj(args) => bobj::build(args, output),
+        Command::Build(args) => build::build(args, output),
+        Command::Run(args) => run::run(args, output),
+        Command::Check(args) => check::check(args, output),
+        Command::New(args) => new::new(args),
+        Command::Init => new::init(),
         Command::Version => version::version(),
-        Command::GetTarget => gettarget(),
-        #[cfg(debug_assertions)]
-        Command::Dev => ops::dev::dev(),
+        Command::GetTarget(args) => gettarget::gettarget(args),
+        Command::Dump(args) => dump::dump(args, output),
+    };
+
+    // with JSON, the errors that aren't in a source file are diagnostics too
+    match res {
+        Err(err) if output.format == MessageFormat::Json => {
+            let mut logs = LogStream::new(output);
+            logs.push_built(BuiltLog::message(LogLevel::Error, err.to_string()));
+            logs.print();
+            Ok(ExitStatus::Error)
+        }
+        res => res,
     }
 }
@@ -6,13 +6,14 @@ fn main() -> Result<(), Box<dyn Error>> {
     let status = match run_with_args(std::env::args_os()) {
         Ok(v) => v,
         Err(err) => {
-            print!("{}", err);
+            eprintln!("{}", err);
             std::process::exit(1)
         }
     };
     match status {
         ExitStatus::Success => {}
         ExitStatus::Error => std::process::exit(1),
+        ExitStatus::Code(code) => std::process::exit(code),
     };
     Ok(())
 }
@@ -1,8 +1,15 @@
 pub mod bobj;
 
+pub mod build;
+
+pub mod run;
+
+pub mod check;
+
+pub mod new;
+
 pub mod version;
 
 pub mod gettarget;
 
-#[cfg(debug_assertions)]
-pub mod dev;
+pub mod dump;
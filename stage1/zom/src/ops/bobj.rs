@@ -1,34 +1,254 @@
 use std::{
     error::Error,
-    // mem,
-    path::PathBuf,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
 };
 
-use crate::ExitStatus;
+use inkwell::{context::Context, targets::FileType};
+use zom_common::token::Token;
+use zom_compiler::compiler::Compiler;
+use zom_errors::prelude::LogOutput;
+
+use crate::{
+    err,
+    ops::dump::{format_ast, format_tokens, DumpFormat},
+    pipeline::{
+        compile_linked, lex, load_program, load_program_with_tokens, opt_level, parse_with_tokens,
+        read_source, source_roots, TargetArgs,
+    },
+    ExitStatus,
+};
+
+/// An artifact that the compiler can emit, one for each stage of the
+/// compilation.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EmitKind {
+    /// The tokens produced by the lexer
+    Tokens,
+    /// The AST produced by the parser
+    Ast,
+    /// The LLVM IR, in its textual form
+    LlvmIr,
+    /// The LLVM bitcode
+    LlvmBc,
+    /// The assembly of the target
+    Asm,
+    /// An object file
+    Obj,
+}
+
+impl EmitKind {
+    /// The extension of the file where the artifact is written.
+    pub fn extension(self) -> &'static str {
+        match self {
+            EmitKind::Tokens => "tokens",
+            EmitKind::Ast => "ast",
+            EmitKind::LlvmIr => "ll",
+            EmitKind::LlvmBc => "bc",
+            EmitKind::Asm => "s",
+            EmitKind::Obj => "o",
+        }
+    }
+
+    /// Does the artifact need the source file to be type checked and compiled
+    /// to a LLVM module?
+    fn needs_module(self) -> bool {
+        self >= EmitKind::LlvmIr
+    }
+}
+
+impl std::fmt::Display for EmitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use clap::ValueEnum;
+        write!(f, "{}", self.to_possible_value().unwrap().get_name())
+    }
+}
 
 #[derive(clap::Args, Debug, Clone)]
 pub struct Args {
     /// Path to the Zom source file
     source_file: PathBuf,
 
-    /// Path to where the object file will go
+    /// Path to where the artifact will go, `-` for stdout. When several
+    /// artifacts are emitted, only the extension changes for each of them
     #[clap(short, long)]
     output_file: Option<PathBuf>,
 
-    /// LLVM level of optimization (not implemented yet)
-    #[clap(short = 'O', long, default_value_t = 2)]
-    // TODO: Change this to the actual things later.
-    optimization_level: usize,
+    /// LLVM level of optimization, from 0 to 3
+    #[clap(short = 'O', long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(0..=3))]
+    optimization_level: u8,
+
+    /// Artifacts to emit, separated by commas
+    #[clap(long, value_enum, value_delimiter = ',', default_value = "obj")]
+    emit: Vec<EmitKind>,
 
-    /// Emits IR instead of a *.o
-    #[clap(long, short, action = clap::ArgAction::SetTrue)]
-    emit_ir: bool,
+    #[clap(flatten)]
+    target: TargetArgs,
 
     /// Print verbose ouput if enabled.
     #[clap(long, short = 'V', action = clap::ArgAction::SetTrue)]
     verbose: bool,
 }
 
-pub fn build(_args: Args) -> Result<ExitStatus, Box<dyn Error>> {
-    todo!("Will be removed later with #42")
+/// Where an artifact is written.
+enum Output {
+    Stdout,
+    File(PathBuf),
+}
+
+impl Output {
+    fn for_kind(args: &Args, kind: EmitKind) -> Output {
+        match &args.output_file {
+            Some(path) if path == Path::new("-") => Output::Stdout,
+            Some(path) if args.emit.len() == 1 => Output::File(path.clone()),
+            Some(path) => Output::File(path.with_extension(kind.extension())),
+            None => Output::File(args.source_file.with_extension(kind.extension())),
+        }
+    }
+
+    fn write(&self, kind: EmitKind, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+        let res = match self {
+            Output::Stdout => io::stdout().write_all(bytes),
+            Output::File(path) => fs::write(path, bytes),
+        };
+        if let Err(err) = res {
+            return err!(fmt "Couldn't write the {} artifact to {}, {}", kind, self, err);
+        }
+        if let Output::File(path) = self {
+            eprintln!("Wrote result to {:?}!", path);
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Output::Stdout => write!(f, "stdout"),
+            Output::File(path) => write!(f, "`{}`", path.display()),
+        }
+    }
+}
+
+/// Writes the tokens of the source file.
+fn write_tokens(args: &Args, tokens: &[Token], source: &str) -> Result<(), Box<dyn Error>> {
+    let out = Output::for_kind(args, EmitKind::Tokens);
+    out.write(
+        EmitKind::Tokens,
+        format_tokens(tokens, source, DumpFormat::Human).as_bytes(),
+    )
+}
+
+pub fn build(mut args: Args, output: LogOutput) -> Result<ExitStatus, Box<dyn Error>> {
+    args.emit.sort();
+    args.emit.dedup();
+    let emit = args.emit.clone();
+    let opt_level = opt_level(args.optimization_level);
+    // the diagnostics mustn't mix with an artifact written to stdout
+    let output = if args.output_file.as_deref() == Some(Path::new("-")) {
+        output.to_stderr()
+    } else {
+        output
+    };
+
+    // the tokens and the AST of the source file alone don't need the packages
+    // it imports. The file is lexed once, even for its tokens and its AST.
+    if !emit.iter().any(|kind| kind.needs_module()) {
+        let source = read_source(&args.source_file)?;
+        let (tokens, ast, lctx) = if emit.contains(&EmitKind::Ast) {
+            let parsed = parse_with_tokens(&source, &args.source_file, output, args.verbose);
+            let Some((ast, tokens, lctx)) = parsed else {
+                return Ok(ExitStatus::Error);
+            };
+            (tokens, Some(ast), lctx)
+        } else {
+            let Some((tokens, lctx)) = lex(&source, &args.source_file, output, args.verbose) else {
+                return Ok(ExitStatus::Error);
+            };
+            (tokens, None, lctx)
+        };
+
+        if emit.contains(&EmitKind::Tokens) {
+            write_tokens(&args, &tokens, &source)?;
+        }
+        if let Some(ast) = ast {
+            let out = Output::for_kind(&args, EmitKind::Ast);
+            out.write(
+                EmitKind::Ast,
+                format_ast(&ast, DumpFormat::Human).as_bytes(),
+            )?;
+        }
+        lctx.print();
+        return Ok(ExitStatus::Success);
+    }
+
+    let files = [args.source_file.clone()];
+    let roots = source_roots(&files);
+    let program = if emit.contains(&EmitKind::Tokens) {
+        load_program_with_tokens(roots, &files, output, args.verbose)?
+    } else {
+        load_program(roots, &files, output, args.verbose)?
+    };
+    let Some(program) = program else {
+        return Ok(ExitStatus::Error);
+    };
+    // the source file is loaded after the packages it imports
+    let root = program.packages.last().unwrap();
+    if emit.contains(&EmitKind::Tokens) {
+        write_tokens(&args, &root.tokens, &root.source)?;
+    }
+    if emit.contains(&EmitKind::Ast) {
+        let out = Output::for_kind(&args, EmitKind::Ast);
+        out.write(
+            EmitKind::Ast,
+            format_ast(&root.ast, DumpFormat::Human).as_bytes(),
+        )?;
+    }
+
+    let compiler = Compiler::new(&args.target.options(), opt_level)?;
+    let context = Context::create();
+    let compiled = compile_linked(
+        &context,
+        &program,
+        compiler.pointer_width(),
+        false,
+        args.verbose,
+    )?;
+    let Some(compiled) = compiled else {
+        return Ok(ExitStatus::Error);
+    };
+    let module = compiled.module;
+
+    if args.verbose {
+        eprintln!("Optimizing with level {}...", args.optimization_level);
+    }
+    compiler.prepare(&module);
+    Compiler::optimize(&module, opt_level);
+
+    for kind in emit.into_iter().filter(|kind| kind.needs_module()) {
+        let out = Output::for_kind(&args, kind);
+        if args.verbose {
+            eprintln!("Emitting {kind} to {out}...");
+        }
+        match kind {
+            EmitKind::LlvmIr => out.write(kind, module.print_to_string().to_bytes())?,
+            EmitKind::LlvmBc => out.write(kind, module.write_bitcode_to_memory().as_slice())?,
+            EmitKind::Asm | EmitKind::Obj => {
+                let file_type = if kind == EmitKind::Asm {
+                    FileType::Assembly
+                } else {
+                    FileType::Object
+                };
+                let buffer = match compiler.emit(&module, file_type) {
+                    Ok(buffer) => buffer,
+                    Err(err) => return err!(fmt "Couldn't emit the {} artifact, {}", kind, err),
+                };
+                out.write(kind, buffer.as_slice())?
+            }
+            EmitKind::Tokens | EmitKind::Ast => unreachable!(),
+        }
+    }
+
+    Ok(ExitStatus::Success)
 }
@@ -0,0 +1,287 @@
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use inkwell::{context::Context, targets::RelocMode, OptimizationLevel};
+use zom_compiler::{
+    compiler::Compiler,
+    linker::{self, LinkOptions, Linker},
+};
+use zom_errors::prelude::LogOutput;
+use zom_sema::loader::Program;
+
+use crate::{
+    err,
+    pipeline::{
+        compile_linked, compile_program, load_program, opt_level, source_roots, TargetArgs,
+    },
+    project::{OutputKind, Project},
+    ExitStatus,
+};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct Args {
+    /// Paths to the Zom source files, by default the current project is built
+    source_files: Vec<PathBuf>,
+
+    /// Path to where the executable will go, by default in the `target`
+    /// directory of the project
+    #[clap(short, long)]
+    output_file: Option<PathBuf>,
+
+    /// LLVM level of optimization, from 0 to 3
+    #[clap(short = 'O', long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(0..=3))]
+    optimization_level: u8,
+
+    /// Linker to use, a C compiler like `cc` or a linker like `ld`,
+    /// by default `$CC` or the first of `cc`, `gcc` and `clang` found
+    #[clap(long)]
+    linker: Option<PathBuf>,
+
+    /// Link with a library, can be used multiple times
+    #[clap(short = 'l', long = "lib", value_name = "LIB")]
+    libs: Vec<String>,
+
+    /// Add a directory where libraries are searched, can be used multiple times
+    #[clap(short = 'L', long = "lib-path", value_name = "PATH")]
+    lib_paths: Vec<PathBuf>,
+
+    #[clap(flatten)]
+    target: TargetArgs,
+
+    /// Print verbose ouput if enabled.
+    #[clap(long, short = 'V', action = clap::ArgAction::SetTrue)]
+    verbose: bool,
+}
+
+pub fn build(args: Args, output: LogOutput) -> Result<ExitStatus, Box<dyn Error>> {
+    let (roots, source_files, kind, default_output) = if args.source_files.is_empty() {
+        let project = Project::find()?;
+        let output = project.output_path();
+        if let Some(dir) = output.parent() {
+            if let Err(err) = fs::create_dir_all(dir) {
+                return err!(fmt "Couldn't create the directory `{}`, {}", dir.display(), err);
+            }
+        }
+        if args.verbose {
+            eprintln!(
+                "Building the project `{}` v{}...",
+                project.name(),
+                project.manifest.package.version
+            );
+        }
+        (
+            project.source_roots()?,
+            project.all_source_files()?,
+            project.manifest.package.kind,
+            output,
+        )
+    } else {
+        let output = args.source_files[0].with_extension(env::consts::EXE_EXTENSION);
+        let roots = source_roots(&args.source_files);
+        (roots, args.source_files.clone(), OutputKind::Bin, output)
+    };
+    let output_file = args.output_file.clone().unwrap_or(default_output);
+
+    let Some(program) = load_program(roots, &source_files, output, args.verbose)? else {
+        return Ok(ExitStatus::Error);
+    };
+
+    let opt_level = opt_level(args.optimization_level);
+    let target_opts = args.target.options();
+    let compiler = Compiler::new(&target_opts, opt_level)?;
+
+    if kind == OutputKind::Object {
+        return build_object(&program, &output_file, &compiler, opt_level, args.verbose);
+    }
+
+    let obj_dir = env::temp_dir().join(format!("zom-build-{}", process::id()));
+    if let Err(err) = fs::create_dir_all(&obj_dir) {
+        return err!(fmt "Couldn't create the directory `{}`, {}", obj_dir.display(), err);
+    }
+
+    let status = match kind {
+        OutputKind::Bin => {
+            build_executable(args, &program, &output_file, &obj_dir, &compiler, opt_level)
+        }
+        OutputKind::Staticlib => build_staticlib(
+            &program,
+            &output_file,
+            &obj_dir,
+            &compiler,
+            opt_level,
+            args.verbose,
+        ),
+        OutputKind::Object => unreachable!(),
+    };
+
+    // the objects are only needed to link
+    let _ = fs::remove_dir_all(&obj_dir);
+
+    status
+}
+
+fn build_executable(
+    args: Args,
+    program: &Program,
+    output_file: &Path,
+    obj_dir: &Path,
+    compiler: &Compiler,
+    opt_level: OptimizationLevel,
+) -> Result<ExitStatus, Box<dyn Error>> {
+    let target = compiler.cross_triple();
+    let linker = match args.linker {
+        Some(linker) => Linker::new(linker),
+        None => Linker::detect(target.as_deref())?,
+    };
+
+    let mut link_opts = LinkOptions {
+        libs: args.libs,
+        lib_paths: args.lib_paths,
+        no_pie: args.target.options().reloc_mode != RelocMode::PIC,
+        target,
+        ..Default::default()
+    };
+
+    let success = compile_objects(
+        program,
+        obj_dir,
+        compiler,
+        opt_level,
+        true,
+        args.verbose,
+        &mut link_opts,
+    )?;
+    if !success {
+        return Ok(ExitStatus::Error);
+    }
+
+    if args.verbose {
+        eprintln!("Linking with `{}`...", linker.program().display());
+    }
+    linker.link(&link_opts, output_file)?;
+    eprintln!("Wrote result to {:?}!", output_file);
+    Ok(ExitStatus::Success)
+}
+
+/// Builds a static library, the libraries of the `extern` declarations are
+/// left to the executable linking with it.
+fn build_staticlib(
+    program: &Program,
+    output_file: &Path,
+    obj_dir: &Path,
+    compiler: &Compiler,
+    opt_level: OptimizationLevel,
+    verbose: bool,
+) -> Result<ExitStatus, Box<dyn Error>> {
+    let mut link_opts = LinkOptions::default();
+    let success = compile_objects(
+        program,
+        obj_dir,
+        compiler,
+        opt_level,
+        false,
+        verbose,
+        &mut link_opts,
+    )?;
+    if !success {
+        return Ok(ExitStatus::Error);
+    }
+
+    if verbose {
+        eprintln!("Archiving `{}`...", output_file.display());
+    }
+    linker::archive(&link_opts.objects, output_file)?;
+    eprintln!("Wrote result to {:?}!", output_file);
+    Ok(ExitStatus::Success)
+}
+
+/// Builds a single object file, with the modules of all the packages.
+fn build_object(
+    program: &Program,
+    output_file: &Path,
+    compiler: &Compiler,
+    opt_level: OptimizationLevel,
+    verbose: bool,
+) -> Result<ExitStatus, Box<dyn Error>> {
+    let context = Context::create();
+    let compiled = compile_linked(&context, program, compiler.pointer_width(), false, verbose)?;
+    let Some(compiled) = compiled else {
+        return Ok(ExitStatus::Error);
+    };
+
+    compiler.prepare(&compiled.module);
+    Compiler::optimize(&compiled.module, opt_level);
+    if let Err(err) = compiler.compile(&compiled.module, output_file) {
+        return err!(fmt "Couldn't write the object file to `{}`, {}", output_file.display(), err);
+    }
+    eprintln!("Wrote result to {:?}!", output_file);
+    Ok(ExitStatus::Success)
+}
+
+/// Compiles each package to an object file in `obj_dir` and adds it to the
+/// link options, with the libraries of its `extern` declarations.
+///
+/// With `entry_point`, the packages are those of an executable, the root
+/// package, the last one, must define `main`. Returns false if a package
+/// contains an error.
+fn compile_objects(
+    program: &Program,
+    obj_dir: &Path,
+    compiler: &Compiler,
+    opt_level: OptimizationLevel,
+    entry_point: bool,
+    verbose: bool,
+    link_opts: &mut LinkOptions,
+) -> Result<bool, Box<dyn Error>> {
+    let context = Context::create();
+    let compiled = compile_program(
+        &context,
+        program,
+        compiler.pointer_width(),
+        entry_point,
+        verbose,
+    );
+    let Some(compiled) = compiled else {
+        return Ok(false);
+    };
+
+    // the `main` of the other packages is mangled
+    let has_main = compiled.last().is_some_and(|root| {
+        root.module
+            .get_function("main")
+            .is_some_and(|main| main.count_basic_blocks() > 0)
+    });
+    for (i, (compiled, package)) in compiled.iter().zip(&program.packages).enumerate() {
+        for lib in &compiled.extern_libs {
+            link_opts.add_extern_lib(lib);
+        }
+
+        compiler.prepare(&compiled.module);
+        Compiler::optimize(&compiled.module, opt_level);
+
+        // files may have the same name, in different directories
+        let stem = package
+            .path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let obj = obj_dir.join(format!("{i}-{stem}.o"));
+        if verbose {
+            eprintln!("Emitting `{}`...", obj.display());
+        }
+        if let Err(err) = compiler.compile(&compiled.module, &obj) {
+            return err!(fmt "Couldn't write the object file to `{}`, {}", obj.display(), err);
+        }
+        link_opts.objects.push(obj);
+    }
+
+    if entry_point && !has_main {
+        return err!("No `main` function found, an executable needs one.");
+    }
+    Ok(true)
+}
@@ -0,0 +1,59 @@
+use std::{error::Error, path::PathBuf};
+
+use zom_errors::prelude::LogOutput;
+
+use crate::{
+    pipeline::{load_program_lenient, source_roots, typeck_program},
+    project::{collect_source_files, Project},
+    ExitStatus,
+};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct Args {
+    /// Paths to Zom source files or to directories containing some, by
+    /// default the files of the current project and of its dependencies
+    paths: Vec<PathBuf>,
+
+    /// Print verbose ouput if enabled.
+    #[clap(long, short = 'V', action = clap::ArgAction::SetTrue)]
+    verbose: bool,
+}
+
+pub fn check(args: Args, output: LogOutput) -> Result<ExitStatus, Box<dyn Error>> {
+    let mut files = Vec::new();
+    for path in &args.paths {
+        if path.is_dir() {
+            collect_source_files(path, &mut files)?;
+        } else {
+            files.push(path.clone());
+        }
+    }
+    let roots = if args.paths.is_empty() {
+        let project = Project::find()?;
+        files = project.all_source_files()?;
+        project.source_roots()?
+    } else {
+        source_roots(&files)
+    };
+
+    // the files that couldn't be loaded are reported, the other ones are
+    // still checked
+    let program = load_program_lenient(roots, &files, output, args.verbose)?;
+
+    let mut failed = program.failed();
+    for checked in typeck_program(&program, usize::BITS, args.verbose) {
+        match checked {
+            Some((_, lctx)) => lctx.print(),
+            None => failed += 1,
+        }
+    }
+
+    if args.verbose || failed != 0 {
+        eprintln!(
+            "Checked {} package(s), {} failed.",
+            program.packages.len() + program.failed(),
+            failed
+        );
+    }
+    Ok((failed == 0).into())
+}
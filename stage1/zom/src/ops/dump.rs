@@ -0,0 +1,262 @@
+//! `zom dump`, prints what a stage of the front-end makes of a source file,
+//! to debug the compiler.
+
+use std::{error::Error, path::PathBuf};
+
+use inkwell::context::Context;
+use serde::Serialize;
+use serde_json::Value;
+use zom_common::token::Token;
+use zom_errors::prelude::LogOutput;
+use zom_parser::source_file::SourceFile;
+
+use crate::{
+    pipeline::{compile_linked, lex, load_program, parse, read_source, source_roots},
+    ExitStatus,
+};
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpKind {
+    /// The tokens, with their span and the code they come from
+    Tokens,
+    /// The abstract syntax tree, with the span of its nodes
+    Ast,
+    /// The LLVM IR, unoptimized, with the packages imported by the file
+    Ir,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    Human,
+    Json,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct Args {
+    /// What to dump
+    #[clap(value_enum)]
+    kind: DumpKind,
+
+    /// Path to the Zom source file
+    source_file: PathBuf,
+
+    /// Format of the dump
+    #[clap(long, value_enum, default_value_t = DumpFormat::Human)]
+    format: DumpFormat,
+}
+
+pub fn dump(args: Args, output: LogOutput) -> Result<ExitStatus, Box<dyn Error>> {
+    // stdout carries the dump
+    let output = output.to_stderr();
+    if args.kind == DumpKind::Ir {
+        return dump_ir(args, output);
+    }
+
+    let source = read_source(&args.source_file)?;
+    if args.kind == DumpKind::Tokens {
+        let Some((tokens, lctx)) = lex(&source, &args.source_file, output, false) else {
+            return Ok(ExitStatus::Error);
+        };
+        print!("{}", format_tokens(&tokens, &source, args.format));
+        lctx.print();
+        return Ok(ExitStatus::Success);
+    }
+
+    let Some((ast, lctx)) = parse(&source, &args.source_file, output, false) else {
+        return Ok(ExitStatus::Error);
+    };
+    print!("{}", format_ast(&ast, args.format));
+    lctx.print();
+    Ok(ExitStatus::Success)
+}
+
+fn dump_ir(args: Args, output: LogOutput) -> Result<ExitStatus, Box<dyn Error>> {
+    let files = [args.source_file];
+    let Some(program) = load_program(source_roots(&files), &files, output, false)? else {
+        return Ok(ExitStatus::Error);
+    };
+    let context = Context::create();
+    let Some(compiled) = compile_linked(&context, &program, usize::BITS, false, false)? else {
+        return Ok(ExitStatus::Error);
+    };
+
+    let ir = compiled.module.print_to_string().to_string();
+    match args.format {
+        DumpFormat::Human => print!("{ir}"),
+        DumpFormat::Json => println!("{}", serde_json::to_string(&ir)?),
+    }
+    Ok(ExitStatus::Success)
+}
+
+#[derive(Serialize)]
+struct DumpedToken<'a> {
+    #[serde(flatten)]
+    token: &'a Token,
+    text: &'a str,
+}
+
+/// Formats the tokens, one per line, with their span and the code they come
+/// from.
+pub fn format_tokens(tokens: &[Token], source: &str, format: DumpFormat) -> String {
+    let text = |token: &Token| source.get(token.span.clone()).unwrap_or_default();
+
+    if format == DumpFormat::Json {
+        let tokens: Vec<DumpedToken> = tokens
+            .iter()
+            .map(|token| DumpedToken {
+                token,
+                text: text(token),
+            })
+            .collect();
+        return to_json(&tokens);
+    }
+
+    let mut s = String::new();
+    for token in tokens {
+        let span = format!("{}..{}", token.span.start, token.span.end);
+        s.push_str(&format!(
+            "{span:<12}{:<24} {:?}\n",
+            format!("{:?}", token.tt),
+            text(token)
+        ));
+    }
+    s
+}
+
+/// Formats the AST, as an indented tree where each node has its span.
+pub fn format_ast(ast: &SourceFile, format: DumpFormat) -> String {
+    if format == DumpFormat::Json {
+        return to_json(ast);
+    }
+
+    let value = serde_json::to_value(ast).expect("the AST can be serialized");
+    let mut s = String::new();
+    write_node(&mut s, 0, "SourceFile", &value);
+    s
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> String {
+    let mut json = serde_json::to_string_pretty(value).expect("the dump can be serialized");
+    json.push('\n');
+    json
+}
+
+/// Writes a node of the tree, on a line starting with its label, and then its
+/// children, indented.
+fn write_node(s: &mut String, indent: usize, label: &str, value: &Value) {
+    let (head, children) = describe(value);
+    s.push_str(&"  ".repeat(indent));
+    s.push_str(label);
+    if head.starts_with('@') {
+        s.push(' ');
+    } else if !head.is_empty() {
+        s.push_str(": ");
+    }
+    s.push_str(&head);
+    s.push('\n');
+    for (label, child) in children {
+        write_node(s, indent + 1, &label, child);
+    }
+}
+
+/// Describes a serialized node of the AST, returns what is written on its line
+/// and its children.
+///
+/// A node with a span, like `Expression`, whose only other field is a variant
+/// of an enum, like `Expr`, is written as the variant.
+fn describe(value: &Value) -> (String, Vec<(String, &Value)>) {
+    match value {
+        Value::Null => ("none".to_owned(), Vec::new()),
+        Value::Array(values) if values.iter().all(is_scalar) => {
+            let values: Vec<String> = values.iter().map(Value::to_string).collect();
+            (format!("[{}]", values.join(", ")), Vec::new())
+        }
+        Value::Array(values) => (
+            String::new(),
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| (format!("[{i}]"), value))
+                .collect(),
+        ),
+        Value::Object(fields) => {
+            if let Some(span) = as_span(value) {
+                return (span, Vec::new());
+            }
+            if let Some((variant, content)) = as_variant(value) {
+                return match content {
+                    // a node with a span keeps its own line
+                    Value::Object(fields) if fields.contains_key("span") => {
+                        (variant.to_owned(), vec![("[0]".to_owned(), content)])
+                    }
+                    Value::Object(_) => {
+                        let (head, children) = describe(content);
+                        (join(variant, &head), children)
+                    }
+                    Value::Array(values) => (
+                        variant.to_owned(),
+                        values
+                            .iter()
+                            .enumerate()
+                            .map(|(i, value)| (format!("[{i}]"), value))
+                            .collect(),
+                    ),
+                    _ => (format!("{variant}({})", describe(content).0), Vec::new()),
+                };
+            }
+
+            let span = fields.get("span").and_then(as_span);
+            let others: Vec<(&String, &Value)> =
+                fields.iter().filter(|(name, _)| *name != "span").collect();
+            if let ([(_, only)], Some(span)) = (&others[..], &span) {
+                if as_variant(only).is_some() {
+                    let (head, children) = describe(only);
+                    return (join(&head, &format!("@ {span}")), children);
+                }
+            }
+
+            let head = span.map(|span| format!("@ {span}")).unwrap_or_default();
+            let children = others
+                .into_iter()
+                .map(|(name, value)| (name.clone(), value))
+                .collect();
+            (head, children)
+        }
+        value => (value.to_string(), Vec::new()),
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+fn join(a: &str, b: &str) -> String {
+    match (a.is_empty(), b.is_empty()) {
+        (true, _) => b.to_owned(),
+        (_, true) => a.to_owned(),
+        _ => format!("{a} {b}"),
+    }
+}
+
+/// A serialized `Range<usize>`, as `start..end`.
+fn as_span(value: &Value) -> Option<String> {
+    let fields = value.as_object()?;
+    if fields.len() != 2 {
+        return None;
+    }
+    let start = fields.get("start")?.as_u64()?;
+    let end = fields.get("end")?.as_u64()?;
+    Some(format!("{start}..{end}"))
+}
+
+/// A serialized variant of an enum with fields, an object with a single field
+/// named after the variant.
+fn as_variant(value: &Value) -> Option<(&str, &Value)> {
+    let fields = value.as_object()?;
+    if fields.len() != 1 {
+        return None;
+    }
+    let (name, content) = fields.iter().next()?;
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        .then_some((name.as_str(), content))
+}
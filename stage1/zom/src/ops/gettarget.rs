@@ -2,10 +2,33 @@ use std::error::Error;
 
 use crate::ExitStatus;
 
-use zom_compiler::target::get_target_triple;
+use zom_compiler::target::{get_target_triple, list_targets};
 
-pub fn gettarget() -> Result<ExitStatus, Box<dyn Error>> {
+#[derive(clap::Args, Debug, Clone)]
+pub struct Args {
+    /// Also list the architectures Zom can compile for
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    list: bool,
+}
+
+pub fn gettarget(args: Args) -> Result<ExitStatus, Box<dyn Error>> {
     println!("Target: {}", get_target_triple());
+    if !args.list {
+        return Ok(ExitStatus::Success);
+    }
+
+    println!();
+    println!("Supported architectures, use them in the triple given to `--target`:");
+
+    let targets = list_targets();
+    let width = targets
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    for (name, description) in targets {
+        println!("    {name:<width$} - {description}");
+    }
 
     Ok(ExitStatus::Success)
 }
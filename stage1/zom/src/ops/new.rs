@@ -0,0 +1,148 @@
+use std::{env, error::Error, fs, path::Path};
+
+use crate::{
+    err,
+    project::{is_valid_name, ENTRY_FILE, MANIFEST_FILE, TARGET_DIR},
+    ExitStatus,
+};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct Args {
+    /// Name of the package, the project is created in a directory of this name
+    name: String,
+}
+
+pub fn new(args: Args) -> Result<ExitStatus, Box<dyn Error>> {
+    let root = Path::new(&args.name);
+    if root.exists() {
+        return err!(fmt "The destination `{}` already exists", root.display());
+    }
+    create_project(root, &args.name)?;
+
+    println!("Created the project `{}`", args.name);
+    Ok(ExitStatus::Success)
+}
+
+/// Creates a project in the current directory, named after it.
+pub fn init() -> Result<ExitStatus, Box<dyn Error>> {
+    let root = env::current_dir()?;
+    let name = match root.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => return err!(fmt "Couldn't name the project after `{}`", root.display()),
+    };
+    if root.join(MANIFEST_FILE).exists() {
+        return err!(fmt "`{}` already exists, the project is already initialized", MANIFEST_FILE);
+    }
+    create_project(&root, &name)?;
+
+    println!("Created the project `{}`", name);
+    Ok(ExitStatus::Success)
+}
+
+/// Writes the manifest and a hello world in `root`, the existing files are
+/// left as they are.
+fn create_project(root: &Path, name: &str) -> Result<(), Box<dyn Error>> {
+    if !is_valid_name(name) {
+        return err!(fmt "Invalid package name `{}`, it must be an identifier and not a keyword", name);
+    }
+
+    let manifest = format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n\n[dependencies]\n");
+    let main = format!(
+        "package {name}\n\nextern \"C\" fn puts(s: *const u8) i32\n\nfn main() void {{\n    puts(\"Hello, world!\")\n}}\n"
+    );
+
+    create_file(&root.join(MANIFEST_FILE), &manifest)?;
+    create_file(&root.join(".gitignore"), &format!("/{TARGET_DIR}\n"))?;
+    create_file(&root.join("src").join(ENTRY_FILE), &main)?;
+    Ok(())
+}
+
+fn create_file(path: &Path, content: &str) -> Result<(), Box<dyn Error>> {
+    if path.exists() {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        if let Err(err) = fs::create_dir_all(dir) {
+            return err!(fmt "Couldn't create the directory `{}`, {}", dir.display(), err);
+        }
+    }
+    if let Err(err) = fs::write(path, content) {
+        return err!(fmt "Couldn't write `{}`, {}", path.display(), err);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, process};
+
+    use super::*;
+    use crate::project::Project;
+
+    /// A directory in the temporary directory, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let dir = env::temp_dir().join(format!("zom-new-{name}-{}", process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn creates_project() {
+        let dir = TempDir::new("create");
+        create_project(&dir.0, "hello").unwrap();
+
+        let project = Project::load(&dir.0).unwrap();
+        assert_eq!(project.name(), "hello");
+        assert_eq!(project.manifest.package.version, "0.1.0");
+        assert_eq!(
+            fs::read_to_string(dir.0.join(".gitignore")).unwrap(),
+            "/target\n"
+        );
+        let main = fs::read_to_string(dir.0.join("src").join(ENTRY_FILE)).unwrap();
+        assert!(main.starts_with("package hello\n"));
+        assert!(main.contains("fn main() void"));
+        assert_eq!(
+            project.source_files().unwrap(),
+            [dir.0.join("src").join(ENTRY_FILE)]
+        );
+    }
+
+    #[test]
+    fn keeps_existing_files() {
+        let dir = TempDir::new("existing");
+        fs::create_dir_all(dir.0.join("src")).unwrap();
+        fs::write(dir.0.join("src").join(ENTRY_FILE), "package mine\n").unwrap();
+        create_project(&dir.0, "mine").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.0.join("src").join(ENTRY_FILE)).unwrap(),
+            "package mine\n"
+        );
+        assert!(dir.0.join(MANIFEST_FILE).is_file());
+    }
+
+    #[test]
+    fn invalid_names() {
+        for name in ["fn", "while", "2cool", "my-project"] {
+            let dir = TempDir::new(&format!("invalid-{name}"));
+            let err = create_project(&dir.0, name).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Invalid package name `{name}`, it must be an identifier and not a keyword"
+                )
+            );
+            assert!(!dir.0.exists());
+        }
+    }
+}
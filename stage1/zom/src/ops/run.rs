@@ -0,0 +1,88 @@
+use std::{error::Error, ffi::CString, path::PathBuf};
+
+use inkwell::context::Context;
+use zom_compiler::{compiler::Compiler, jit};
+use zom_errors::prelude::LogOutput;
+
+use crate::{
+    err,
+    pipeline::{compile_linked, load_program, opt_level, source_roots},
+    project::Project,
+    ExitStatus,
+};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct Args {
+    /// Path to the Zom source file, by default the current project is run. If
+    /// it doesn't end with `.zom`, it's the first argument of the program
+    source_file: Option<PathBuf>,
+
+    /// Arguments passed to the program
+    #[clap(trailing_var_arg = true, allow_hyphen_values = true)]
+    program_args: Vec<String>,
+
+    /// LLVM level of optimization, from 0 to 3
+    #[clap(short = 'O', long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=3))]
+    optimization_level: u8,
+
+    /// Print verbose ouput if enabled.
+    #[clap(long, short = 'V', action = clap::ArgAction::SetTrue)]
+    verbose: bool,
+}
+
+pub fn run(mut args: Args, output: LogOutput) -> Result<ExitStatus, Box<dyn Error>> {
+    let opt_level = opt_level(args.optimization_level);
+
+    let (program_name, roots, source_files) = match args.source_file {
+        Some(path) if path.extension().is_some_and(|ext| ext == "zom") => {
+            let files = vec![path.clone()];
+            (path, source_roots(&files), files)
+        }
+        source_file => {
+            if let Some(arg) = source_file {
+                args.program_args
+                    .insert(0, arg.to_string_lossy().into_owned());
+            }
+            let project = Project::find()?;
+            (
+                PathBuf::from(project.name()),
+                project.source_roots()?,
+                project.all_source_files()?,
+            )
+        }
+    };
+
+    let Some(program) = load_program(roots, &source_files, output, args.verbose)? else {
+        return Ok(ExitStatus::Error);
+    };
+    let context = Context::create();
+    // the JIT runs the code on the host
+    let compiled = match compile_linked(&context, &program, usize::BITS, true, args.verbose)? {
+        Some(compiled) => compiled,
+        None => return Ok(ExitStatus::Error),
+    };
+
+    if compiled.module.get_function("main").is_none() {
+        return err!("No `main` function found, a program needs one to run.");
+    }
+    for lib in &compiled.extern_libs {
+        if !jit::load_extern_lib(lib) {
+            eprintln!("warning: couldn't load the library {lib:?}, its functions may not resolve");
+        }
+    }
+
+    Compiler::optimize(&compiled.module, opt_level);
+
+    let mut program_args = Vec::with_capacity(args.program_args.len() + 1);
+    program_args.push(CString::new(program_name.to_string_lossy().as_bytes())?);
+    for arg in args.program_args {
+        program_args.push(CString::new(arg)?);
+    }
+
+    if args.verbose {
+        eprintln!("Running `{}`...", program_name.display());
+    }
+    let code = jit::run_main(&compiled.module, opt_level, &program_args)?;
+
+    Ok(ExitStatus::Code(code))
+}
@@ -0,0 +1,448 @@
+//! The compilation pipeline shared by the subcommands, from source files to
+//! LLVM modules.
+//!
+//! Every stage prints its logs and returns `None` if there was an error in the
+//! source files, so a subcommand can stop after any of them.
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use inkwell::{
+    context::Context,
+    module::Module,
+    targets::{CodeModel, RelocMode},
+    OptimizationLevel,
+};
+use zom_codegen::gen::CodeGen;
+use zom_common::token::Token;
+use zom_compiler::target::TargetOptions;
+use zom_errors::prelude::*;
+use zom_lexer::Lexer;
+use zom_parser::{source_file::SourceFile, toplvldecl::TopLvlDecl, Parser};
+use zom_sema::{
+    loader::{Loader, Program},
+    typeck::{ResolvedImport, TypeChecker, TypeckResults},
+};
+
+use crate::err;
+
+/// A package compiled to a LLVM module.
+pub struct CompiledFile<'ctx> {
+    pub module: Module<'ctx>,
+    /// Libraries of the `extern "lib" fn` declarations, in order of appearance.
+    pub extern_libs: Vec<String>,
+}
+
+/// Converts the `-O` level given to the subcommands, from 0 to 3.
+pub fn opt_level(level: u8) -> OptimizationLevel {
+    match level {
+        0 => OptimizationLevel::None,
+        1 => OptimizationLevel::Less,
+        2 => OptimizationLevel::Default,
+        _ => OptimizationLevel::Aggressive,
+    }
+}
+
+/// Options selecting the machine the code is generated for, shared by the
+/// subcommands emitting machine code.
+#[derive(clap::Args, Debug, Clone)]
+pub struct TargetArgs {
+    /// Target triple to compile for, by default the host, see `zom get-target --list`
+    #[clap(long)]
+    target: Option<String>,
+
+    /// CPU to compile for, by default the host CPU when compiling for the host
+    /// and a generic CPU otherwise
+    #[clap(long)]
+    cpu: Option<String>,
+
+    /// Features of the CPU to enable or disable, e.g: `+avx2,-sse4.1`
+    #[clap(long)]
+    target_features: Option<String>,
+
+    /// Relocation model of the generated code
+    #[clap(long, value_enum, default_value_t = RelocModelArg::Pic)]
+    relocation_model: RelocModelArg,
+
+    /// Generate position independent code, same as `--relocation-model pic`
+    #[clap(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["static_code", "relocation_model"])]
+    pic: bool,
+
+    /// Generate non-relocatable code, same as `--relocation-model static`
+    #[clap(long = "static", action = clap::ArgAction::SetTrue, conflicts_with = "relocation_model")]
+    static_code: bool,
+
+    /// Code model of the generated code
+    #[clap(long, value_enum, default_value_t = CodeModelArg::Default)]
+    code_model: CodeModelArg,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+enum RelocModelArg {
+    /// The default of the target
+    Default,
+    /// Position independent code, needed by PIE executables and shared libraries
+    Pic,
+    /// Non-relocatable code, for static executables
+    Static,
+    /// Code that is not position independent but may reference shared libraries
+    DynamicNoPic,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+enum CodeModelArg {
+    Default,
+    Small,
+    Kernel,
+    Medium,
+    Large,
+}
+
+impl TargetArgs {
+    /// Returns the relocation model, given by `--pic`, `--static` or
+    /// `--relocation-model`, they conflict with each other.
+    fn reloc_model(&self) -> RelocModelArg {
+        if self.pic {
+            RelocModelArg::Pic
+        } else if self.static_code {
+            RelocModelArg::Static
+        } else {
+            self.relocation_model
+        }
+    }
+
+    pub fn options(&self) -> TargetOptions {
+        TargetOptions {
+            triple: self.target.clone(),
+            cpu: self.cpu.clone(),
+            features: self.target_features.clone(),
+            reloc_mode: match self.reloc_model() {
+                RelocModelArg::Default => RelocMode::Default,
+                RelocModelArg::Pic => RelocMode::PIC,
+                RelocModelArg::Static => RelocMode::Static,
+                RelocModelArg::DynamicNoPic => RelocMode::DynamicNoPic,
+            },
+            code_model: match self.code_model {
+                CodeModelArg::Default => CodeModel::Default,
+                CodeModelArg::Small => CodeModel::Small,
+                CodeModelArg::Kernel => CodeModel::Kernel,
+                CodeModelArg::Medium => CodeModel::Medium,
+                CodeModelArg::Large => CodeModel::Large,
+            },
+        }
+    }
+}
+
+/// Reads a source file.
+pub fn read_source(path: &Path) -> Result<String, Box<dyn Error>> {
+    match fs::read_to_string(path) {
+        Ok(src) => Ok(src),
+        Err(err) => err!(fmt "Couldn't read `{}`, {}", path.display(), err),
+    }
+}
+
+/// Lexes a source file.
+pub fn lex<'a>(
+    source: &'a str,
+    path: &'a Path,
+    output: LogOutput,
+    verbose: bool,
+) -> Option<(Vec<Token>, LogContext<'a>)> {
+    let lctx = LogContext::new(source, path, output);
+
+    if verbose {
+        eprintln!("Lexing `{}`...", path.display());
+    }
+    let mut lexer = Lexer::new(source, path, lctx);
+    match lexer.lex() {
+        FinalRes::Ok(tokens, lctx) => Some((tokens, lctx)),
+        FinalRes::Err(logs) => {
+            logs.print();
+            None
+        }
+    }
+}
+
+/// Parses a source file, it's lexed as the parser needs its tokens.
+pub fn parse<'a>(
+    source: &'a str,
+    path: &'a Path,
+    output: LogOutput,
+    verbose: bool,
+) -> Option<(SourceFile, LogContext<'a>)> {
+    parse_file(
+        Lexer::new(source, path, LogContext::new(source, path, output)),
+        verbose,
+    )
+    .map(|(ast, _, lctx)| (ast, lctx))
+}
+
+/// Parses a source file like [`parse`], and also returns its tokens, it's
+/// lexed once for both.
+pub fn parse_with_tokens<'a>(
+    source: &'a str,
+    path: &'a Path,
+    output: LogOutput,
+    verbose: bool,
+) -> Option<(SourceFile, Vec<Token>, LogContext<'a>)> {
+    let lexer = Lexer::new(source, path, LogContext::new(source, path, output));
+    parse_file(lexer.recording(), verbose)
+}
+
+/// Parses the file of the lexer, the tokens are the ones the lexer recorded.
+fn parse_file(lexer: Lexer, verbose: bool) -> Option<(SourceFile, Vec<Token>, LogContext)> {
+    if verbose {
+        eprintln!("Parsing `{}`...", lexer.file_path().display());
+    }
+    let (res, tokens) = Parser::new(lexer).parse_with_tokens();
+    match res {
+        // the lexing errors don't stop the parsing
+        FinalRes::Ok(_, lctx) if lctx.failed() => {
+            lctx.print();
+            None
+        }
+        FinalRes::Ok(ast, lctx) => Some((ast, tokens, lctx)),
+        FinalRes::Err(logs) => {
+            logs.print();
+            None
+        }
+    }
+}
+
+/// Generates the LLVM module of a type checked source file, the remaining logs
+/// are printed. Only the `main` of the `root` package is the `main` of the
+/// program.
+#[allow(clippy::too_many_arguments)]
+pub fn gen_module<'ctx>(
+    context: &'ctx Context,
+    ast: &SourceFile,
+    results: TypeckResults,
+    lctx: LogContext,
+    pointer_width: u32,
+    root: bool,
+    entry_point: bool,
+    verbose: bool,
+) -> Option<Module<'ctx>> {
+    if verbose {
+        eprintln!(
+            "Generating the LLVM IR of `{}`...",
+            ast.pkg_path.path.join(".")
+        );
+    }
+    let mut codegen = CodeGen::new(context, &ast.pkg_path.path.join("."), lctx, results)
+        .with_pointer_width(pointer_width);
+    if entry_point {
+        codegen = codegen.with_entry_point();
+    } else if root {
+        codegen = codegen.with_root();
+    }
+    match codegen.gen(ast) {
+        FinalRes::Ok(module, lctx) => {
+            lctx.print();
+            Some(module)
+        }
+        FinalRes::Err(logs) => {
+            logs.print();
+            None
+        }
+    }
+}
+
+/// Directories where the packages imported by source files given on the
+/// command line are searched, the directories of the files.
+pub fn source_roots(files: &[PathBuf]) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    for file in files {
+        let root = match file.parent() {
+            Some(dir) if dir != Path::new("") => dir.to_owned(),
+            _ => PathBuf::from("."),
+        };
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+/// Loads the source files and the packages they import, searched in the
+/// source roots.
+///
+/// `None` is returned if there was an error in a source file, its logs are
+/// printed.
+pub fn load_program(
+    roots: Vec<PathBuf>,
+    files: &[PathBuf],
+    output: LogOutput,
+    verbose: bool,
+) -> Result<Option<Program>, Box<dyn Error>> {
+    let program = load(Loader::new(roots, output), files, verbose)?;
+    Ok(Some(program).filter(|program| program.failed() == 0))
+}
+
+/// Loads the program like [`load_program`], and keeps the tokens of the
+/// source files, they are lexed once for both the parser and the tokens.
+pub fn load_program_with_tokens(
+    roots: Vec<PathBuf>,
+    files: &[PathBuf],
+    output: LogOutput,
+    verbose: bool,
+) -> Result<Option<Program>, Box<dyn Error>> {
+    let loader = Loader::new(roots, output).keeping_tokens();
+    let program = load(loader, files, verbose)?;
+    Ok(Some(program).filter(|program| program.failed() == 0))
+}
+
+/// Loads the source files and the packages they import, like
+/// [`load_program`], but the files with errors are only left out of the
+/// program.
+pub fn load_program_lenient(
+    roots: Vec<PathBuf>,
+    files: &[PathBuf],
+    output: LogOutput,
+    verbose: bool,
+) -> Result<Program, Box<dyn Error>> {
+    load(Loader::new(roots, output), files, verbose)
+}
+
+/// Loads the source files with the loader, the errors are printed.
+fn load(loader: Loader, files: &[PathBuf], verbose: bool) -> Result<Program, Box<dyn Error>> {
+    if verbose {
+        for file in files {
+            eprintln!("Loading `{}`...", file.display());
+        }
+    }
+    let program = loader.load(files)?;
+    program.errors().print();
+    Ok(program)
+}
+
+/// Type checks the packages of the program, in order, the logs of the
+/// packages with errors are printed.
+///
+/// `pointer_width` is the width of a pointer of the target, in bits. The
+/// result of a package is `None` if it contains an error, or if it imports a
+/// package that does, then it isn't checked.
+pub fn typeck_program(
+    program: &Program,
+    pointer_width: u32,
+    verbose: bool,
+) -> Vec<Option<(TypeckResults, LogContext<'_>)>> {
+    let mut checked: Vec<Option<(TypeckResults, LogContext)>> = Vec::new();
+
+    for (id, package) in program.packages.iter().enumerate() {
+        let imports: Option<Vec<ResolvedImport>> = package
+            .imports
+            .iter()
+            .map(|import| {
+                let (results, _) = checked[import.package].as_ref()?;
+                Some(ResolvedImport {
+                    name: import.name.clone(),
+                    item: import.item.clone(),
+                    interface: results.interface.clone(),
+                    span: import.span.clone(),
+                })
+            })
+            .collect();
+        let Some(imports) = imports else {
+            checked.push(None);
+            continue;
+        };
+
+        if verbose {
+            eprintln!("Type checking `{}`...", package.name());
+        }
+        let typeck = TypeChecker::new(program.lctx(id))
+            .with_pointer_width(pointer_width)
+            .with_imports(imports);
+        match typeck.check(&package.ast) {
+            FinalRes::Ok(results, lctx) => checked.push(Some((results, lctx))),
+            FinalRes::Err(logs) => {
+                logs.print();
+                checked.push(None);
+            }
+        }
+    }
+
+    checked
+}
+
+/// Type checks the program and generates the LLVM module of each of its
+/// packages, for a target whose pointers are `pointer_width` bits wide. The
+/// last package is the root of the program, with `entry_point` its `main` is
+/// the entry point of an executable.
+///
+/// `None` is returned if there was an error in a package.
+pub fn compile_program<'ctx>(
+    context: &'ctx Context,
+    program: &Program,
+    pointer_width: u32,
+    entry_point: bool,
+    verbose: bool,
+) -> Option<Vec<CompiledFile<'ctx>>> {
+    let checked: Option<Vec<_>> = typeck_program(program, pointer_width, verbose)
+        .into_iter()
+        .collect();
+
+    let mut compiled = Vec::new();
+    let root = program.packages.len().saturating_sub(1);
+    for (id, (package, (results, lctx))) in program.packages.iter().zip(checked?).enumerate() {
+        let module = gen_module(
+            context,
+            &package.ast,
+            results,
+            lctx,
+            pointer_width,
+            id == root,
+            entry_point && id == root,
+            verbose,
+        )?;
+
+        let mut extern_libs = Vec::new();
+        for decl in &package.ast.decls {
+            if let TopLvlDecl::Function { lib: Some(lib), .. } = &decl.decl {
+                if !extern_libs.contains(lib) {
+                    extern_libs.push(lib.clone());
+                }
+            }
+        }
+        compiled.push(CompiledFile {
+            module,
+            extern_libs,
+        });
+    }
+    Some(compiled)
+}
+
+/// Compiles the program, like [`compile_program`], and links the modules of
+/// its packages into one, the libraries of the packages are merged.
+pub fn compile_linked<'ctx>(
+    context: &'ctx Context,
+    program: &Program,
+    pointer_width: u32,
+    entry_point: bool,
+    verbose: bool,
+) -> Result<Option<CompiledFile<'ctx>>, Box<dyn Error>> {
+    let Some(compiled) = compile_program(context, program, pointer_width, entry_point, verbose)
+    else {
+        return Ok(None);
+    };
+
+    let mut compiled = compiled.into_iter();
+    let Some(mut linked) = compiled.next() else {
+        return Ok(None);
+    };
+    for (file, package) in compiled.zip(&program.packages[1..]) {
+        if let Err(err) = linked.module.link_in_module(file.module) {
+            return err!(fmt "Couldn't link `{}` with the other packages, {}", package.path.display(), err);
+        }
+        for lib in file.extern_libs {
+            if !linked.extern_libs.contains(&lib) {
+                linked.extern_libs.push(lib);
+            }
+        }
+    }
+    Ok(Some(linked))
+}
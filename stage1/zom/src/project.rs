@@ -0,0 +1,411 @@
+//! Zom projects, a directory with a `Zom.toml` manifest describing the
+//! package, where its source files are and what it depends on.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use zom_common::token::KEYWORDS;
+
+use crate::err;
+
+/// Name of the manifest file, at the root of a project.
+pub const MANIFEST_FILE: &str = "Zom.toml";
+
+/// Directory, in the project root, where the artifacts are written.
+pub const TARGET_DIR: &str = "target";
+
+/// File of the source directory defining the `main` function of a project.
+pub const ENTRY_FILE: &str = "main.zom";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub package: Package,
+    /// Other projects this one depends on, by name.
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    /// Directory containing the source files, relative to the project root.
+    #[serde(default = "Package::default_src")]
+    pub src: PathBuf,
+    #[serde(default)]
+    pub kind: OutputKind,
+}
+
+impl Package {
+    fn default_src() -> PathBuf {
+        PathBuf::from("src")
+    }
+}
+
+/// What building the project produces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputKind {
+    /// An executable, the project must define a `main` function.
+    #[default]
+    Bin,
+    /// A static library, an archive of object files.
+    Staticlib,
+    /// A single object file.
+    Object,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Dependency {
+    /// Path to the root of the dependency, relative to the project root.
+    pub path: PathBuf,
+}
+
+/// A project, loaded from its manifest.
+#[derive(Debug, Clone)]
+pub struct Project {
+    /// Directory containing the manifest.
+    pub root: PathBuf,
+    pub manifest: Manifest,
+}
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+impl Project {
+    /// Loads the project whose manifest is in `root`.
+    pub fn load(root: &Path) -> Result<Project> {
+        let path = root.join(MANIFEST_FILE);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) => return err!(fmt "Couldn't read `{}`, {}", path.display(), err),
+        };
+        let manifest: Manifest = match toml::from_str(&content) {
+            Ok(manifest) => manifest,
+            Err(err) => return err!(fmt "Invalid manifest `{}`, {}", path.display(), err),
+        };
+        if !is_valid_name(&manifest.package.name) {
+            return err!(fmt "Invalid package name `{}` in `{}`, it must be an identifier and not a keyword",
+                manifest.package.name,
+                path.display()
+            );
+        }
+
+        Ok(Project {
+            root: root.to_owned(),
+            manifest,
+        })
+    }
+
+    /// Finds the project containing the current directory, by looking for a
+    /// manifest in it and then in its parents.
+    pub fn find() -> Result<Project> {
+        let cwd = match env::current_dir() {
+            Ok(cwd) => cwd,
+            Err(err) => return err!(fmt "Couldn't get the current directory, {}", err),
+        };
+        match cwd
+            .ancestors()
+            .find(|dir| dir.join(MANIFEST_FILE).is_file())
+        {
+            Some(root) => Project::load(root),
+            None => {
+                err!(fmt "Couldn't find `{}` in `{}` or any parent directory, give a source file or create a project with `zom new`",
+                    MANIFEST_FILE,
+                    cwd.display()
+                )
+            }
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.manifest.package.name
+    }
+
+    /// Directory containing the source files.
+    pub fn src_dir(&self) -> PathBuf {
+        self.root.join(&self.manifest.package.src)
+    }
+
+    /// The `.zom` files of the source directory, the entry file is the last
+    /// one, so its package is the root of the program.
+    pub fn source_files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        collect_source_files(&self.src_dir(), &mut files)?;
+        if files.is_empty() {
+            return err!(fmt "No source file found in `{}`", self.src_dir().display());
+        }
+        let entry = self.src_dir().join(ENTRY_FILE);
+        if let Some(i) = files.iter().position(|file| *file == entry) {
+            let entry = files.remove(i);
+            files.push(entry);
+        }
+        Ok(files)
+    }
+
+    /// Loads the dependencies of the project and theirs, each one once, a
+    /// dependency comes before the projects depending on it.
+    pub fn dependencies(&self) -> Result<Vec<Project>> {
+        let mut deps = Vec::new();
+        self.collect_dependencies(&mut Vec::new(), &mut deps)?;
+        Ok(deps)
+    }
+
+    fn collect_dependencies(
+        &self,
+        stack: &mut Vec<PathBuf>,
+        deps: &mut Vec<Project>,
+    ) -> Result<()> {
+        stack.push(canonical(&self.root));
+        for (name, dep) in &self.manifest.dependencies {
+            let dep_project = Project::load(&self.root.join(&dep.path))?;
+            if dep_project.name() != name {
+                return err!(fmt "The dependency `{}` of `{}` is named `{}` in its manifest",
+                    name,
+                    self.name(),
+                    dep_project.name()
+                );
+            }
+            if dep_project.manifest.package.kind == OutputKind::Bin {
+                return err!(fmt "The dependency `{}` of `{}` is a binary, it can't be depended on",
+                    name,
+                    self.name()
+                );
+            }
+
+            let dep_root = canonical(&dep_project.root);
+            if stack.contains(&dep_root) {
+                return err!(fmt "Cyclic dependency, `{}` depends on `{}` which depends on it",
+                    self.name(),
+                    name
+                );
+            }
+            if deps.iter().any(|dep| canonical(&dep.root) == dep_root) {
+                continue;
+            }
+            dep_project.collect_dependencies(stack, deps)?;
+            deps.push(dep_project);
+        }
+        stack.pop();
+        Ok(())
+    }
+
+    /// The source files of the project and of all its dependencies.
+    pub fn all_source_files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for dep in self.dependencies()? {
+            files.extend(dep.source_files()?);
+        }
+        files.extend(self.source_files()?);
+        Ok(files)
+    }
+
+    /// Directories where the imported packages are searched, the source
+    /// directory of the project and then the ones of its dependencies.
+    pub fn source_roots(&self) -> Result<Vec<PathBuf>> {
+        let mut roots = vec![self.src_dir()];
+        for dep in self.dependencies()? {
+            roots.push(dep.src_dir());
+        }
+        Ok(roots)
+    }
+
+    /// Path to the artifact produced by building the project.
+    pub fn output_path(&self) -> PathBuf {
+        let name = self.name();
+        let file = match self.manifest.package.kind {
+            OutputKind::Bin => PathBuf::from(name).with_extension(env::consts::EXE_EXTENSION),
+            OutputKind::Staticlib => PathBuf::from(format!("lib{name}.a")),
+            OutputKind::Object => PathBuf::from(format!("{name}.o")),
+        };
+        self.root.join(TARGET_DIR).join(file)
+    }
+}
+
+/// Can the name be used as a package name, in a `package` clause? It must be
+/// an identifier, so not a keyword.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !KEYWORDS.contains(&name)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_owned())
+}
+
+/// Collects the `.zom` files of the directory and of its subdirectories,
+/// sorted so the output doesn't depend on the file system.
+pub fn collect_source_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => return err!(fmt "Couldn't read the directory `{}`, {}", dir.display(), err),
+    };
+    let mut paths = entries
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            collect_source_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "zom") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+
+    /// A directory in the temporary directory, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let dir = env::temp_dir().join(format!("zom-project-{name}-{}", process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn parse(manifest: &str) -> std::result::Result<Manifest, toml::de::Error> {
+        toml::from_str(manifest)
+    }
+
+    #[test]
+    fn minimal_manifest() {
+        let manifest = parse("[package]\nname = \"hello\"\nversion = \"0.1.0\"\n").unwrap();
+        assert_eq!(manifest.package.name, "hello");
+        assert_eq!(manifest.package.version, "0.1.0");
+        assert_eq!(manifest.package.src, PathBuf::from("src"));
+        assert_eq!(manifest.package.kind, OutputKind::Bin);
+        assert!(manifest.dependencies.is_empty());
+    }
+
+    #[test]
+    fn full_manifest() {
+        let manifest = parse(
+            "[package]\nname = \"lib\"\nversion = \"1.2.3\"\nsrc = \"source\"\nkind = \"staticlib\"\n\n\
+             [dependencies]\nmath = { path = \"../math\" }\n",
+        )
+        .unwrap();
+        assert_eq!(manifest.package.src, PathBuf::from("source"));
+        assert_eq!(manifest.package.kind, OutputKind::Staticlib);
+        assert_eq!(manifest.dependencies["math"].path, PathBuf::from("../math"));
+
+        let manifest =
+            parse("[package]\nname = \"obj\"\nversion = \"0.1.0\"\nkind = \"object\"\n").unwrap();
+        assert_eq!(manifest.package.kind, OutputKind::Object);
+    }
+
+    #[test]
+    fn invalid_manifests() {
+        // missing version
+        assert!(parse("[package]\nname = \"hello\"\n").is_err());
+        // unknown fields
+        assert!(
+            parse("[package]\nname = \"hello\"\nversion = \"0.1.0\"\nauthor = \"me\"\n").is_err()
+        );
+        assert!(parse("[package]\nname = \"hello\"\nversion = \"0.1.0\"\n[profile]\n").is_err());
+        assert!(parse(
+            "[package]\nname = \"hello\"\nversion = \"0.1.0\"\n[dependencies]\nmath = { git = \"x\" }\n"
+        )
+        .is_err());
+        // unknown kind
+        assert!(
+            parse("[package]\nname = \"hello\"\nversion = \"0.1.0\"\nkind = \"dylib\"\n").is_err()
+        );
+    }
+
+    #[test]
+    fn names() {
+        assert!(is_valid_name("hello"));
+        assert!(is_valid_name("_hello_2"));
+        assert!(is_valid_name("Hello"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("2hello"));
+        assert!(!is_valid_name("hello-world"));
+        assert!(!is_valid_name("héllo"));
+        assert!(!is_valid_name("fn"));
+        assert!(!is_valid_name("package"));
+        assert!(!is_valid_name("true"));
+    }
+
+    #[test]
+    fn load() {
+        let dir = TempDir::new("load");
+        fs::write(
+            dir.0.join(MANIFEST_FILE),
+            "[package]\nname = \"hello\"\nversion = \"0.1.0\"\nkind = \"object\"\n",
+        )
+        .unwrap();
+        let project = Project::load(&dir.0).unwrap();
+        assert_eq!(project.name(), "hello");
+        assert_eq!(project.src_dir(), dir.0.join("src"));
+        assert_eq!(
+            project.output_path(),
+            dir.0.join(TARGET_DIR).join("hello.o")
+        );
+    }
+
+    #[test]
+    fn load_keyword_name() {
+        let dir = TempDir::new("keyword");
+        fs::write(
+            dir.0.join(MANIFEST_FILE),
+            "[package]\nname = \"fn\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let err = Project::load(&dir.0).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid package name `fn`"));
+    }
+
+    #[test]
+    fn entry_file_last() {
+        let dir = TempDir::new("entry");
+        fs::write(
+            dir.0.join(MANIFEST_FILE),
+            "[package]\nname = \"hello\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let src = dir.0.join("src");
+        fs::create_dir_all(src.join("util")).unwrap();
+        for file in ["main.zom", "a.zom", "z.zom", "util/b.zom", "notes.txt"] {
+            fs::write(src.join(file), "").unwrap();
+        }
+
+        let files = Project::load(&dir.0).unwrap().source_files().unwrap();
+        assert_eq!(
+            files,
+            [
+                src.join("a.zom"),
+                src.join("util").join("b.zom"),
+                src.join("z.zom"),
+                src.join("main.zom"),
+            ]
+        );
+    }
+}
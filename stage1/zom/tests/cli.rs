@@ -0,0 +1,212 @@
+//! Running the `zom` binary, on files and projects written in a temporary
+//! directory.
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::{self, Command, Output},
+};
+
+use serde_json::{json, Value};
+
+/// A directory in the temporary directory, removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates the directory with the files, by path relative to it.
+    fn new(name: &str, files: &[(&str, &str)]) -> TempDir {
+        let dir = env::temp_dir().join(format!("zom-cli-{name}-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (path, content) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        TempDir(dir)
+    }
+
+    /// Runs `zom` with the arguments, in the directory.
+    fn zom(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_zom"))
+            .args(args)
+            .current_dir(&self.0)
+            .output()
+            .unwrap()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+/// The logs printed with `--message-format=json`, one per line.
+fn json_logs(output: &Output) -> Vec<Value> {
+    stdout(output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn check_json() {
+    let dir = TempDir::new(
+        "check-json",
+        &[(
+            "a.zom",
+            "package a\n\nfn main() void {\n    var x: i32 = true\n}\n",
+        )],
+    );
+    let output = dir.zom(&["check", "--message-format=json", "a.zom"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        json_logs(&output),
+        [json!({
+            "level": "error",
+            "message": "mismatched types",
+            "span": {
+                "file": "a.zom",
+                "byte_start": 45,
+                "byte_end": 49,
+                "line_start": 4,
+                "column_start": 18,
+                "line_end": 4,
+                "column_end": 22,
+            },
+            "cursor_message": "expected `i32`, found `bool`",
+            "notes": [],
+        })]
+    );
+    assert!(stderr(&output).contains("1 failed"));
+}
+
+#[test]
+fn check_json_notes() {
+    let dir = TempDir::new(
+        "check-json-notes",
+        &[(
+            "b.zom",
+            "package b\n\nfn main() void {\n    var x: i32 = (1 + 2;\n}\n",
+        )],
+    );
+    let output = dir.zom(&["--message-format=json", "check", "b.zom"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        json_logs(&output),
+        [json!({
+            "level": "error",
+            "message": "expected `)`, found `;`",
+            "span": {
+                "file": "b.zom",
+                "byte_start": 51,
+                "byte_end": 52,
+                "line_start": 4,
+                "column_start": 24,
+                "line_end": 4,
+                "column_end": 25,
+            },
+            "cursor_message": null,
+            "notes": [{
+                "level": "note",
+                "message": "opening parenthesis found here",
+                "span": {
+                    "file": "b.zom",
+                    "byte_start": 45,
+                    "byte_end": 46,
+                    "line_start": 4,
+                    "column_start": 18,
+                    "line_end": 4,
+                    "column_end": 19,
+                },
+            }],
+        })]
+    );
+}
+
+#[test]
+fn check_json_clean() {
+    let dir = TempDir::new(
+        "check-json-clean",
+        &[("c.zom", "package c\n\nfn main() i32 {\n    return 0\n}\n")],
+    );
+    let output = dir.zom(&["check", "--message-format=json", "c.zom"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn emit_to_stdout() {
+    let dir = TempDir::new(
+        "emit-stdout",
+        &[("d.zom", "package d\n\nfn main() i32 {\n    return 0\n}\n")],
+    );
+    let output = dir.zom(&["bobj", "--emit", "tokens,llvm-ir", "-o", "-", "d.zom"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let out = stdout(&output);
+    assert!(out.starts_with("0..7"));
+    assert!(out.contains("Ident(\"main\")"));
+    assert!(out.contains("define i32 @main()"));
+    assert!(!out.contains("Wrote result"));
+    // nothing is written next to the source file
+    assert!(!dir.0.join("d.ll").exists());
+    assert!(!dir.0.join("d.tokens").exists());
+}
+
+#[test]
+fn emit_to_stdout_logs_on_stderr() {
+    let dir = TempDir::new(
+        "emit-stdout-logs",
+        &[(
+            "e.zom",
+            "package e\n\nfn main() i32 {\n    return true\n}\n",
+        )],
+    );
+    let output = dir.zom(&["bobj", "--emit", "tokens,llvm-ir", "-o", "-", "e.zom"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with("0..7"));
+    assert!(!stdout(&output).contains("mismatched types"));
+    assert!(stderr(&output).contains("mismatched types"));
+}
+
+#[test]
+fn imported_main() {
+    let dir = TempDir::new(
+        "imported-main",
+        &[
+            (
+                "Zom.toml",
+                "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+            ),
+            (
+                "src/util.zom",
+                "package util\n\npub fn main() i32 {\n    return 5\n}\n",
+            ),
+            (
+                "src/main.zom",
+                "package app\n\nimport util\n\nfn main() i32 {\n    return util.main() + 2\n}\n",
+            ),
+        ],
+    );
+    let output = dir.zom(&["run"]);
+    assert_eq!(output.status.code(), Some(7), "{}", stderr(&output));
+}
+
+#[test]
+fn new_keyword_name() {
+    let dir = TempDir::new("new-keyword", &[]);
+    fs::create_dir_all(&dir.0).unwrap();
+    let output = dir.zom(&["new", "fn"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Invalid package name `fn`"));
+    assert!(!dir.0.join("fn").exists());
+}
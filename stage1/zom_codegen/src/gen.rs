@@ -1,44 +1,226 @@
 //! Module related to the transformation of the AST to a LLVM IR.
+//!
+//! The source file must have been type checked before, the code generation
+//! doesn't report errors in the source code.
 
-use std::{borrow::Borrow, collections::HashMap};
+use std::collections::{HashMap, HashSet};
 
 use inkwell::{
+    basic_block::BasicBlock,
     builder::Builder,
     context::Context,
-    module::Module,
-    passes::PassManager,
-    types::BasicMetadataTypeEnum,
-    values::{BasicMetadataValueEnum, FunctionValue, IntValue, PointerValue},
-    IntPredicate,
+    module::{Linkage, Module},
+    types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType, StructType},
+    values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue},
+    AddressSpace, FloatPredicate, IntPredicate,
 };
 
-use zom_fe::parser::{
-    expr::Expression,
-    function::{Function, Prototype},
-    ASTNode,
+use zom_errors::prelude::*;
+use zom_parser::{
+    block::Block,
+    expr::{
+        ArmBody, BinOperation, Expr, Expression, ExpressionList, FieldInit, MatchArm,
+        UnaryOperation,
+    },
+    impl_decl::ImplFn,
+    pattern::{Pat, Pattern},
+    source_file::SourceFile,
+    stmt::{ForIter, ForRange, Statement, Stmt},
+    toplvldecl::{Prototype, TopLvlDecl},
+    types::PrimitiveTy,
+    var_decl::{VarDecl, VarType},
 };
+use zom_sema::{
+    ty::Ty,
+    typeck::{
+        is_place_expr, method_symbol, Autoref, EnumDef, FnSig, TypeckResults, Var, VariantDef,
+    },
+};
+
+/// Symbol of the Zom `main` function when a C-ABI `main` entry point is
+/// generated, the entry point calls it.
+pub const ZOM_MAIN_SYMBOL: &str = "__zom_main";
+
+/// A LLVM value and the Zom type it has.
+#[derive(Debug, Clone)]
+pub struct Value<'ctx> {
+    pub val: BasicValueEnum<'ctx>,
+    pub ty: Ty,
+}
+
+/// A location in memory, like a variable, that can be read and maybe written.
+#[derive(Debug, Clone)]
+struct Place<'ctx> {
+    ptr: PointerValue<'ctx>,
+    ty: Ty,
+}
+
+/// A declared function and its signature.
+#[derive(Debug, Clone)]
+struct Function<'ctx> {
+    value: FunctionValue<'ctx>,
+    args: Vec<Ty>,
+    ret_ty: Ty,
+}
 
-use zom_common::token::*;
+/// Where the control flow goes after a `break` or a `continue`.
+struct JumpTarget<'ctx> {
+    label: Option<String>,
+    break_bb: BasicBlock<'ctx>,
+    /// `None` if the target is a labeled block, not a loop.
+    continue_bb: Option<BasicBlock<'ctx>>,
+}
 
-/// Defines the `Expression` compiler.
+/// Generates the LLVM IR of a type checked source file.
 pub struct CodeGen<'a, 'ctx> {
-    pub context: &'ctx Context,
-    pub builder: &'a Builder<'ctx>,
-    pub fpm: &'a PassManager<FunctionValue<'ctx>>,
-    pub module: &'a Module<'ctx>,
-    pub function: &'a Function,
+    context: &'ctx Context,
+    module: Module<'ctx>,
+    builder: Builder<'ctx>,
+    lctx: LogContext<'a>,
+    results: TypeckResults,
+
+    functions: HashMap<String, Function<'ctx>>,
+    globals: HashMap<String, Place<'ctx>>,
+    /// Local variables, the last scope is the innermost.
+    scopes: Vec<HashMap<String, Place<'ctx>>>,
+    jump_targets: Vec<JumpTarget<'ctx>>,
 
-    variables: HashMap<String, PointerValue<'ctx>>,
     fn_value_opt: Option<FunctionValue<'ctx>>,
-}
 
-pub type GeneratedCode<'ctx> = Result<Vec<FunctionValue<'ctx>>, &'static str>;
+    /// Width of a pointer of the target, in bits.
+    pointer_width: u32,
+
+    /// Is it the root package of the program? Its `main` is the `main` of
+    /// the program, the one of the other packages is mangled.
+    root: bool,
+    /// Generate a C-ABI `main` calling the Zom `main`?
+    entry_point: bool,
+}
 
 impl<'a, 'ctx> CodeGen<'a, 'ctx> {
-    /// Gets a defined function given its name.
-    #[inline]
-    fn get_function(&self, name: &str) -> Option<FunctionValue<'ctx>> {
-        self.module.get_function(name)
+    /// Creates the code generator of a source file, given what the type
+    /// checker found out about it.
+    pub fn new(
+        context: &'ctx Context,
+        module_name: &str,
+        lctx: LogContext<'a>,
+        results: TypeckResults,
+    ) -> Self {
+        CodeGen {
+            context,
+            module: context.create_module(module_name),
+            builder: context.create_builder(),
+            lctx,
+            results,
+            functions: HashMap::new(),
+            globals: HashMap::new(),
+            scopes: Vec::new(),
+            jump_targets: Vec::new(),
+            fn_value_opt: None,
+            pointer_width: usize::BITS,
+            root: false,
+            entry_point: false,
+        }
+    }
+
+    /// Sets the width of a pointer of the target, in bits, it's the width of
+    /// `usize` and `isize`. By default it's the one of the host.
+    pub fn with_pointer_width(mut self, pointer_width: u32) -> Self {
+        self.pointer_width = pointer_width;
+        self
+    }
+
+    /// Marks the package as the root of the program, its `main` keeps the
+    /// `main` symbol.
+    pub fn with_root(mut self) -> Self {
+        self.root = true;
+        self
+    }
+
+    /// Generates a C-ABI `main` entry point, calling the Zom `main`, if the
+    /// source file defines one. Used for the root package, when the module is
+    /// linked into an executable.
+    pub fn with_entry_point(mut self) -> Self {
+        self.root = true;
+        self.entry_point = true;
+        self
+    }
+
+    /// Generates the module of the given source file. Every function is
+    /// declared before any body is generated, so a function can be called
+    /// before its definition.
+    pub fn gen(mut self, source: &SourceFile) -> FinalRes<'a, Module<'ctx>> {
+        // the imported items, like `import std.fmt.println`, are used by
+        // their name
+        for import in self.results.imports.clone() {
+            let Some(item) = &import.item else {
+                continue;
+            };
+            if let Some(sig) = import.interface.functions.get(item) {
+                let symbol = import.interface.function_symbol(item);
+                let function = self.imported_function(&symbol, &sig.def);
+                self.functions.insert(import.name.clone(), function);
+            } else if let Some(var) = import.interface.globals.get(item) {
+                let symbol = import.interface.global_symbol(item);
+                let place = self.imported_global(&symbol, &var.def);
+                self.globals.insert(import.name.clone(), place);
+            }
+        }
+
+        let mut bodies = Vec::new();
+        let mut has_main = false;
+        for decl in &source.decls {
+            match &decl.decl {
+                TopLvlDecl::Function { lib, proto, body } => {
+                    let is_main = self.root && proto.name == "main" && body.is_some();
+                    let symbol = if is_main {
+                        has_main = true;
+                        let symbol = if self.entry_point {
+                            ZOM_MAIN_SYMBOL
+                        } else {
+                            "main"
+                        };
+                        symbol.to_owned()
+                    } else {
+                        self.results.interface.function_symbol(&proto.name)
+                    };
+
+                    // without an entry point, the `main` of the root package
+                    // is the one of the C program
+                    let exported = decl.public || lib.is_some() || (is_main && !self.entry_point);
+                    self.declare_function(exported, proto, &symbol);
+                    let function = &self.functions[&proto.name];
+                    bodies.extend(body.as_ref().map(|body| (function.clone(), proto, body)));
+                }
+                TopLvlDecl::Impl(impl_decl) => {
+                    let ty = self.results.ty(&impl_decl.ty).clone();
+                    for f in &impl_decl.fns {
+                        let function = self.declare_method(&ty, f);
+                        bodies.push((function, &f.proto, &f.body));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for decl in &source.decls {
+            if let TopLvlDecl::GlobalVarDecl(var_decl) = &decl.decl {
+                self.compile_global(decl.public, var_decl);
+            }
+        }
+
+        for (function, proto, body) in bodies {
+            self.compile_fn_body(function, proto, body);
+        }
+
+        if has_main && self.entry_point {
+            self.gen_entry_point();
+        }
+
+        if self.lctx.failed() {
+            return FinalRes::Err(self.lctx.stream());
+        }
+        FinalRes::Ok(self.module, self.lctx)
     }
 
     /// Returns the `FunctionValue` representing the function being compiled.
@@ -50,8 +232,204 @@ impl<'a, 'ctx> CodeGen<'a, 'ctx> {
         self.fn_value_opt.unwrap()
     }
 
+    /// Returns the basic block where the builder is positioned.
+    #[inline]
+    fn current_block(&self) -> BasicBlock<'ctx> {
+        self.builder.get_insert_block().unwrap()
+    }
+
+    /// Returns the type of the expression, found by the type checker.
+    #[inline]
+    fn expr_ty(&self, expr: &Expression) -> Ty {
+        self.results.expr_ty(expr).clone()
+    }
+
+    /// Is the block either the entry block of the function or the target of a
+    /// branch?
+    fn is_reachable(&self, bb: BasicBlock<'ctx>) -> bool {
+        bb.get_first_use().is_some() || self.fn_value().get_first_basic_block() == Some(bb)
+    }
+
+    /// Terminates the current block with a branch to `bb`, if it isn't already
+    /// terminated.
+    fn branch_to(&self, bb: BasicBlock<'ctx>) {
+        let current = self.current_block();
+        if current.get_terminator().is_some() {
+            return;
+        }
+        if self.is_reachable(current) {
+            self.builder.build_unconditional_branch(bb);
+        } else {
+            self.builder.build_unreachable();
+        }
+    }
+
+    /// Maps a Zom type to the corresponding LLVM type, `None` for `void`.
+    fn basic_type(&self, ty: &Ty) -> Option<BasicTypeEnum<'ctx>> {
+        use PrimitiveTy::*;
+        Some(match ty {
+            Ty::Prim(Void) => return None,
+            Ty::Prim(Bool) => self.context.bool_type().into(),
+            Ty::Prim(F16) => self.context.f16_type().into(),
+            Ty::Prim(F32) => self.context.f32_type().into(),
+            Ty::Prim(F64) => self.context.f64_type().into(),
+            Ty::Prim(F128) => self.context.f128_type().into(),
+            Ty::Prim(_) => self
+                .context
+                .custom_width_int_type(ty.int_bits(self.pointer_width).unwrap())
+                .into(),
+            Ty::Pointer { pointee, .. } => match self.basic_type(pointee) {
+                Some(pointee) => pointee.ptr_type(AddressSpace::default()).into(),
+                None => self
+                    .context
+                    .i8_type()
+                    .ptr_type(AddressSpace::default())
+                    .into(),
+            },
+            Ty::Array { elem, len } => self.value_type(elem).array_type(*len as u32).into(),
+            Ty::Slice { elem, .. } => self.slice_type(elem).into(),
+            Ty::Struct { .. } => self.struct_type(ty).into(),
+            Ty::Enum { .. } => self.enum_type(ty),
+        })
+    }
+
+    /// Returns the LLVM type of a slice, `{ ptr, len }`, a pointer to its
+    /// first element and the number of elements.
+    fn slice_type(&self, elem: &Ty) -> StructType<'ctx> {
+        let ptr = self.value_type(elem).ptr_type(AddressSpace::default());
+        let len = self.value_type(&Ty::USIZE);
+        self.context.struct_type(&[ptr.into(), len], false)
+    }
+
+    /// Returns the LLVM type of an enum.
+    ///
+    /// An enum without payloads is only its discriminant, an integer of its
+    /// representation, like a C enum. Otherwise it's a named struct, like a C
+    /// struct of the tag and of the union of the payloads: `{ tag, storage }`
+    /// where the storage is as big and as aligned as the biggest and the most
+    /// aligned payload.
+    fn enum_type(&self, ty: &Ty) -> BasicTypeEnum<'ctx> {
+        let Ty::Enum { package, name } = ty else {
+            unreachable!("`{ty}` isn't an enum")
+        };
+        let def = self.results.enum_def(ty);
+        let tag = self.value_type(&def.repr);
+        if !def.has_payload() {
+            return tag;
+        }
+        let symbol = format!("{package}.{name}");
+        if let Some(enum_type) = self.context.get_struct_type(&symbol) {
+            return enum_type.into();
+        }
+
+        let enum_type = self.context.opaque_struct_type(&symbol);
+        let (size, align) = self.union_layout(def);
+        let storage = self
+            .context
+            .custom_width_int_type(align as u32 * 8)
+            .array_type(size.div_ceil(align) as u32);
+        enum_type.set_body(&[tag, storage.into()], false);
+        enum_type.into()
+    }
+
+    /// Returns the LLVM struct type of the payload of a variant.
+    fn payload_type(&self, variant: &VariantDef) -> StructType<'ctx> {
+        let fields: Vec<BasicTypeEnum> = variant
+            .fields
+            .iter()
+            .map(|ty| self.value_type(ty))
+            .collect();
+        self.context.struct_type(&fields, false)
+    }
+
+    /// Returns the size and the alignment, in bytes, of a value of the type,
+    /// laid out like a C compiler of the target would.
+    fn layout(&self, ty: &Ty) -> (u64, u64) {
+        use PrimitiveTy::*;
+        match ty {
+            Ty::Prim(Void) => (0, 1),
+            Ty::Prim(Bool) => (1, 1),
+            Ty::Prim(F16) => (2, 2),
+            Ty::Prim(F32) => (4, 4),
+            Ty::Prim(F64) => (8, 8),
+            Ty::Prim(F128) => (16, 16),
+            Ty::Prim(_) => {
+                let bytes = ty.int_bits(self.pointer_width).unwrap() as u64 / 8;
+                (bytes, bytes)
+            }
+            Ty::Pointer { .. } => {
+                let bytes = self.pointer_width as u64 / 8;
+                (bytes, bytes)
+            }
+            Ty::Array { elem, len } => {
+                let (size, align) = self.layout(elem);
+                (size * len, align)
+            }
+            Ty::Slice { .. } => {
+                let bytes = self.pointer_width as u64 / 8;
+                (2 * bytes, bytes)
+            }
+            Ty::Struct { .. } => {
+                let fields = &self.results.struct_def(ty).fields;
+                struct_layout(fields.iter().map(|field| self.layout(&field.ty)))
+            }
+            Ty::Enum { .. } => {
+                let def = self.results.enum_def(ty);
+                if def.has_payload() {
+                    struct_layout([self.layout(&def.repr), self.union_layout(def)])
+                } else {
+                    self.layout(&def.repr)
+                }
+            }
+        }
+    }
+
+    /// Returns the size and the alignment, in bytes, of the union of the
+    /// payloads of the enum.
+    fn union_layout(&self, def: &EnumDef) -> (u64, u64) {
+        def.variants
+            .iter()
+            .map(|variant| struct_layout(variant.fields.iter().map(|ty| self.layout(ty))))
+            .fold((0, 1), |(size, align), (variant_size, variant_align)| {
+                (size.max(variant_size), align.max(variant_align))
+            })
+    }
+
+    /// Returns the named LLVM struct type of a struct, its fields are laid out
+    /// like the ones of a C struct.
+    ///
+    /// The type is named `package.Name`, it's created with an opaque body
+    /// before its fields, so a field can be a pointer to the struct itself.
+    fn struct_type(&self, ty: &Ty) -> StructType<'ctx> {
+        let Ty::Struct { package, name } = ty else {
+            unreachable!("`{ty}` isn't a struct")
+        };
+        let symbol = format!("{package}.{name}");
+        if let Some(struct_type) = self.context.get_struct_type(&symbol) {
+            return struct_type;
+        }
+
+        let struct_type = self.context.opaque_struct_type(&symbol);
+        let fields: Vec<BasicTypeEnum> = self
+            .results
+            .struct_def(ty)
+            .fields
+            .iter()
+            .map(|field| self.value_type(&field.ty))
+            .collect();
+        struct_type.set_body(&fields, false);
+        struct_type
+    }
+
+    /// Same as `basic_type` but for the type of a value, that the type checker
+    /// ensured isn't `void`.
+    fn value_type(&self, ty: &Ty) -> BasicTypeEnum<'ctx> {
+        self.basic_type(ty)
+            .expect("`void` as the type of a value should be a type error")
+    }
+
     /// Creates a new stack allocation instruction in the entry block of the function.
-    fn create_entry_block_alloca(&self, name: &str) -> PointerValue<'ctx> {
+    fn create_entry_block_alloca(&self, ty: BasicTypeEnum<'ctx>, name: &str) -> PointerValue<'ctx> {
         let builder = self.context.create_builder();
 
         let entry = self.fn_value().get_first_basic_block().unwrap();
@@ -61,255 +439,1688 @@ impl<'a, 'ctx> CodeGen<'a, 'ctx> {
             None => builder.position_at_end(entry),
         }
 
-        builder.build_alloca(self.context.f64_type(), name)
+        builder.build_alloca(ty, name)
     }
 
-    /// Compiles the specified `Expr` into an LLVM `FloatValue`.
-    fn compile_expr(&mut self, expr: &Expression) -> Result<IntValue<'ctx>, &'static str> {
-        match expr {
-            Expression::LiteralExpr(nb) => Ok(self.context.i32_type().const_int(*nb as u64, true)),
+    /// Declares a new local variable in the innermost scope and returns a
+    /// pointer to its storage.
+    fn declare_local(&mut self, name: &str, ty: Ty) -> PointerValue<'ctx> {
+        let ptr = self.create_entry_block_alloca(self.value_type(&ty), name);
 
-            Expression::VariableExpr(ref name) => match self.variables.get(name.as_str()) {
-                Some(var) => Ok(self
-                    .builder
-                    .build_load(self.context.i32_type(), *var, name.as_str())
-                    .into_int_value()),
-                None => Err("Could not find a matching variable."),
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_owned(), Place { ptr, ty });
+        ptr
+    }
+
+    /// Looks for a local variable and then for a global one.
+    fn get_variable(&self, name: &str) -> Place<'ctx> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .or_else(|| self.globals.get(name))
+            .cloned()
+            .expect("an undeclared variable should be a type error")
+    }
+
+    /// Creates a private global containing the null terminated string and
+    /// returns a pointer to its first byte.
+    fn const_str(&self, s: &str) -> PointerValue<'ctx> {
+        let value = self.context.const_string(s.as_bytes(), true);
+        // `.str` can't be the name of a Zom item, so it can't be the symbol of
+        // an imported global
+        let global = self.module.add_global(value.get_type(), None, ".str");
+        global.set_linkage(Linkage::Private);
+        global.set_constant(true);
+        global.set_unnamed_addr(true);
+        global.set_initializer(&value);
+
+        global
+            .as_pointer_value()
+            .const_cast(self.context.i8_type().ptr_type(AddressSpace::default()))
+    }
+
+    /// Declares the function, with its signature, in the module under the
+    /// given symbol name.
+    fn declare_function(&mut self, exported: bool, proto: &Prototype, symbol: &str) {
+        let args: Vec<Ty> = proto
+            .args
+            .iter()
+            .map(|arg| self.results.ty(&arg.ty).clone())
+            .collect();
+        let ret_ty = self.results.ty(&proto.ret_ty).clone();
+
+        let fn_type = self.fn_type(&args, &ret_ty);
+        let linkage = if exported {
+            None
+        } else {
+            Some(Linkage::Internal)
+        };
+        let value = self.module.add_function(symbol, fn_type, linkage);
+
+        // set arguments names
+        for (param, arg) in value.get_param_iter().zip(&proto.args) {
+            param.set_name(&arg.name);
+        }
+
+        self.functions.insert(
+            proto.name.clone(),
+            Function {
+                value,
+                args,
+                ret_ty,
             },
+        );
+    }
 
-            Expression::BinaryExpr {
-                op,
-                lhs: ref left,
-                rhs: ref right,
-            } => {
-                if op == &"=".to_owned() {
-                    // handle assignement
-                    let var_name = match *left.borrow() {
-                        Expression::VariableExpr(ref var_name) => var_name,
-                        _ => {
-                            return Err("Expected variable as left-hand operator of assignement.");
-                        }
-                    };
+    /// Declares a function of an `impl` block of the type in the module, under
+    /// its mangled symbol.
+    fn declare_method(&mut self, ty: &Ty, f: &ImplFn) -> Function<'ctx> {
+        let proto = &f.proto;
+        let sig = self.results.methods[ty][&proto.name].def.sig.clone();
 
-                    let var_val = self.compile_expr(right)?;
-                    let var = self
-                        .variables
-                        .get(var_name.as_str())
-                        .ok_or("Undefined variable.")?;
+        let fn_type = self.fn_type(&sig.args, &sig.ret_ty);
+        let linkage = if f.public {
+            None
+        } else {
+            Some(Linkage::Internal)
+        };
+        let symbol = method_symbol(ty, &proto.name);
+        let value = self.module.add_function(&symbol, fn_type, linkage);
 
-                    self.builder.build_store(*var, var_val);
+        for (param, name) in value.get_param_iter().zip(proto.arg_names()) {
+            param.set_name(name);
+        }
 
-                    Ok(var_val)
-                } else {
-                    let lhs = self.compile_expr(left)?;
-                    let rhs = self.compile_expr(right)?;
-
-                    match op.as_str() {
-                        OP_PLUS => Ok(self.builder.build_int_add(lhs, rhs, "tmpadd")),
-                        OP_MINUS => Ok(self.builder.build_int_sub(lhs, rhs, "tmpsub")),
-                        OP_MUL => Ok(self.builder.build_int_mul(lhs, rhs, "tmpmul")),
-                        OP_DIV => Ok(self.builder.build_int_signed_div(lhs, rhs, "tmpdiv")),
-                        OP_COMP_LT => Ok({
-                            let cmp = self.builder.build_int_compare(
-                                IntPredicate::ULT,
-                                lhs,
-                                rhs,
-                                "tmpcmp",
-                            );
-
-                            self.builder
-                                .build_int_cast(cmp, self.context.i32_type(), "tmpbool")
-                        }),
-                        OP_COMP_GT => Ok({
-                            let cmp = self.builder.build_int_compare(
-                                IntPredicate::ULT,
-                                rhs,
-                                lhs,
-                                "tmpcmp",
-                            );
-
-                            self.builder
-                                .build_int_cast(cmp, self.context.i32_type(), "tmpbool")
-                        }),
-
-                        custom => {
-                            let mut name = String::from("binary");
-
-                            name.push_str(custom);
-
-                            match self.get_function(name.as_str()) {
-                                Some(fun) => {
-                                    match self
-                                        .builder
-                                        .build_call(fun, &[lhs.into(), rhs.into()], "tmpbin")
-                                        .try_as_basic_value()
-                                        .left()
-                                    {
-                                        Some(value) => Ok(value.into_int_value()),
-                                        None => Err("Invalid call produced."),
-                                    }
-                                }
-
-                                None => Err("Undefined binary operator."),
-                            }
-                        }
-                    }
+        Function {
+            value,
+            args: sig.args,
+            ret_ty: sig.ret_ty,
+        }
+    }
+
+    /// Returns a function of an `impl` block of the type, the ones of the
+    /// imported packages are declared once.
+    fn method(&mut self, ty: &Ty, name: &str) -> Function<'ctx> {
+        let sig = self.results.methods[ty][name].def.sig.clone();
+        let symbol = method_symbol(ty, name);
+        let value = match self.module.get_function(&symbol) {
+            Some(value) => value,
+            None => {
+                let fn_type = self.fn_type(&sig.args, &sig.ret_ty);
+                self.module.add_function(&symbol, fn_type, None)
+            }
+        };
+        Function {
+            value,
+            args: sig.args,
+            ret_ty: sig.ret_ty,
+        }
+    }
+
+    fn fn_type(&self, args: &[Ty], ret_ty: &Ty) -> FunctionType<'ctx> {
+        let params: Vec<BasicMetadataTypeEnum> =
+            args.iter().map(|ty| self.value_type(ty).into()).collect();
+
+        match self.basic_type(ret_ty) {
+            Some(ty) => ty.fn_type(&params, false),
+            None => self.context.void_type().fn_type(&params, false),
+        }
+    }
+
+    /// Declares a function of an imported package under its symbol, once.
+    fn imported_function(&mut self, symbol: &str, sig: &FnSig) -> Function<'ctx> {
+        let value = match self.module.get_function(symbol) {
+            Some(value) => value,
+            None => {
+                let fn_type = self.fn_type(&sig.args, &sig.ret_ty);
+                self.module.add_function(symbol, fn_type, None)
+            }
+        };
+        Function {
+            value,
+            args: sig.args.clone(),
+            ret_ty: sig.ret_ty.clone(),
+        }
+    }
+
+    /// Declares a global of an imported package under its symbol, once.
+    fn imported_global(&mut self, symbol: &str, var: &Var) -> Place<'ctx> {
+        let global = match self.module.get_global(symbol) {
+            Some(global) => global,
+            None => {
+                let global = self
+                    .module
+                    .add_global(self.value_type(&var.ty), None, symbol);
+                global.set_constant(!var.mutable);
+                global
+            }
+        };
+        Place {
+            ptr: global.as_pointer_value(),
+            ty: var.ty.clone(),
+        }
+    }
+
+    /// Returns the global if the expression is a global of an imported
+    /// package, like `math.pi`.
+    fn package_global(&mut self, expr: &Expression) -> Option<Place<'ctx>> {
+        let import = self.results.package_item(expr)?;
+        let Expr::MemberAccessExpr { member_name, .. } = &expr.expr else {
+            unreachable!("an item of a package is a member access")
+        };
+        let var = import.interface.globals[member_name].def.clone();
+        let symbol = import.interface.global_symbol(member_name);
+        Some(self.imported_global(&symbol, &var))
+    }
+
+    /// Generates `fn main(argc: i32, argv: **u8) i32` with the C calling
+    /// convention, it calls the Zom `main` and returns its exit code.
+    ///
+    /// The Zom `main` takes no arguments or the program arguments,
+    /// `(argc: i32, argv: **u8)`, and returns `void`, 0 is the exit code then,
+    /// or an integer truncated or extended to an `i32`. The type checker
+    /// ensured its signature is one of those.
+    fn gen_entry_point(&mut self) {
+        let main = self.functions["main"].clone();
+
+        let i32_type = self.context.i32_type();
+        let argv_type = self
+            .context
+            .i8_type()
+            .ptr_type(AddressSpace::default())
+            .ptr_type(AddressSpace::default());
+        let fn_type = i32_type.fn_type(&[i32_type.into(), argv_type.into()], false);
+        let entry_point = self.module.add_function("main", fn_type, None);
+
+        let argc = entry_point.get_nth_param(0).unwrap();
+        argc.set_name("argc");
+        let argv = entry_point.get_nth_param(1).unwrap();
+        argv.set_name("argv");
+
+        let entry = self.context.append_basic_block(entry_point, "entry");
+        self.builder.position_at_end(entry);
+
+        let args: &[BasicMetadataValueEnum] = if main.args.is_empty() {
+            &[]
+        } else {
+            &[argc.into(), argv.into()]
+        };
+        let call = self.builder.build_call(main.value, args, "");
+        let code = match call.try_as_basic_value().left() {
+            Some(ret) => self.builder.build_int_cast_sign_flag(
+                ret.into_int_value(),
+                i32_type,
+                main.ret_ty.is_signed(),
+                "exitcode",
+            ),
+            None => i32_type.const_zero(),
+        };
+        self.builder.build_return(Some(&code));
+    }
+
+    /// Compiles the body of an already declared function.
+    fn compile_fn_body(&mut self, function: Function<'ctx>, proto: &Prototype, body: &Block) {
+        let entry = self.context.append_basic_block(function.value, "entry");
+        self.builder.position_at_end(entry);
+
+        self.fn_value_opt = Some(function.value);
+
+        // arguments are stored on the stack, so they can be assigned
+        self.scopes.push(HashMap::new());
+        for ((param, name), ty) in function
+            .value
+            .get_param_iter()
+            .zip(proto.arg_names())
+            .zip(function.args)
+        {
+            let alloca = self.declare_local(name, ty);
+            self.builder.build_store(alloca, param);
+        }
+
+        self.compile_block(body);
+        self.scopes.pop();
+
+        // the type checker ensured a non-void function cannot reach its end
+        if self.current_block().get_terminator().is_none() {
+            if function.ret_ty.is_void() {
+                self.builder.build_return(None);
+            } else {
+                self.builder.build_unreachable();
+            }
+        }
+
+        if !function.value.verify(true) {
+            self.lctx.push(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!(
+                    "internal compiler error: invalid LLVM IR generated for function `{}`",
+                    proto.name
+                )
+                .into(),
+                cursor_msg: Some("please open an issue on the Zom repository".into()),
+                location: proto.span.clone(),
+            });
+        }
+        self.fn_value_opt = None;
+    }
+
+    /// Compiles a global variable, the type checker ensured its initializer is
+    /// a literal.
+    fn compile_global(&mut self, exported: bool, decl: &VarDecl) {
+        let value = decl.expr.as_ref().map(|expr| self.compile_expr(expr));
+        let ty = match (&decl.ty, &value) {
+            (Some(ty), _) => self.results.ty(ty).clone(),
+            (None, Some(value)) => value.ty.clone(),
+            (None, None) => unreachable!("a global without type should be a type error"),
+        };
+        let llvm_ty = self.value_type(&ty);
+        let is_const = matches!(decl.var_type, VarType::ConstVar);
+
+        let symbol = self.results.interface.global_symbol(&decl.name);
+        let global = self.module.add_global(llvm_ty, None, &symbol);
+        match value {
+            Some(value) => global.set_initializer(&value.val),
+            None => global.set_initializer(&llvm_ty.const_zero()),
+        }
+        global.set_constant(is_const);
+        if !exported {
+            global.set_linkage(Linkage::Internal);
+        }
+
+        self.globals.insert(
+            decl.name.clone(),
+            Place {
+                ptr: global.as_pointer_value(),
+                ty,
+            },
+        );
+    }
+
+    /// Compiles every statement of the block in a new scope.
+    fn compile_block(&mut self, block: &Block) {
+        self.scopes.push(HashMap::new());
+        for stmt in &block.stmts {
+            // the previous statement was a return, break or continue, the
+            // following statements are unreachable but are still compiled.
+            if self.current_block().get_terminator().is_some() {
+                let dead = self
+                    .context
+                    .append_basic_block(self.fn_value(), "unreachable");
+                self.builder.position_at_end(dead);
+            }
+
+            self.compile_stmt(stmt);
+        }
+        self.scopes.pop();
+    }
+
+    /// Compiles a statement nested in another one, like the body of an if
+    /// statement, in its own scope.
+    fn compile_nested_stmt(&mut self, stmt: &Statement) {
+        self.scopes.push(HashMap::new());
+        self.compile_stmt(stmt);
+        self.scopes.pop();
+    }
+
+    fn compile_stmt(&mut self, stmt: &Statement) {
+        match &stmt.stmt {
+            Stmt::ExprStmt(expr) => match &expr.expr {
+                // a call statement may call a function returning void.
+                Expr::CallExpr { fn_op, args } => {
+                    self.compile_call(fn_op, args);
+                }
+                // so does a match statement
+                Expr::MatchExpr { scrutinee, arms } => {
+                    self.compile_match(scrutinee, arms, &self.expr_ty(expr));
+                }
+                _ => {
+                    self.compile_expr(expr);
+                }
+            },
+            Stmt::IfElseStmt {
+                predicate,
+                stmt_true,
+                stmt_false,
+            } => self.compile_if_else_stmt(predicate, stmt_true, stmt_false.as_deref()),
+            Stmt::BlockStmt { label, block } => self.compile_block_stmt(label.as_ref(), block),
+            Stmt::ReturnStmt(expr) => {
+                let value = expr.as_ref().map(|expr| self.compile_expr(expr));
+                match value {
+                    Some(value) => self.builder.build_return(Some(&value.val)),
+                    None => self.builder.build_return(None),
+                };
+            }
+            Stmt::WhileStmt {
+                label,
+                ctrling_expr,
+                loop_body,
+            } => self.compile_while_stmt(label.as_ref(), ctrling_expr, loop_body),
+            Stmt::ForStmt {
+                label,
+                index,
+                var,
+                iter,
+                loop_body,
+            } => match iter {
+                ForIter::Range(range) => {
+                    self.compile_for_stmt(label.as_ref(), var, range, loop_body)
                 }
+                ForIter::Elems(expr) => self.compile_for_elems_stmt(
+                    label.as_ref(),
+                    index.as_deref(),
+                    var,
+                    expr,
+                    loop_body,
+                ),
+            },
+            Stmt::BreakStmt { label, .. } => {
+                let target = self.find_jump_target(label.as_deref(), false);
+                self.builder.build_unconditional_branch(target);
+            }
+            Stmt::ContinueStmt { label } => {
+                let target = self.find_jump_target(label.as_deref(), true);
+                self.builder.build_unconditional_branch(target);
+            }
+            Stmt::AssignementStmt { lhs, rhs } => self.compile_assignement_stmt(lhs, rhs),
+            Stmt::CompoundAssignStmt { place, op, value } => {
+                self.compile_compound_assign_stmt(place, op, value)
             }
+            Stmt::ShortVarDeclStmt { names, exprs } => self.compile_short_var_decl(names, exprs),
+            Stmt::VariableDeclStmt(decl) => self.compile_var_decl_stmt(decl),
+        }
+    }
 
-            Expression::CallExpr(ref fn_name, ref args) => {
-                match self.get_function(fn_name.as_str()) {
-                    Some(fun) => {
-                        let mut compiled_args = Vec::with_capacity(args.len());
-
-                        for arg in args {
-                            compiled_args.push(self.compile_expr(arg)?);
-                        }
-
-                        let argsv: Vec<BasicMetadataValueEnum> = compiled_args
-                            .iter()
-                            .by_ref()
-                            .map(|&val| val.into())
-                            .collect();
-
-                        match self
-                            .builder
-                            .build_call(fun, argsv.as_slice(), "tmp")
-                            .try_as_basic_value()
-                            .left()
-                        {
-                            Some(value) => Ok(value.into_int_value()),
-                            None => Err("Invalid call produced."),
-                        }
-                    }
-                    None => Err("Unknown function."),
+    fn compile_if_else_stmt(
+        &mut self,
+        predicate: &Expression,
+        stmt_true: &Statement,
+        stmt_false: Option<&Statement>,
+    ) {
+        let cond = self.compile_condition(predicate);
+
+        let function = self.fn_value();
+        let then_bb = self.context.append_basic_block(function, "then");
+        let else_bb = stmt_false.map(|_| self.context.append_basic_block(function, "else"));
+        let merge_bb = self.context.append_basic_block(function, "ifcont");
+
+        self.builder
+            .build_conditional_branch(cond, then_bb, else_bb.unwrap_or(merge_bb));
+
+        self.builder.position_at_end(then_bb);
+        self.compile_nested_stmt(stmt_true);
+        self.branch_to(merge_bb);
+
+        if let (Some(stmt_false), Some(else_bb)) = (stmt_false, else_bb) {
+            self.builder.position_at_end(else_bb);
+            self.compile_nested_stmt(stmt_false);
+            self.branch_to(merge_bb);
+        }
+
+        self.builder.position_at_end(merge_bb);
+    }
+
+    fn compile_block_stmt(&mut self, label: Option<&String>, block: &Block) {
+        let Some(label) = label else {
+            self.compile_block(block);
+            return;
+        };
+
+        let after_bb = self
+            .context
+            .append_basic_block(self.fn_value(), "block.end");
+        self.jump_targets.push(JumpTarget {
+            label: Some(label.clone()),
+            break_bb: after_bb,
+            continue_bb: None,
+        });
+        self.compile_block(block);
+        self.jump_targets.pop();
+
+        self.branch_to(after_bb);
+        self.builder.position_at_end(after_bb);
+    }
+
+    fn compile_while_stmt(
+        &mut self,
+        label: Option<&String>,
+        ctrling_expr: &Expression,
+        loop_body: &Block,
+    ) {
+        let function = self.fn_value();
+        let cond_bb = self.context.append_basic_block(function, "while.cond");
+        let body_bb = self.context.append_basic_block(function, "while.body");
+        let after_bb = self.context.append_basic_block(function, "while.end");
+
+        self.branch_to(cond_bb);
+        self.builder.position_at_end(cond_bb);
+        let cond = self.compile_condition(ctrling_expr);
+        self.builder
+            .build_conditional_branch(cond, body_bb, after_bb);
+
+        self.builder.position_at_end(body_bb);
+        self.jump_targets.push(JumpTarget {
+            label: label.cloned(),
+            break_bb: after_bb,
+            continue_bb: Some(cond_bb),
+        });
+        self.compile_block(loop_body);
+        self.jump_targets.pop();
+        self.branch_to(cond_bb);
+
+        self.builder.position_at_end(after_bb);
+    }
+
+    /// Compiles a `for` loop over a range, the end and the step are computed
+    /// once. The loop ends before the variable would go past the end, so it
+    /// never overflows, even with an inclusive range up to the maximum.
+    fn compile_for_stmt(
+        &mut self,
+        label: Option<&String>,
+        var: &str,
+        range: &ForRange,
+        loop_body: &Block,
+    ) {
+        let inclusive = range.inclusive;
+        let start = self.compile_expr(&range.start);
+        let end = self.compile_expr(&range.end).val.into_int_value();
+        let ty = start.ty;
+        let step = match &range.step {
+            Some(step) => self.compile_expr(step).val.into_int_value(),
+            None => self.compile_int_lit(1, false, &ty).into_int_value(),
+        };
+        let counter = self.create_entry_block_alloca(self.value_type(&ty), "for.counter");
+        self.builder.build_store(counter, start.val);
+
+        let function = self.fn_value();
+        let cond_bb = self.context.append_basic_block(function, "for.cond");
+        let body_bb = self.context.append_basic_block(function, "for.body");
+        let step_bb = self.context.append_basic_block(function, "for.step");
+        let next_bb = self.context.append_basic_block(function, "for.next");
+        let after_bb = self.context.append_basic_block(function, "for.end");
+
+        // the range may be empty
+        self.branch_to(cond_bb);
+        self.builder.position_at_end(cond_bb);
+        let value = self
+            .builder
+            .build_load(self.value_type(&ty), counter, "value")
+            .into_int_value();
+        let predicate = match (inclusive, ty.is_signed()) {
+            (false, false) => IntPredicate::ULT,
+            (false, true) => IntPredicate::SLT,
+            (true, false) => IntPredicate::ULE,
+            (true, true) => IntPredicate::SLE,
+        };
+        let cond = self
+            .builder
+            .build_int_compare(predicate, value, end, "forcond");
+        self.builder
+            .build_conditional_branch(cond, body_bb, after_bb);
+
+        self.builder.position_at_end(body_bb);
+        self.scopes.push(HashMap::new());
+        let value = self.builder.build_load(self.value_type(&ty), counter, var);
+        let ptr = self.declare_local(var, ty.clone());
+        self.builder.build_store(ptr, value);
+        self.jump_targets.push(JumpTarget {
+            label: label.cloned(),
+            break_bb: after_bb,
+            continue_bb: Some(step_bb),
+        });
+        self.compile_block(loop_body);
+        self.jump_targets.pop();
+        self.scopes.pop();
+        self.branch_to(step_bb);
+
+        // the values left until the end, never negative as the variable is
+        // in the range
+        self.builder.position_at_end(step_bb);
+        let value = self
+            .builder
+            .build_load(self.value_type(&ty), counter, "value")
+            .into_int_value();
+        let left = self.builder.build_int_sub(end, value, "forleft");
+        let predicate = if inclusive {
+            IntPredicate::ULT
+        } else {
+            IntPredicate::ULE
+        };
+        let last = self
+            .builder
+            .build_int_compare(predicate, left, step, "forlast");
+        self.builder
+            .build_conditional_branch(last, after_bb, next_bb);
+
+        self.builder.position_at_end(next_bb);
+        let next = self.builder.build_int_add(value, step, "fornext");
+        self.builder.build_store(counter, next);
+        self.builder.build_unconditional_branch(body_bb);
+
+        self.builder.position_at_end(after_bb);
+    }
+
+    /// Compiles a `for` loop over the elements of an array or of a slice, the
+    /// array or the slice is computed once.
+    fn compile_for_elems_stmt(
+        &mut self,
+        label: Option<&String>,
+        index: Option<&str>,
+        var: &str,
+        expr: &Expression,
+        loop_body: &Block,
+    ) {
+        let (ptr, len, elem) = self.compile_elems(expr);
+        let usize_type = self.value_type(&Ty::USIZE).into_int_type();
+        let counter = self.create_entry_block_alloca(usize_type.into(), "for.index");
+        self.builder.build_store(counter, usize_type.const_zero());
+
+        let function = self.fn_value();
+        let cond_bb = self.context.append_basic_block(function, "for.cond");
+        let body_bb = self.context.append_basic_block(function, "for.body");
+        let step_bb = self.context.append_basic_block(function, "for.step");
+        let after_bb = self.context.append_basic_block(function, "for.end");
+
+        // the array or the slice may be empty
+        self.branch_to(cond_bb);
+        self.builder.position_at_end(cond_bb);
+        let i = self
+            .builder
+            .build_load(usize_type, counter, "index")
+            .into_int_value();
+        let cond = self
+            .builder
+            .build_int_compare(IntPredicate::ULT, i, len, "forcond");
+        self.builder
+            .build_conditional_branch(cond, body_bb, after_bb);
+
+        self.builder.position_at_end(body_bb);
+        self.scopes.push(HashMap::new());
+        let i = self
+            .builder
+            .build_load(usize_type, counter, "index")
+            .into_int_value();
+        if let Some(index) = index {
+            let ptr = self.declare_local(index, Ty::USIZE);
+            self.builder.build_store(ptr, i);
+        }
+        let value = self.load_elem(ptr, i, &elem, var);
+        let ptr = self.declare_local(var, elem);
+        self.builder.build_store(ptr, value);
+        self.jump_targets.push(JumpTarget {
+            label: label.cloned(),
+            break_bb: after_bb,
+            continue_bb: Some(step_bb),
+        });
+        self.compile_block(loop_body);
+        self.jump_targets.pop();
+        self.scopes.pop();
+        self.branch_to(step_bb);
+
+        // the index is less than the length, it can't overflow
+        self.builder.position_at_end(step_bb);
+        let i = self
+            .builder
+            .build_load(usize_type, counter, "index")
+            .into_int_value();
+        let next = self
+            .builder
+            .build_int_nuw_add(i, usize_type.const_int(1, false), "fornext");
+        self.builder.build_store(counter, next);
+        self.builder.build_unconditional_branch(cond_bb);
+
+        self.builder.position_at_end(after_bb);
+    }
+
+    /// Finds the block where a `break` or a `continue` jumps to.
+    ///
+    /// Without a label, the innermost loop is the target. With a label, it's
+    /// the loop or the block with this label.
+    fn find_jump_target(&self, label: Option<&str>, is_continue: bool) -> BasicBlock<'ctx> {
+        let target = self
+            .jump_targets
+            .iter()
+            .rev()
+            .find(|target| match label {
+                Some(label) => target.label.as_deref() == Some(label),
+                None => target.continue_bb.is_some(),
+            })
+            .expect("a jump without target should be a type error");
+
+        if is_continue {
+            target.continue_bb.unwrap()
+        } else {
+            target.break_bb
+        }
+    }
+
+    fn compile_assignement_stmt(&mut self, lhs: &ExpressionList, rhs: &ExpressionList) {
+        let places: Vec<Place> = lhs.0.iter().map(|expr| self.compile_place(expr)).collect();
+
+        // every value is computed before any store, so `a, b = b, a` swaps
+        let values: Vec<Value> = rhs.0.iter().map(|expr| self.compile_expr(expr)).collect();
+
+        for (place, value) in places.iter().zip(values) {
+            self.builder.build_store(place.ptr, value.val);
+        }
+    }
+
+    fn compile_compound_assign_stmt(
+        &mut self,
+        place: &Expression,
+        op: &BinOperation,
+        value: &Expression,
+    ) {
+        let place = self.compile_place(place);
+        let current = self
+            .builder
+            .build_load(self.value_type(&place.ty), place.ptr, "loadtmp");
+        let lhs_val = Value {
+            val: current,
+            ty: place.ty,
+        };
+        let rhs_val = self.compile_expr(value);
+
+        let result = self.build_binary(op, lhs_val, rhs_val);
+        self.builder.build_store(place.ptr, result);
+    }
+
+    fn compile_short_var_decl(&mut self, names: &[String], exprs: &[Expression]) {
+        let values: Vec<Value> = exprs.iter().map(|expr| self.compile_expr(expr)).collect();
+
+        for (name, value) in names.iter().zip(values) {
+            let ptr = self.declare_local(name, value.ty);
+            self.builder.build_store(ptr, value.val);
+        }
+    }
+
+    fn compile_var_decl_stmt(&mut self, decl: &VarDecl) {
+        let value = decl.expr.as_ref().map(|expr| self.compile_expr(expr));
+        let ty = match (&decl.ty, &value) {
+            (Some(ty), _) => self.results.ty(ty).clone(),
+            (None, Some(value)) => value.ty.clone(),
+            (None, None) => unreachable!("a variable without type should be a type error"),
+        };
+
+        let ptr = self.declare_local(&decl.name, ty);
+        if let Some(value) = value {
+            self.builder.build_store(ptr, value.val);
+        }
+    }
+
+    /// Compiles an expression of type `bool`, used as a condition.
+    fn compile_condition(&mut self, expr: &Expression) -> IntValue<'ctx> {
+        self.compile_expr(expr).val.into_int_value()
+    }
+
+    /// Compiles an expression that refers to a location in memory, returns
+    /// the location without reading it.
+    fn compile_place(&mut self, expr: &Expression) -> Place<'ctx> {
+        match &expr.expr {
+            Expr::IdentifierExpr(name) => self.get_variable(name),
+            Expr::MemberAccessExpr {
+                expr: inner,
+                member_name,
+            } => match self.package_global(expr) {
+                Some(place) => place,
+                None => {
+                    let base = self.compile_place(inner);
+                    self.field_place(base.ptr, &base.ty, member_name)
+                }
+            },
+            Expr::PointerMemberAccessExpr {
+                expr: inner,
+                member_name,
+            } => {
+                let ptr = self.compile_expr(inner);
+                let pointee = ptr.ty.pointee().unwrap().clone();
+                self.field_place(ptr.val.into_pointer_value(), &pointee, member_name)
+            }
+            Expr::ParenthesizedExpr(inner) => self.compile_place(inner),
+            Expr::IndexExpr { expr: inner, index } => {
+                let (ptr, _, elem) = self.compile_elems(inner);
+                let index = self.compile_expr(index).val.into_int_value();
+                Place {
+                    ptr: self.elem_ptr(ptr, index, &elem),
+                    ty: elem,
+                }
+            }
+            Expr::UnaryExpr {
+                op: UnaryOperation::Dereference,
+                expr: inner,
+            } => Place {
+                ptr: self.compile_expr(inner).val.into_pointer_value(),
+                ty: self.expr_ty(expr),
+            },
+            _ => unreachable!("an invalid place expression should be a type error"),
+        }
+    }
+
+    /// Compiles an expression, its type was found by the type checker.
+    fn compile_expr(&mut self, expr: &Expression) -> Value<'ctx> {
+        let ty = self.expr_ty(expr);
+        let val = match &expr.expr {
+            Expr::IntLitExpr { value, .. } => self.compile_int_lit(*value, false, &ty),
+            Expr::FloatLitExpr(float) => self.compile_float_lit(*float, &ty),
+            Expr::CharLitExpr(c) => self.compile_int_lit(*c as u128, false, &ty),
+            Expr::BoolLitExpr(b) => self.context.bool_type().const_int(*b as u64, false).into(),
+            Expr::StrLitExpr(s) => self.const_str(s).into(),
+            Expr::IdentifierExpr(name) => {
+                let var = self.get_variable(name);
+                self.builder
+                    .build_load(self.value_type(&var.ty), var.ptr, name)
+            }
+            Expr::ParenthesizedExpr(inner) => return self.compile_expr(inner),
+            Expr::BinaryExpr { lhs, op, rhs } => self.compile_binary(lhs, op, rhs),
+            Expr::UnaryExpr { op, expr: inner } => self.compile_unary(expr, op, inner, &ty),
+            Expr::CallExpr { fn_op, args } => {
+                self.compile_call(fn_op, args)
+                    .expect("a call to a void function as a value should be a type error")
+                    .val
+            }
+            Expr::IfElseExpr {
+                true_expr,
+                predicate,
+                false_expr,
+            } => self.compile_if_else_expr(true_expr, predicate, false_expr),
+            Expr::MemberAccessExpr { .. } if self.results.variant(expr).is_some() => {
+                let (enum_ty, index) = self.results.variant(expr).unwrap();
+                self.compile_variant(&enum_ty.clone(), index, &[])
+            }
+            Expr::MemberAccessExpr {
+                expr: inner,
+                member_name,
+            } => match self.package_global(expr) {
+                Some(var) => {
+                    self.builder
+                        .build_load(self.value_type(&var.ty), var.ptr, member_name)
+                }
+                None => {
+                    let value = self.compile_expr(inner);
+                    self.compile_member(value, member_name)
                 }
+            },
+            Expr::PointerMemberAccessExpr { member_name, .. } => {
+                let place = self.compile_place(expr);
+                self.builder
+                    .build_load(self.value_type(&place.ty), place.ptr, member_name)
+            }
+            Expr::IndexExpr { .. } => {
+                let place = self.compile_place(expr);
+                self.builder
+                    .build_load(self.value_type(&place.ty), place.ptr, "elem")
+            }
+            Expr::SliceExpr {
+                expr: inner,
+                start,
+                end,
+            } => self.compile_slice(inner, start.as_deref(), end.as_deref()),
+            Expr::ArrayLitExpr(elems) => self.compile_array_lit(&ty, elems),
+            Expr::StructLitExpr { fields, .. } => self.compile_struct_lit(&ty, fields),
+            Expr::MatchExpr { scrutinee, arms } => self
+                .compile_match(scrutinee, arms, &ty)
+                .expect("a void match as a value should be a type error"),
+        };
+        Value { val, ty }
+    }
+
+    /// Returns the field of a struct value, or the length of an array or of a
+    /// slice.
+    fn compile_member(&self, value: Value<'ctx>, member_name: &str) -> BasicValueEnum<'ctx> {
+        let index = match &value.ty {
+            Ty::Array { len, .. } => {
+                return self.compile_int_lit(*len as u128, false, &Ty::USIZE);
             }
-            _ => Err("expression not covered by the codegen."),
+            Ty::Slice { .. } => 1,
+            ty => self.results.struct_def(ty).field(member_name).unwrap().0,
+        };
+        self.builder
+            .build_extract_value(value.val.into_struct_value(), index as u32, member_name)
+            .unwrap()
+    }
+
+    /// Returns a pointer to the first element of the array or of the slice,
+    /// the number of elements and their type.
+    ///
+    /// An array that isn't a place is stored on the stack first.
+    fn compile_elems(&mut self, expr: &Expression) -> (PointerValue<'ctx>, IntValue<'ctx>, Ty) {
+        let ty = self.expr_ty(expr);
+        match ty {
+            Ty::Array { elem, len } => {
+                let ptr = if is_place_expr(expr) {
+                    self.compile_place(expr).ptr
+                } else {
+                    let value = self.compile_expr(expr);
+                    let ptr = self.create_entry_block_alloca(value.val.get_type(), "array");
+                    self.builder.build_store(ptr, value.val);
+                    ptr
+                };
+                let len = self.compile_int_lit(len as u128, false, &Ty::USIZE);
+                (ptr, len.into_int_value(), *elem)
+            }
+            Ty::Slice { elem, .. } => {
+                let slice = self.compile_expr(expr).val.into_struct_value();
+                let ptr = self.builder.build_extract_value(slice, 0, "ptr").unwrap();
+                let len = self.builder.build_extract_value(slice, 1, "len").unwrap();
+                (ptr.into_pointer_value(), len.into_int_value(), *elem)
+            }
+            _ => unreachable!("only arrays and slices have elements"),
         }
     }
 
-    /// Compiles the specified `Prototype` into an extern LLVM `FunctionValue`.
-    fn compile_prototype(&self, proto: &Prototype) -> Result<FunctionValue<'ctx>, &'static str> {
-        let ret_type = self.context.i32_type();
-        let args_types = std::iter::repeat(ret_type)
-            .take(proto.args.len())
-            .map(|f| f.into())
-            .collect::<Vec<BasicMetadataTypeEnum>>();
-        let args_types = args_types.as_slice();
+    /// Returns a pointer to the element at the index, of elements of type
+    /// `elem` starting at `ptr`.
+    fn elem_ptr(
+        &self,
+        ptr: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+        elem: &Ty,
+    ) -> PointerValue<'ctx> {
+        // SAFETY: the pointer is to elements of type `elem`
+        unsafe {
+            self.builder
+                .build_in_bounds_gep(self.value_type(elem), ptr, &[index], "elem")
+        }
+    }
 
-        let fn_type = self.context.i32_type().fn_type(args_types, false);
-        let fn_val = self.module.add_function(proto.name.as_str(), fn_type, None);
+    /// Loads the element at the index, of elements of type `elem` starting at
+    /// `ptr`.
+    fn load_elem(
+        &self,
+        ptr: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+        elem: &Ty,
+        name: &str,
+    ) -> BasicValueEnum<'ctx> {
+        let ptr = self.elem_ptr(ptr, index, elem);
+        self.builder.build_load(self.value_type(elem), ptr, name)
+    }
 
-        // set arguments names
-        for (i, arg) in fn_val.get_param_iter().enumerate() {
-            arg.set_name(&proto.args[i].name);
+    /// Compiles the slice of an array or of a slice, from the start, or the
+    /// first element, to the end, or the last element.
+    fn compile_slice(
+        &mut self,
+        expr: &Expression,
+        start: Option<&Expression>,
+        end: Option<&Expression>,
+    ) -> BasicValueEnum<'ctx> {
+        let (ptr, len, elem) = self.compile_elems(expr);
+        let start = match start {
+            Some(start) => self.compile_expr(start).val.into_int_value(),
+            None => self.value_type(&Ty::USIZE).into_int_type().const_zero(),
+        };
+        let end = match end {
+            Some(end) => self.compile_expr(end).val.into_int_value(),
+            None => len,
+        };
+
+        let ptr = self.elem_ptr(ptr, start, &elem);
+        let len = self.builder.build_int_sub(end, start, "slicelen");
+        let slice = self.slice_type(&elem).get_undef();
+        let slice = self
+            .builder
+            .build_insert_value(slice, ptr, 0, "slice")
+            .unwrap();
+        self.builder
+            .build_insert_value(slice, len, 1, "slice")
+            .unwrap()
+            .into_struct_value()
+            .into()
+    }
+
+    /// Compiles an array literal, its elements are inserted one by one.
+    fn compile_array_lit(&mut self, ty: &Ty, elems: &[Expression]) -> BasicValueEnum<'ctx> {
+        let mut array = self.value_type(ty).into_array_type().get_undef();
+        for (i, elem) in elems.iter().enumerate() {
+            let value = self.compile_expr(elem);
+            array = self
+                .builder
+                .build_insert_value(array, value.val, i as u32, "arraytmp")
+                .unwrap()
+                .into_array_value();
         }
+        array.into()
+    }
 
-        // finally return built prototype
-        Ok(fn_val)
+    /// Returns the field of the struct of type `ty` stored at `ptr`.
+    fn field_place(&self, ptr: PointerValue<'ctx>, ty: &Ty, member_name: &str) -> Place<'ctx> {
+        let (index, field) = self.results.struct_def(ty).field(member_name).unwrap();
+        let ptr = self
+            .builder
+            .build_struct_gep(self.struct_type(ty), ptr, index as u32, member_name)
+            .unwrap();
+        Place {
+            ptr,
+            ty: field.ty.clone(),
+        }
     }
 
-    /// Compiles the specified `Function` into an LLVM `FunctionValue`.
-    fn compile_fn(&mut self) -> Result<FunctionValue<'ctx>, &'static str> {
-        let proto = &self.function.prototype;
-        let function = self.compile_prototype(proto)?;
+    /// Compiles a variant of an enum, with the values of its payload.
+    fn compile_variant(
+        &mut self,
+        ty: &Ty,
+        index: usize,
+        args: &[Expression],
+    ) -> BasicValueEnum<'ctx> {
+        let def = self.results.enum_def(ty).clone();
+        let variant = &def.variants[index];
+        let discriminant = variant.discriminant;
+        let tag = self.compile_int_lit(discriminant.unsigned_abs(), discriminant < 0, &def.repr);
+        if !def.has_payload() {
+            return tag;
+        }
+
+        let values: Vec<Value> = args.iter().map(|arg| self.compile_expr(arg)).collect();
 
-        // got external function, returning only compiled prototype
-        if self.function.body.is_none() {
-            return Ok(function);
+        let enum_type = self.enum_type(ty).into_struct_type();
+        let ptr = self.create_entry_block_alloca(enum_type.into(), &variant.name);
+        let tag_ptr = self
+            .builder
+            .build_struct_gep(enum_type, ptr, 0, "tag")
+            .unwrap();
+        self.builder.build_store(tag_ptr, tag);
+
+        if !values.is_empty() {
+            let payload_type = self.payload_type(variant);
+            let storage = self
+                .builder
+                .build_struct_gep(enum_type, ptr, 1, "storage")
+                .unwrap();
+            let payload = self.builder.build_pointer_cast(
+                storage,
+                payload_type.ptr_type(AddressSpace::default()),
+                "payload",
+            );
+            for (i, value) in values.into_iter().enumerate() {
+                let field = self
+                    .builder
+                    .build_struct_gep(payload_type, payload, i as u32, "field")
+                    .unwrap();
+                self.builder.build_store(field, value.val);
+            }
         }
 
-        let entry = self.context.append_basic_block(function, "entry");
+        self.builder.build_load(enum_type, ptr, &variant.name)
+    }
 
-        self.builder.position_at_end(entry);
+    /// Compiles a struct literal, the values of the fields are computed in
+    /// the order of the literal.
+    fn compile_struct_lit(&mut self, ty: &Ty, fields: &[FieldInit]) -> BasicValueEnum<'ctx> {
+        let values: Vec<(&str, Value)> = fields
+            .iter()
+            .map(|init| (init.name.as_str(), self.compile_expr(&init.expr)))
+            .collect();
+
+        let mut aggregate = self.struct_type(ty).get_undef();
+        for (name, value) in values {
+            let (index, _) = self.results.struct_def(ty).field(name).unwrap();
+            aggregate = self
+                .builder
+                .build_insert_value(aggregate, value.val, index as u32, name)
+                .unwrap()
+                .into_struct_value();
+        }
+        aggregate.into()
+    }
 
-        // update fn field
-        self.fn_value_opt = Some(function);
+    /// Compiles an integer literal of type `ty`, the type checker ensured the
+    /// value fits in it.
+    fn compile_int_lit(&self, value: u128, negative: bool, ty: &Ty) -> BasicValueEnum<'ctx> {
+        let bits = ty.int_bits(self.pointer_width).unwrap();
 
-        // build variables map
-        self.variables.reserve(proto.args.len());
+        let int_ty = self.context.custom_width_int_type(bits);
+        let v = if negative {
+            value.wrapping_neg()
+        } else {
+            value
+        };
+        let val = if bits > 64 {
+            int_ty.const_int_arbitrary_precision(&[v as u64, (v >> 64) as u64])
+        } else {
+            int_ty.const_int(v as u64, false)
+        };
+        val.into()
+    }
+
+    fn compile_float_lit(&self, value: f64, ty: &Ty) -> BasicValueEnum<'ctx> {
+        self.value_type(ty)
+            .into_float_type()
+            .const_float(value)
+            .into()
+    }
+
+    fn compile_binary(
+        &mut self,
+        lhs: &Expression,
+        op: &BinOperation,
+        rhs: &Expression,
+    ) -> BasicValueEnum<'ctx> {
+        if op.is_logical() {
+            return self.compile_logical(lhs, op, rhs);
+        }
+
+        let lhs_val = self.compile_expr(lhs);
+        let rhs_val = self.compile_expr(rhs);
+        self.build_binary(op, lhs_val, rhs_val)
+    }
 
-        for (i, arg) in function.get_param_iter().enumerate() {
-            let arg_name = proto.args[i].name.as_str();
-            let alloca = self.create_entry_block_alloca(arg_name);
+    /// Compiles `&&` and `||`, the right-hand side is only evaluated if the
+    /// left-hand side doesn't give the result.
+    fn compile_logical(
+        &mut self,
+        lhs: &Expression,
+        op: &BinOperation,
+        rhs: &Expression,
+    ) -> BasicValueEnum<'ctx> {
+        let lhs_val = self.compile_expr(lhs).val.into_int_value();
+        let lhs_end = self.current_block();
 
-            self.builder.build_store(alloca, arg);
+        let function = self.fn_value();
+        let rhs_bb = self.context.append_basic_block(function, "logic.rhs");
+        let merge_bb = self.context.append_basic_block(function, "logic.end");
 
-            self.variables.insert(proto.args[i].name.clone(), alloca);
+        if *op == BinOperation::LogicalAnd {
+            self.builder
+                .build_conditional_branch(lhs_val, rhs_bb, merge_bb);
+        } else {
+            self.builder
+                .build_conditional_branch(lhs_val, merge_bb, rhs_bb);
         }
 
-        // compile body
-        let body = self.compile_expr(self.function.body.as_ref().unwrap())?;
+        let (rhs_val, rhs_end) = self.compile_branch(rhs_bb, rhs, merge_bb);
+
+        self.builder.position_at_end(merge_bb);
+        let phi = self.builder.build_phi(self.context.bool_type(), "logictmp");
+        phi.add_incoming(&[(&lhs_val, lhs_end), (&rhs_val, rhs_end)]);
+
+        phi.as_basic_value()
+    }
 
-        self.builder.build_return(Some(&body));
+    /// Builds a binary operation, other than `&&` and `||`, on two values of
+    /// the same type.
+    fn build_binary(
+        &self,
+        op: &BinOperation,
+        lhs_val: Value<'ctx>,
+        rhs_val: Value<'ctx>,
+    ) -> BasicValueEnum<'ctx> {
+        use BinOperation::*;
 
-        // return the whole thing after verification and optimization
-        if function.verify(true) {
-            self.fpm.run_on(&function);
+        let ty = lhs_val.ty;
+
+        // the values of an enum without payloads are its discriminant
+        if ty.is_int() || ty.is_bool() || ty.is_enum() {
+            let (l, r) = (lhs_val.val.into_int_value(), rhs_val.val.into_int_value());
+            let signed = ty.is_signed();
+
+            if op.is_comparison() {
+                let pred = match (op, signed) {
+                    (CompEq, _) => IntPredicate::EQ,
+                    (CompNe, _) => IntPredicate::NE,
+                    (CompLT, true) => IntPredicate::SLT,
+                    (CompLT, false) => IntPredicate::ULT,
+                    (CompGT, true) => IntPredicate::SGT,
+                    (CompGT, false) => IntPredicate::UGT,
+                    (CompLTE, true) => IntPredicate::SLE,
+                    (CompLTE, false) => IntPredicate::ULE,
+                    (CompGTE, true) => IntPredicate::SGE,
+                    (CompGTE, false) => IntPredicate::UGE,
+                    _ => unreachable!(),
+                };
+                return self.builder.build_int_compare(pred, l, r, "cmptmp").into();
+            }
 
-            Ok(function)
+            match op {
+                And => self.builder.build_and(l, r, "andtmp"),
+                Or => self.builder.build_or(l, r, "ortmp"),
+                Xor => self.builder.build_xor(l, r, "xortmp"),
+                Add => self.builder.build_int_add(l, r, "addtmp"),
+                Sub => self.builder.build_int_sub(l, r, "subtmp"),
+                Mul => self.builder.build_int_mul(l, r, "multmp"),
+                Div if signed => self.builder.build_int_signed_div(l, r, "divtmp"),
+                Div => self.builder.build_int_unsigned_div(l, r, "divtmp"),
+                Rem if signed => self.builder.build_int_signed_rem(l, r, "remtmp"),
+                Rem => self.builder.build_int_unsigned_rem(l, r, "remtmp"),
+                LShift => self.builder.build_left_shift(l, r, "shltmp"),
+                RShift => self.builder.build_right_shift(l, r, signed, "shrtmp"),
+                _ => unreachable!(),
+            }
+            .into()
+        } else if ty.is_float() {
+            let (l, r) = (
+                lhs_val.val.into_float_value(),
+                rhs_val.val.into_float_value(),
+            );
+
+            if op.is_comparison() {
+                let pred = match op {
+                    CompEq => FloatPredicate::OEQ,
+                    CompNe => FloatPredicate::UNE,
+                    CompLT => FloatPredicate::OLT,
+                    CompGT => FloatPredicate::OGT,
+                    CompLTE => FloatPredicate::OLE,
+                    CompGTE => FloatPredicate::OGE,
+                    _ => unreachable!(),
+                };
+                return self
+                    .builder
+                    .build_float_compare(pred, l, r, "cmptmp")
+                    .into();
+            }
+
+            match op {
+                Add => self.builder.build_float_add(l, r, "addtmp"),
+                Sub => self.builder.build_float_sub(l, r, "subtmp"),
+                Mul => self.builder.build_float_mul(l, r, "multmp"),
+                Div => self.builder.build_float_div(l, r, "divtmp"),
+                Rem => self.builder.build_float_rem(l, r, "remtmp"),
+                _ => unreachable!(),
+            }
+            .into()
         } else {
-            unsafe {
-                function.delete();
+            // only pointers equality is type checked
+            let int_ty = self.context.custom_width_int_type(self.pointer_width);
+            let l =
+                self.builder
+                    .build_ptr_to_int(lhs_val.val.into_pointer_value(), int_ty, "lhsint");
+            let r =
+                self.builder
+                    .build_ptr_to_int(rhs_val.val.into_pointer_value(), int_ty, "rhsint");
+            let pred = if *op == CompEq {
+                IntPredicate::EQ
+            } else {
+                IntPredicate::NE
+            };
+            self.builder.build_int_compare(pred, l, r, "cmptmp").into()
+        }
+    }
+
+    fn compile_unary(
+        &mut self,
+        expr: &Expression,
+        op: &UnaryOperation,
+        inner: &Expression,
+        ty: &Ty,
+    ) -> BasicValueEnum<'ctx> {
+        match op {
+            UnaryOperation::Negation => {
+                if let Expr::IntLitExpr { value, .. } = inner.expr {
+                    return self.compile_int_lit(value, true, ty);
+                }
+                // a negative literal may initialize a global, outside of a
+                // function
+                if let Expr::FloatLitExpr(float) = inner.expr {
+                    return self.compile_float_lit(-float, ty);
+                }
+                let value = self.compile_expr(inner);
+                if ty.is_float() {
+                    self.builder
+                        .build_float_neg(value.val.into_float_value(), "negtmp")
+                        .into()
+                } else {
+                    self.builder
+                        .build_int_neg(value.val.into_int_value(), "negtmp")
+                        .into()
+                }
+            }
+            UnaryOperation::Not => {
+                let value = self.compile_expr(inner);
+                self.builder
+                    .build_not(value.val.into_int_value(), "nottmp")
+                    .into()
+            }
+            UnaryOperation::BitwiseNot => {
+                let value = self.compile_expr(inner);
+                self.builder
+                    .build_not(value.val.into_int_value(), "bnottmp")
+                    .into()
+            }
+            UnaryOperation::AddressOf => self.compile_place(inner).ptr.into(),
+            UnaryOperation::Dereference => {
+                let place = self.compile_place(expr);
+                self.builder
+                    .build_load(self.value_type(&place.ty), place.ptr, "dereftmp")
+            }
+        }
+    }
+
+    /// Compiles a call, returns `None` if the function returns `void`.
+    fn compile_call(&mut self, fn_op: &Expression, args: &[Expression]) -> Option<Value<'ctx>> {
+        if let Some((ty, index)) = self.results.variant(fn_op) {
+            let ty = ty.clone();
+            let val = self.compile_variant(&ty, index, args);
+            return Some(Value { val, ty });
+        }
+
+        let (function, receiver) = match self.results.method_call(fn_op).cloned() {
+            Some(call) => {
+                let receiver = call
+                    .autoref
+                    .map(|autoref| self.compile_receiver(fn_op, &call.ty, autoref));
+                (self.method(&call.ty, &call.name), receiver)
             }
+            None => match &fn_op.expr {
+                Expr::MemberAccessExpr { member_name, .. } => {
+                    let import = self
+                        .results
+                        .package_item(fn_op)
+                        .expect("member access is rejected by the type checker");
+                    let sig = import.interface.functions[member_name].def.clone();
+                    let symbol = import.interface.function_symbol(member_name);
+                    (self.imported_function(&symbol, &sig), None)
+                }
+                Expr::IdentifierExpr(name) => (self.functions[name].clone(), None),
+                _ => unreachable!("calling an expression is rejected by the type checker"),
+            },
+        };
 
-            Err("Invalid generated function.")
+        let mut values: Vec<BasicMetadataValueEnum> =
+            receiver.into_iter().map(Into::into).collect();
+        for arg in args {
+            values.push(self.compile_expr(arg).val.into());
         }
+
+        // a call returning void cannot be named
+        let name = if function.ret_ty.is_void() {
+            ""
+        } else {
+            "calltmp"
+        };
+        let call = self.builder.build_call(function.value, &values, name);
+
+        call.try_as_basic_value().left().map(|val| Value {
+            val,
+            ty: function.ret_ty,
+        })
     }
 
-    /// Compiles the specified `Function` in the given `Context` and using the specified `Builder`, `PassManager`, and `Module`.
-    pub fn compile(
-        context: &'ctx Context,
-        builder: &'a Builder<'ctx>,
-        pass_manager: &'a PassManager<FunctionValue<'ctx>>,
-        module: &'a Module<'ctx>,
-        function: &Function,
-    ) -> Result<FunctionValue<'ctx>, &'static str> {
-        let mut compiler = CodeGen {
-            context,
-            builder,
-            fpm: pass_manager,
-            module,
-            function,
-            fn_value_opt: None,
-            variables: HashMap::new(),
+    /// Compiles the receiver of a method call, like `p` in `p.len()`, passed
+    /// as the method takes it.
+    fn compile_receiver(
+        &mut self,
+        fn_op: &Expression,
+        ty: &Ty,
+        autoref: Autoref,
+    ) -> BasicValueEnum<'ctx> {
+        let (Expr::MemberAccessExpr { expr: base, .. }
+        | Expr::PointerMemberAccessExpr { expr: base, .. }) = &fn_op.expr
+        else {
+            unreachable!("a method is called through a member access")
         };
+        match autoref {
+            Autoref::Value => self.compile_expr(base).val,
+            Autoref::Deref => {
+                let ptr = self.compile_expr(base).val.into_pointer_value();
+                self.builder.build_load(self.value_type(ty), ptr, "self")
+            }
+            Autoref::Ref => self.compile_place(base).ptr.into(),
+            Autoref::RefTemporary => {
+                let value = self.compile_expr(base);
+                let ptr = self.create_entry_block_alloca(self.value_type(ty), "self");
+                self.builder.build_store(ptr, value.val);
+                ptr.into()
+            }
+        }
+    }
+
+    fn compile_if_else_expr(
+        &mut self,
+        true_expr: &Expression,
+        predicate: &Expression,
+        false_expr: &Expression,
+    ) -> BasicValueEnum<'ctx> {
+        let cond = self.compile_condition(predicate);
+
+        let function = self.fn_value();
+        let then_bb = self.context.append_basic_block(function, "then");
+        let else_bb = self.context.append_basic_block(function, "else");
+        let merge_bb = self.context.append_basic_block(function, "ifcont");
+
+        self.builder
+            .build_conditional_branch(cond, then_bb, else_bb);
 
-        compiler.compile_fn()
+        let (true_val, then_end) = self.compile_branch(then_bb, true_expr, merge_bb);
+        let (false_val, else_end) = self.compile_branch(else_bb, false_expr, merge_bb);
+
+        self.builder.position_at_end(merge_bb);
+        let phi = self.builder.build_phi(true_val.get_type(), "iftmp");
+        phi.add_incoming(&[(&true_val, then_end), (&false_val, else_end)]);
+
+        phi.as_basic_value()
     }
 
-    /// Compiles the specified `AST` in the given `Context` and using the specified `Builder`, `PassManager`, and `Module`.
+    /// Compiles a match, returns its value or `None` if it's `void`.
     ///
-    /// This call either `compile_fn(...)` if it's a FunctionNode or,
-    /// calls `compile_ext(...)` if it's a ExternNode
-    pub fn compile_ast(
-        context: &'ctx Context,
-        builder: &'a Builder<'ctx>,
-        pass_manager: &'a PassManager<FunctionValue<'ctx>>,
-        module: &'a Module<'ctx>,
-        ast: &[ASTNode],
-    ) -> GeneratedCode<'ctx> {
-        let mut result = vec![];
-
-        for node in ast {
-            match node {
-                ASTNode::FunctionNode(fun) => {
-                    let mut compiler = CodeGen {
-                        context,
-                        builder,
-                        fpm: pass_manager,
-                        module,
-                        function: fun,
-                        fn_value_opt: None,
-                        variables: HashMap::new(),
-                    };
+    /// It's lowered to a `switch` if every arm is a constant, a variant whose
+    /// payload is only bound, a binding or a wildcard, without guard.
+    /// Otherwise the arms are tried one after the other.
+    fn compile_match(
+        &mut self,
+        scrutinee: &Expression,
+        arms: &[MatchArm],
+        ty: &Ty,
+    ) -> Option<BasicValueEnum<'ctx>> {
+        // the scrutinee is stored, so the patterns can test its fields
+        let value = self.compile_expr(scrutinee);
+        let ptr = self.create_entry_block_alloca(self.value_type(&value.ty), "scrutinee");
+        self.builder.build_store(ptr, value.val);
+        let place = Place { ptr, ty: value.ty };
+
+        let function = self.fn_value();
+        let arm_bbs: Vec<_> = arms
+            .iter()
+            .map(|_| self.context.append_basic_block(function, "match.arm"))
+            .collect();
+        // the type checker ensured every value matches an arm
+        let nomatch_bb = self.context.append_basic_block(function, "match.nomatch");
+        let merge_bb = self.context.append_basic_block(function, "match.end");
+
+        let switched = is_switchable(arms, &place.ty);
+        if switched {
+            self.build_match_switch(arms, &place, &arm_bbs, nomatch_bb);
+        } else {
+            let first_bb = arm_bbs.first().copied().unwrap_or(nomatch_bb);
+            self.builder.build_unconditional_branch(first_bb);
+        }
+
+        let mut incoming = Vec::new();
+        for (i, arm) in arms.iter().enumerate() {
+            let next_bb = arm_bbs.get(i + 1).copied().unwrap_or(nomatch_bb);
+            self.builder.position_at_end(arm_bbs[i]);
+            self.scopes.push(HashMap::new());
+
+            // the switch already tested the values
+            self.compile_pattern(&arm.pattern, &place, (!switched).then_some(next_bb));
+            if let Some(guard) = &arm.guard {
+                let cond = self.compile_condition(guard);
+                let body_bb = self.context.append_basic_block(function, "match.body");
+                self.builder
+                    .build_conditional_branch(cond, body_bb, next_bb);
+                self.builder.position_at_end(body_bb);
+            }
+
+            let value = match &arm.body {
+                ArmBody::Expr(Expression {
+                    expr: Expr::CallExpr { fn_op, args },
+                    ..
+                }) => self.compile_call(fn_op, args).map(|value| value.val),
+                ArmBody::Expr(expr) => Some(self.compile_expr(expr).val),
+                ArmBody::Block(block) => {
+                    self.compile_block(block);
+                    None
+                }
+            };
+            self.scopes.pop();
+
+            let end = self.current_block();
+            if end.get_terminator().is_none() && self.is_reachable(end) {
+                incoming.extend(value.map(|value| (value, end)));
+            }
+            self.branch_to(merge_bb);
+        }
+
+        self.builder.position_at_end(nomatch_bb);
+        self.builder.build_unreachable();
+
+        self.builder.position_at_end(merge_bb);
+        let basic_type = self.basic_type(ty)?;
+        // every arm may never complete
+        if incoming.is_empty() {
+            return Some(basic_type.const_zero());
+        }
+        let phi = self.builder.build_phi(basic_type, "matchtmp");
+        for (value, bb) in &incoming {
+            phi.add_incoming(&[(value, *bb)]);
+        }
+        Some(phi.as_basic_value())
+    }
+
+    /// Builds the `switch` on the value, or on the tag of an enum with
+    /// payloads, jumping to the block of the first arm that matches it.
+    fn build_match_switch(
+        &mut self,
+        arms: &[MatchArm],
+        place: &Place<'ctx>,
+        arm_bbs: &[BasicBlock<'ctx>],
+        nomatch_bb: BasicBlock<'ctx>,
+    ) {
+        let switched = self.load_tag(place);
+
+        let mut cases = Vec::new();
+        let mut matched = HashSet::new();
+        let mut default_bb = nomatch_bb;
+        for (arm, &bb) in arms.iter().zip(arm_bbs) {
+            // the arms after a binding or a wildcard are unreachable, and so
+            // are the cases already matched
+            let (value, key) = match &arm.pattern.pat {
+                Pat::Wildcard | Pat::Binding(_) => {
+                    default_bb = bb;
+                    break;
+                }
+                Pat::Literal(lit) => (self.compile_expr(lit).val.into_int_value(), lit_value(lit)),
+                Pat::Variant { path, .. } => {
+                    let (enum_ty, index) = self.results.variant(path).unwrap();
+                    let enum_ty = enum_ty.clone();
+                    (self.compile_discriminant(&enum_ty, index), index as u128)
+                }
+                Pat::Range { .. } | Pat::Struct { .. } => {
+                    unreachable!("the pattern isn't a constant")
+                }
+            };
+            if matched.insert(key) {
+                cases.push((value, bb));
+            }
+        }
+        self.builder.build_switch(switched, default_bb, &cases);
+    }
+
+    /// Loads the value of the integer, of the bool or of the enum without
+    /// payloads at `place`, or the tag of the enum with payloads.
+    fn load_tag(&self, place: &Place<'ctx>) -> IntValue<'ctx> {
+        if !place.ty.is_enum() || !self.results.enum_def(&place.ty).has_payload() {
+            return self
+                .builder
+                .build_load(self.value_type(&place.ty), place.ptr, "value")
+                .into_int_value();
+        }
+        let def = self.results.enum_def(&place.ty);
+        let tag_ptr = self
+            .builder
+            .build_struct_gep(
+                self.enum_type(&place.ty).into_struct_type(),
+                place.ptr,
+                0,
+                "tag",
+            )
+            .unwrap();
+        self.builder
+            .build_load(self.value_type(&def.repr), tag_ptr, "tag")
+            .into_int_value()
+    }
 
-                    result.push(compiler.compile_fn()?);
+    /// Returns the discriminant of the variant of the enum, of the type of its
+    /// tag.
+    fn compile_discriminant(&self, ty: &Ty, index: usize) -> IntValue<'ctx> {
+        let def = self.results.enum_def(ty);
+        let discriminant = def.variants[index].discriminant;
+        self.compile_int_lit(discriminant.unsigned_abs(), discriminant < 0, &def.repr)
+            .into_int_value()
+    }
+
+    /// Compiles the test of the pattern on the value at `place`, the bindings
+    /// are declared in the innermost scope. The execution continues if the
+    /// value matches, it jumps to `fail_bb` otherwise. Without `fail_bb` the
+    /// value is known to match, only the bindings are compiled.
+    fn compile_pattern(
+        &mut self,
+        pattern: &Pattern,
+        place: &Place<'ctx>,
+        fail_bb: Option<BasicBlock<'ctx>>,
+    ) {
+        match &pattern.pat {
+            Pat::Wildcard => {}
+            Pat::Binding(name) => {
+                let value = self
+                    .builder
+                    .build_load(self.value_type(&place.ty), place.ptr, name);
+                let ptr = self.declare_local(name, place.ty.clone());
+                self.builder.build_store(ptr, value);
+            }
+            Pat::Literal(lit) => {
+                let Some(fail_bb) = fail_bb else {
+                    return;
+                };
+                let value = self.load_tag(place);
+                let lit = self.compile_expr(lit).val.into_int_value();
+                let cond = self
+                    .builder
+                    .build_int_compare(IntPredicate::EQ, value, lit, "pateq");
+                self.test_pattern(cond, fail_bb);
+            }
+            Pat::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let Some(fail_bb) = fail_bb else {
+                    return;
+                };
+                let value = self.load_tag(place);
+                let start = self.compile_expr(start).val.into_int_value();
+                let end = self.compile_expr(end).val.into_int_value();
+                let (ge, le, lt) = if place.ty.is_signed() {
+                    (IntPredicate::SGE, IntPredicate::SLE, IntPredicate::SLT)
+                } else {
+                    (IntPredicate::UGE, IntPredicate::ULE, IntPredicate::ULT)
+                };
+                let lower = self.builder.build_int_compare(ge, value, start, "patge");
+                let upper = self.builder.build_int_compare(
+                    if *inclusive { le } else { lt },
+                    value,
+                    end,
+                    "patle",
+                );
+                let cond = self.builder.build_and(lower, upper, "patrange");
+                self.test_pattern(cond, fail_bb);
+            }
+            Pat::Variant { path, fields } => {
+                let (enum_ty, index) = self.results.variant(path).unwrap();
+                let enum_ty = enum_ty.clone();
+                if let Some(fail_bb) = fail_bb {
+                    let tag = self.load_tag(place);
+                    let discriminant = self.compile_discriminant(&enum_ty, index);
+                    let cond = self.builder.build_int_compare(
+                        IntPredicate::EQ,
+                        tag,
+                        discriminant,
+                        "pattag",
+                    );
+                    self.test_pattern(cond, fail_bb);
+                }
+
+                let fields = fields.as_deref().unwrap_or_default();
+                if fields.is_empty() {
+                    return;
+                }
+                let variant = self.results.enum_def(&enum_ty).variants[index].clone();
+                let payload_type = self.payload_type(&variant);
+                let storage = self
+                    .builder
+                    .build_struct_gep(
+                        self.enum_type(&enum_ty).into_struct_type(),
+                        place.ptr,
+                        1,
+                        "storage",
+                    )
+                    .unwrap();
+                let payload = self.builder.build_pointer_cast(
+                    storage,
+                    payload_type.ptr_type(AddressSpace::default()),
+                    "payload",
+                );
+                for (i, (field, ty)) in fields.iter().zip(variant.fields).enumerate() {
+                    let ptr = self
+                        .builder
+                        .build_struct_gep(payload_type, payload, i as u32, "field")
+                        .unwrap();
+                    self.compile_pattern(field, &Place { ptr, ty }, fail_bb);
+                }
+            }
+            Pat::Struct { fields, .. } => {
+                for field in fields {
+                    let field_place = self.field_place(place.ptr, &place.ty, &field.name);
+                    self.compile_pattern(&field.pattern, &field_place, fail_bb);
                 }
             }
         }
+    }
 
-        Ok(result)
+    /// Continues in a new block if the condition is true, jumps to `fail_bb`
+    /// otherwise.
+    fn test_pattern(&self, cond: IntValue<'ctx>, fail_bb: BasicBlock<'ctx>) {
+        let next_bb = self.context.append_basic_block(self.fn_value(), "pat.next");
+        self.builder
+            .build_conditional_branch(cond, next_bb, fail_bb);
+        self.builder.position_at_end(next_bb);
     }
+
+    /// Compiles the expression of a branch of an if-else expression in `bb`,
+    /// returns its value and the block it ends in.
+    fn compile_branch(
+        &mut self,
+        bb: BasicBlock<'ctx>,
+        expr: &Expression,
+        merge_bb: BasicBlock<'ctx>,
+    ) -> (BasicValueEnum<'ctx>, BasicBlock<'ctx>) {
+        self.builder.position_at_end(bb);
+        let value = self.compile_expr(expr);
+        let end = self.current_block();
+        self.builder.build_unconditional_branch(merge_bb);
+        (value.val, end)
+    }
+}
+
+/// Can the match on a value of type `ty` be lowered to a `switch`? See
+/// `CodeGen::compile_match`.
+fn is_switchable(arms: &[MatchArm], ty: &Ty) -> bool {
+    (ty.is_int() || ty.is_bool() || ty.is_enum())
+        && arms.iter().all(|arm| {
+            arm.guard.is_none()
+                && match &arm.pattern.pat {
+                    Pat::Wildcard | Pat::Binding(_) | Pat::Literal(_) => true,
+                    Pat::Variant { fields, .. } => fields
+                        .iter()
+                        .flatten()
+                        .all(|field| matches!(field.pat, Pat::Wildcard | Pat::Binding(_))),
+                    Pat::Range { .. } | Pat::Struct { .. } => false,
+                }
+        })
+}
+
+/// Returns the value of the literal of a pattern, negative values wrap around.
+fn lit_value(lit: &Expression) -> u128 {
+    match &lit.expr {
+        Expr::IntLitExpr { value, .. } => *value,
+        Expr::UnaryExpr { expr: inner, .. } => lit_value(inner).wrapping_neg(),
+        Expr::CharLitExpr(c) => *c as u128,
+        Expr::BoolLitExpr(b) => *b as u128,
+        _ => unreachable!("patterns only have integer, char and bool literals"),
+    }
+}
+
+/// Returns the size and the alignment of a C struct with fields of the given
+/// sizes and alignments, in order.
+fn struct_layout(fields: impl IntoIterator<Item = (u64, u64)>) -> (u64, u64) {
+    let (size, align) =
+        fields
+            .into_iter()
+            .fold((0u64, 1), |(offset, align), (field_size, field_align)| {
+                (
+                    offset.next_multiple_of(field_align) + field_size,
+                    align.max(field_align),
+                )
+            });
+    (size.next_multiple_of(align), align)
 }
@@ -1,4 +1,3 @@
 //! Zom crate responsible for the generation of the LLVM IR.
 
-// pub mod gen;
-// This is module is disabled, it will be re-enable when the codegeneration will be done. #25
+pub mod gen;
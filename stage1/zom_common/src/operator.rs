@@ -1,20 +1,36 @@
 use std::fmt::{self, Display};
 use std::str::FromStr;
 
+use serde::Serialize;
+
 /// Ampersand, `&`
 pub const OP_AMPERSAND: &str = "&";
+/// Ampersand2, `&&`
+pub const OP_AMPERSAND2: &str = "&&";
+/// AmpersandEqual, `&=`
+pub const OP_AMPERSANDEQUAL: &str = "&=";
 /// Asterisk, `*`
 pub const OP_ASTERISK: &str = "*";
+/// AsteriskEqual, `*=`
+pub const OP_ASTERISKEQUAL: &str = "*=";
 /// Caret, `^`
 pub const OP_CARET: &str = "^";
+/// CaretEqual, `^=`
+pub const OP_CARETEQUAL: &str = "^=";
 /// Dot, `.`
 pub const OP_DOT: &str = ".";
 /// DotAsterisk, `.*`
 pub const OP_DOTASTERISK: &str = ".*";
+/// Dot2, `..`
+pub const OP_DOT2: &str = "..";
+/// Dot2Equal, `..=`
+pub const OP_DOT2EQUAL: &str = "..=";
 /// Equal, `=`,
 pub const OP_EQUAL: &str = "=";
 /// Equal2, `==`
 pub const OP_EQUAL2: &str = "==";
+/// EqualRArrow, `=>`
+pub const OP_EQUALRARROW: &str = "=>";
 /// Exclamationmark, `!`
 pub const OP_EXCLAMATIONMARK: &str = "!";
 /// ExclamationmarkEqual, `!=`
@@ -23,73 +39,126 @@ pub const OP_EXCLAMATIONMARKEQUAL: &str = "!=";
 pub const OP_LARROW: &str = "<";
 /// LArrow2, `<<`
 pub const OP_LARROW2: &str = "<<";
+/// LArrow2Equal, `<<=`
+pub const OP_LARROW2EQUAL: &str = "<<=";
 /// LArrowEqual, `<=`
 pub const OP_LARROWEQUAL: &str = "<=";
 /// Minus, `-`
 pub const OP_MINUS: &str = "-";
+/// MinusEqual, `-=`
+pub const OP_MINUSEQUAL: &str = "-=";
+/// MinusRArrow, `->`
+pub const OP_MINUSRARROW: &str = "->";
 /// Percent, `%`
 pub const OP_PERCENT: &str = "%";
+/// PercentEqual, `%=`
+pub const OP_PERCENTEQUAL: &str = "%=";
+/// Pipe, `|`
+pub const OP_PIPE: &str = "|";
 /// Pipe2, `||`
 pub const OP_PIPE2: &str = "||";
+/// PipeEqual, `|=`
+pub const OP_PIPEEQUAL: &str = "|=";
 /// Plus, `+`
 pub const OP_PLUS: &str = "+";
+/// PlusEqual, `+=`
+pub const OP_PLUSEQUAL: &str = "+=";
 /// RArrow, `>`
 pub const OP_RARROW: &str = ">";
 /// RArrow2, `>>`
 pub const OP_RARROW2: &str = ">>";
+/// RArrow2Equal, `>>=`
+pub const OP_RARROW2EQUAL: &str = ">>=";
 /// RArrowEqual, `>=`
 pub const OP_RARROWEQUAL: &str = ">=";
 /// Slash, `/`
 pub const OP_SLASH: &str = "/";
+/// SlashEqual, `/=`
+pub const OP_SLASHEQUAL: &str = "/=";
+/// Tilde, `~`
+pub const OP_TILDE: &str = "~";
 
 /// Maximum operator lenght
-pub const OPERATOR_LENGHT: usize = 2;
+pub const OPERATOR_LENGHT: usize = 3;
 /// List of unique operators (contains no aliases)
-
-pub const OPERATORS: [&str; 20] = [
+pub const OPERATORS: [&str; 37] = [
     OP_AMPERSAND,
+    OP_AMPERSAND2,
+    OP_AMPERSANDEQUAL,
     OP_ASTERISK,
+    OP_ASTERISKEQUAL,
     OP_CARET,
+    OP_CARETEQUAL,
     OP_DOT,
     OP_DOTASTERISK,
+    OP_DOT2,
+    OP_DOT2EQUAL,
     OP_EQUAL,
     OP_EQUAL2,
+    OP_EQUALRARROW,
     OP_EXCLAMATIONMARK,
     OP_EXCLAMATIONMARKEQUAL,
     OP_LARROW,
     OP_LARROW2,
+    OP_LARROW2EQUAL,
     OP_LARROWEQUAL,
     OP_MINUS,
+    OP_MINUSEQUAL,
+    OP_MINUSRARROW,
     OP_PERCENT,
+    OP_PERCENTEQUAL,
+    OP_PIPE,
     OP_PIPE2,
+    OP_PIPEEQUAL,
     OP_PLUS,
+    OP_PLUSEQUAL,
     OP_RARROW,
     OP_RARROW2,
+    OP_RARROW2EQUAL,
     OP_RARROWEQUAL,
     OP_SLASH,
+    OP_SLASHEQUAL,
+    OP_TILDE,
 ];
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize)]
 pub enum Operator {
     Ampersand,
+    Ampersand2,
+    AmpersandEqual,
     Asterisk,
+    AsteriskEqual,
     Caret,
+    CaretEqual,
     Dot,
     DotAsterisk,
+    Dot2,
+    Dot2Equal,
     Equal,
     Equal2,
+    EqualRArrow,
     Exclamationmark,
     ExclamationmarkEqual,
     LArrow,
     LArrow2,
+    LArrow2Equal,
     LArrowEqual,
     Minus,
+    MinusEqual,
+    MinusRArrow,
     Percent,
+    PercentEqual,
+    Pipe,
     Pipe2,
+    PipeEqual,
     Plus,
+    PlusEqual,
     RArrow,
     RArrow2,
+    RArrow2Equal,
     RArrowEqual,
     Slash,
+    SlashEqual,
+    Tilde,
 }
 
 impl Display for Operator {
@@ -97,25 +166,42 @@ impl Display for Operator {
         use self::Operator::*;
         let op = match *self {
             Ampersand => OP_AMPERSAND,
+            Ampersand2 => OP_AMPERSAND2,
+            AmpersandEqual => OP_AMPERSANDEQUAL,
             Asterisk => OP_ASTERISK,
+            AsteriskEqual => OP_ASTERISKEQUAL,
             Caret => OP_CARET,
+            CaretEqual => OP_CARETEQUAL,
             Dot => OP_DOT,
             DotAsterisk => OP_DOTASTERISK,
+            Dot2 => OP_DOT2,
+            Dot2Equal => OP_DOT2EQUAL,
             Equal => OP_EQUAL,
             Equal2 => OP_EQUAL2,
+            EqualRArrow => OP_EQUALRARROW,
             Exclamationmark => OP_EXCLAMATIONMARK,
             ExclamationmarkEqual => OP_EXCLAMATIONMARKEQUAL,
             LArrow => OP_LARROW,
             LArrow2 => OP_LARROW2,
+            LArrow2Equal => OP_LARROW2EQUAL,
             LArrowEqual => OP_LARROWEQUAL,
             Minus => OP_MINUS,
+            MinusEqual => OP_MINUSEQUAL,
+            MinusRArrow => OP_MINUSRARROW,
             Percent => OP_PERCENT,
+            PercentEqual => OP_PERCENTEQUAL,
+            Pipe => OP_PIPE,
             Pipe2 => OP_PIPE2,
+            PipeEqual => OP_PIPEEQUAL,
             Plus => OP_PLUS,
+            PlusEqual => OP_PLUSEQUAL,
             RArrow => OP_RARROW,
             RArrow2 => OP_RARROW2,
+            RArrow2Equal => OP_RARROW2EQUAL,
             RArrowEqual => OP_RARROWEQUAL,
             Slash => OP_SLASH,
+            SlashEqual => OP_SLASHEQUAL,
+            Tilde => OP_TILDE,
         };
         write!(f, "{op}")
     }
@@ -128,46 +214,67 @@ impl FromStr for Operator {
         use self::Operator::*;
         Ok(match s {
             OP_AMPERSAND => Ampersand,
+            OP_AMPERSAND2 => Ampersand2,
+            OP_AMPERSANDEQUAL => AmpersandEqual,
             OP_ASTERISK => Asterisk,
+            OP_ASTERISKEQUAL => AsteriskEqual,
             OP_CARET => Caret,
+            OP_CARETEQUAL => CaretEqual,
             OP_DOT => Dot,
+            OP_DOT2 => Dot2,
+            OP_DOT2EQUAL => Dot2Equal,
             OP_EQUAL => Equal,
             OP_EQUAL2 => Equal2,
+            OP_EQUALRARROW => EqualRArrow,
             OP_EXCLAMATIONMARK => Exclamationmark,
             OP_EXCLAMATIONMARKEQUAL => ExclamationmarkEqual,
             OP_LARROW => LArrow,
             OP_LARROW2 => LArrow2,
+            OP_LARROW2EQUAL => LArrow2Equal,
             OP_LARROWEQUAL => LArrowEqual,
             OP_MINUS => Minus,
+            OP_MINUSEQUAL => MinusEqual,
+            OP_MINUSRARROW => MinusRArrow,
             OP_PERCENT => Percent,
+            OP_PERCENTEQUAL => PercentEqual,
+            OP_PIPE => Pipe,
             OP_PIPE2 => Pipe2,
+            OP_PIPEEQUAL => PipeEqual,
             OP_PLUS => Plus,
+            OP_PLUSEQUAL => PlusEqual,
             OP_RARROW => RArrow,
             OP_RARROW2 => RArrow2,
+            OP_RARROW2EQUAL => RArrow2Equal,
             OP_RARROWEQUAL => RArrowEqual,
             OP_SLASH => Slash,
+            OP_SLASHEQUAL => SlashEqual,
+            OP_TILDE => Tilde,
             op => return Err(format!("unknown binary operator `{}`", op)),
         })
     }
 }
 
 /// Operator Precedence Value for Unary Dereference
-pub const PR_DEREFERENCE: u16 = 9;
-/// Operator Precedence Value for Unary Operations: AddressOf, Negation, Not, but not Dereference
-pub const PR_UNARY: u16 = 8;
+pub const PR_DEREFERENCE: u16 = 11;
+/// Operator Precedence Value for Unary Operations: AddressOf, Negation, Not, BitwiseNot, but not Dereference
+pub const PR_UNARY: u16 = 10;
 /// Operator Precedence Value for Mul Div Rem
-pub const PR_MUL_DIV_REM: u16 = 7;
+pub const PR_MUL_DIV_REM: u16 = 9;
 /// Operator Precedence Value for Add Sub
-pub const PR_ADD_SUB: u16 = 6;
+pub const PR_ADD_SUB: u16 = 8;
 /// Operator Precedence Value for Right and Left shifts
-pub const PR_SHIFT: u16 = 5;
+pub const PR_SHIFT: u16 = 7;
 /// Operator Precedence Value for Less than, Greater than, Less than or equal to and greater than or equal to
-pub const PR_COMP: u16 = 4;
+pub const PR_COMP: u16 = 6;
 /// Operator Precedence Value for Eq Ne
-pub const PR_COMP_EQ_NE: u16 = 3;
+pub const PR_COMP_EQ_NE: u16 = 5;
 /// Operator Precedence Value for And
-pub const PR_AND: u16 = 2;
+pub const PR_AND: u16 = 4;
 /// Operator Precedence Value for Xor
-pub const PR_XOR: u16 = 1;
+pub const PR_XOR: u16 = 3;
 /// Operator Precedence Value for Or
-pub const PR_OR: u16 = 0;
+pub const PR_OR: u16 = 2;
+/// Operator Precedence Value for Logical And
+pub const PR_LOGICAL_AND: u16 = 1;
+/// Operator Precedence Value for Logical Or
+pub const PR_LOGICAL_OR: u16 = 0;
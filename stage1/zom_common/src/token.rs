@@ -7,6 +7,8 @@ use std::{
     ops::Range,
 };
 
+use serde::Serialize;
+
 pub use TokenType::*;
 
 use crate::operator::Operator;
@@ -85,20 +87,82 @@ pub const KW_IMPORT: &str = "import";
 /// const for the keyword `as`
 pub const KW_AS: &str = "as";
 
-#[derive(Debug, PartialEq, Clone)]
+/// const for the keyword `in`
+pub const KW_IN: &str = "in";
+
+/// const for the keyword `by`
+pub const KW_BY: &str = "by";
+
+/// Every keyword, none of them can be an identifier.
+pub const KEYWORDS: &[&str] = &[
+    KW_FN,
+    KW_EXTERN,
+    KW_VAR,
+    KW_CONST,
+    KW_STRUCT,
+    KW_ENUM,
+    KW_RETURN,
+    KW_IF,
+    KW_ELSE,
+    KW_WHILE,
+    KW_FOR,
+    KW_PUB,
+    KW_ASYNC,
+    KW_AWAIT,
+    KW_MATCH,
+    KW_IMPL,
+    KW_TRUE,
+    KW_FALSE,
+    KW_UNDEFINED,
+    KW_BREAK,
+    KW_CONTINUE,
+    KW_PACKAGE,
+    KW_IMPORT,
+    KW_AS,
+    KW_IN,
+    KW_BY,
+];
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct Token {
     /// `tt` means token type.
     pub tt: TokenType,
     pub span: Range<usize>,
+    /// The trivia before the token, that isn't on the line of the previous
+    /// token, only kept by the lossless lexing.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub leading: Vec<Trivia>,
+    /// The trivia after the token, until the end of its line, only kept by
+    /// the lossless lexing.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trailing: Vec<Trivia>,
 }
 
 impl Token {
     pub fn new(tt: TokenType, span: Range<usize>) -> Token {
-        Token { tt, span }
+        Token {
+            tt,
+            span,
+            leading: Vec::new(),
+            trailing: Vec::new(),
+        }
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// Code that isn't part of a token, e.g: the whitespaces and the comments.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub enum TriviaKind {
+    Whitespace,
+    Comment,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum TokenType {
     // Operators
     /// Operators, should only be an OP_** constant.
@@ -122,8 +186,8 @@ pub enum TokenType {
     At,        // ` @ `
 
     // Literals
-    Int(u64),
-    Float(f32),
+    Int(IntLit),
+    Float(f64),
     Str(String),
     Char(char),
 
@@ -152,12 +216,84 @@ pub enum TokenType {
     Package,
     Import,
     As,
+    In,
+    By,
 
     // Identifier
     Ident(String), // Identifier is a alphanumeric with `_` string
 
+    // Doc comments, with the text after their marker
+    DocComment(String),      // `///`, documents the item after it
+    InnerDocComment(String), // `//!`, documents the file it's in
+
+    // Placeholder of a token that couldn't be lexed, its error is already
+    // reported
+    Invalid,
+
     EOF,
 }
+/// An integer literal, e.g: `0xFF_u8`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct IntLit {
+    pub value: u128,
+    pub radix: Radix,
+    /// Name of the type after the digits, e.g: `u8`, it may not be a type.
+    pub suffix: Option<String>,
+}
+
+impl IntLit {
+    /// A decimal integer literal without suffix.
+    pub fn new(value: u128) -> IntLit {
+        IntLit {
+            value,
+            radix: Radix::Decimal,
+            suffix: None,
+        }
+    }
+}
+
+/// Radix of an integer literal, given by its prefix.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub enum Radix {
+    /// `0b`
+    Binary,
+    /// `0o`
+    Octal,
+    Decimal,
+    /// `0x`
+    Hexadecimal,
+}
+
+impl Radix {
+    pub fn value(self) -> u32 {
+        match self {
+            Radix::Binary => 2,
+            Radix::Octal => 8,
+            Radix::Decimal => 10,
+            Radix::Hexadecimal => 16,
+        }
+    }
+
+    /// Prefix of the literals, empty for decimal.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Radix::Binary => "0b",
+            Radix::Octal => "0o",
+            Radix::Decimal => "",
+            Radix::Hexadecimal => "0x",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Radix::Binary => "binary",
+            Radix::Octal => "octal",
+            Radix::Decimal => "decimal",
+            Radix::Hexadecimal => "hexadecimal",
+        }
+    }
+}
+
 impl TokenType {
     pub fn format_toks(tokens: Vec<TokenType>) -> String {
         let mut s = "".to_owned();
@@ -220,9 +356,15 @@ impl Display for TokenType {
             Package => write!(f, "keyword `package`"),
             Import => write!(f, "keyword `import`"),
             As => write!(f, "keyword `as`"),
+            In => write!(f, "keyword `in`"),
+            By => write!(f, "keyword `by`"),
 
             Ident(name) => write!(f, "identifier {name}"),
 
+            DocComment(_) | InnerDocComment(_) => write!(f, "doc comment"),
+
+            Invalid => write!(f, "invalid token"),
+
             EOF => write!(f, "End of File"),
         }
     }
@@ -1,70 +1,71 @@
 use std::path::Path;
 
 use inkwell::{
+    memory_buffer::MemoryBuffer,
     module::Module,
-    targets::{
-        CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
-    },
+    passes::{PassManager, PassManagerBuilder},
+    support::LLVMString,
+    targets::{FileType, TargetMachine},
     OptimizationLevel,
 };
 
+use crate::target::{TargetError, TargetOptions};
+
+/// Emits the machine code of a module, for a target.
 pub struct Compiler {
-    triple: TargetTriple,
-    config: InitializationConfig,
+    machine: TargetMachine,
 }
 
 impl Compiler {
-    pub fn compile(
-        triple: TargetTriple,
-        config: InitializationConfig,
-        module: Module,
-        output: &Path,
-    ) -> Result<(), inkwell::support::LLVMString> {
-        let compiler = Compiler { triple, config };
+    pub fn new(options: &TargetOptions, opt_level: OptimizationLevel) -> Result<Self, TargetError> {
+        Ok(Compiler {
+            machine: options.create_target_machine(opt_level)?,
+        })
+    }
 
-        Target::initialize_all(&compiler.config);
+    /// Width of a pointer of the target, in bits.
+    pub fn pointer_width(&self) -> u32 {
+        self.machine.get_target_data().get_pointer_byte_size(None) * 8
+    }
 
-        let target = Target::from_triple(&compiler.triple)
-            .expect("Error, while trying to get the current target from the target triple.");
+    /// Target triple of the machine code, `None` if it's the host.
+    pub fn cross_triple(&self) -> Option<String> {
+        let triple = self.machine.get_triple();
+        (triple != TargetMachine::get_default_triple())
+            .then(|| triple.as_str().to_string_lossy().into_owned())
+    }
 
-        let target_machine = target
-            .create_target_machine(
-                &compiler.triple,
-                TargetMachine::get_host_cpu_name()
-                    .to_str()
-                    .expect("Things went wrong"), // if it doesn't work, try with "generic"
-                TargetMachine::get_host_cpu_features()
-                    .to_str()
-                    .expect("Things went wrong"),
-                OptimizationLevel::Default,
-                RelocMode::Default,
-                CodeModel::Default,
-            )
-            .unwrap();
+    /// Sets the triple and the data layout of the target on the module, it
+    /// should be done before optimizing it.
+    pub fn prepare(&self, module: &Module) {
+        module.set_triple(&self.machine.get_triple());
+        module.set_data_layout(&self.machine.get_target_data().get_data_layout());
+    }
 
-        module.set_triple(&compiler.triple);
+    /// Writes the module as an object file.
+    pub fn compile(&self, module: &Module, output: &Path) -> Result<(), LLVMString> {
+        self.prepare(module);
+        self.machine.write_to_file(module, FileType::Object, output)
+    }
 
-        target_machine.write_to_file(&module, FileType::Object, output)
+    /// Emits the module as an object file or as assembly, in memory so it can
+    /// be written to a file or to stdout.
+    pub fn emit(&self, module: &Module, file_type: FileType) -> Result<MemoryBuffer, LLVMString> {
+        self.prepare(module);
+        self.machine.write_to_memory_buffer(module, file_type)
     }
 
-    pub fn compile_default(
-        module: Module,
-        output: &Path,
-    ) -> Result<(), inkwell::support::LLVMString> {
-        let init_config = InitializationConfig {
-            info: true,
-            machine_code: true,
-            asm_parser: true,
-            asm_printer: true,
-            base: true,
-            disassembler: false,
-        };
+    /// Runs the LLVM optimization passes of the given level on the module.
+    pub fn optimize(module: &Module, opt_level: OptimizationLevel) {
+        if opt_level == OptimizationLevel::None {
+            return;
+        }
+
+        let pm_builder = PassManagerBuilder::create();
+        pm_builder.set_optimization_level(opt_level);
 
-        Self::compile(
-            TargetMachine::get_default_triple(),
-            init_config,
-            module,
-            output,
-        )
+        let pm = PassManager::create(());
+        pm_builder.populate_module_pass_manager(&pm);
+        pm.run_on(module);
     }
 }
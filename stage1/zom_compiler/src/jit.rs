@@ -0,0 +1,136 @@
+//! Module responsible for executing a LLVM module in-process, with the JIT of
+//! LLVM, without emitting an object file or linking.
+
+use std::{
+    ffi::{c_char, c_void, CString},
+    fmt, ptr,
+};
+
+use inkwell::{
+    execution_engine::FunctionLookupError,
+    module::Module,
+    support::{load_library_permanently, LLVMString},
+    targets::{InitializationConfig, Target},
+    OptimizationLevel,
+};
+
+/// Signature of the C-ABI `main` entry point generated by the code generation.
+type MainFn = unsafe extern "C" fn(i32, *const *const c_char) -> i32;
+
+extern "C" {
+    // part of the LLVM C API but not exposed by inkwell.
+    fn LLVMSearchForAddressOfSymbol(symbol_name: *const c_char) -> *mut c_void;
+}
+
+#[derive(Debug)]
+pub enum JitError {
+    /// The native target couldn't be initialized.
+    Target(String),
+    /// The execution engine couldn't be created.
+    Engine(LLVMString),
+    /// The module doesn't contain a `main` entry point.
+    NoMain(FunctionLookupError),
+    /// Extern functions whose symbol wasn't found in the process or in the
+    /// loaded libraries.
+    Unresolved(Vec<String>),
+}
+
+impl fmt::Display for JitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JitError::Target(err) => write!(f, "couldn't initialize the native target, {err}"),
+            JitError::Engine(err) => write!(f, "couldn't create the JIT, {err}"),
+            JitError::NoMain(err) => write!(f, "couldn't find the `main` function, {err}"),
+            JitError::Unresolved(symbols) => write!(
+                f,
+                "couldn't resolve the extern function(s) `{}`, is a library missing?",
+                symbols.join("`, `")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for JitError {}
+
+/// Makes the symbols of a library, given by the library string of an
+/// `extern "lib" fn` declaration, available to the JIT.
+///
+/// The C library is already loaded in the process, so `"C"` and `"c"` are
+/// skipped. Returns false if the library couldn't be loaded.
+pub fn load_extern_lib(lib: &str) -> bool {
+    if lib.eq_ignore_ascii_case("c") || lib.is_empty() {
+        return true;
+    }
+
+    let candidates = if lib.contains(['/', '\\']) || lib.contains('.') {
+        vec![lib.to_owned()]
+    } else if cfg!(target_os = "windows") {
+        vec![format!("{lib}.dll")]
+    } else if cfg!(target_os = "macos") {
+        vec![format!("lib{lib}.dylib")]
+    } else {
+        // `libfoo.so` may be a linker script, e.g: the libm of glibc, so the
+        // versioned shared objects are tried too.
+        let mut candidates = vec![format!("lib{lib}.so")];
+        candidates.extend((0..10).map(|version| format!("lib{lib}.so.{version}")));
+        candidates
+    };
+
+    // `load_library_permanently` returns true on error
+    candidates
+        .iter()
+        .any(|file| !load_library_permanently(file))
+}
+
+/// Runs the C-ABI `main` of the module, generated by the code generation, with
+/// the given program arguments, the first one being the program name.
+///
+/// The extern functions are resolved against the symbols of the process, like
+/// the ones of the C library. Returns the exit code of the program.
+pub fn run_main(
+    module: &Module,
+    opt_level: OptimizationLevel,
+    args: &[CString],
+) -> Result<i32, JitError> {
+    Target::initialize_native(&InitializationConfig::default()).map_err(JitError::Target)?;
+
+    let engine = module
+        .create_jit_execution_engine(opt_level)
+        .map_err(JitError::Engine)?;
+
+    // an unresolved symbol would crash the JIT, so they are checked before
+    let unresolved: Vec<String> = module
+        .get_functions()
+        .filter(|function| function.count_basic_blocks() == 0)
+        .map(|function| function.get_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with("llvm.") && !symbol_exists(name))
+        .collect();
+    if !unresolved.is_empty() {
+        return Err(JitError::Unresolved(unresolved));
+    }
+
+    let argv: Vec<*const c_char> = args
+        .iter()
+        .map(|arg| arg.as_ptr())
+        .chain([ptr::null()])
+        .collect();
+
+    // SAFETY: the `main` entry point is generated with this exact signature,
+    // `argv` is null terminated and outlives the call.
+    unsafe {
+        let main = engine
+            .get_function::<MainFn>("main")
+            .map_err(JitError::NoMain)?;
+        Ok(main.call(args.len() as i32, argv.as_ptr()))
+    }
+}
+
+/// Is the symbol defined in the process or in a library loaded with
+/// `load_extern_lib`?
+fn symbol_exists(name: &str) -> bool {
+    let Ok(name) = CString::new(name) else {
+        return false;
+    };
+    // SAFETY: `name` is a valid null terminated string.
+    unsafe { !LLVMSearchForAddressOfSymbol(name.as_ptr()).is_null() }
+}
@@ -5,3 +5,7 @@
 pub mod target;
 
 pub mod compiler;
+
+pub mod jit;
+
+pub mod linker;
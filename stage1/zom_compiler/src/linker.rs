@@ -0,0 +1,364 @@
+//! Module responsible for linking object files into an executable, by calling
+//! the system linker, or into a static library, with the system archiver.
+
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::target::get_target_triple;
+
+/// C compiler drivers tried, in this order, when no linker is given.
+const DEFAULT_DRIVERS: [&str; 3] = ["cc", "gcc", "clang"];
+
+/// Directories where the C runtime startup files of the host are searched,
+/// when linking with a raw `ld`, after the multiarch one.
+const HOST_CRT_DIRS: [&str; 3] = ["/usr/lib64", "/usr/lib", "/lib64"];
+
+/// The C runtimes of Linux known when linking with a raw `ld`, by the
+/// architecture of the triple: its multiarch name and its dynamic loader.
+const LINUX_RUNTIMES: [(&str, &str, &str); 6] = [
+    ("x86_64", "x86_64-linux-gnu", "/lib64/ld-linux-x86-64.so.2"),
+    ("aarch64", "aarch64-linux-gnu", "/lib/ld-linux-aarch64.so.1"),
+    ("i386", "i386-linux-gnu", "/lib/ld-linux.so.2"),
+    ("i686", "i386-linux-gnu", "/lib/ld-linux.so.2"),
+    (
+        "riscv64",
+        "riscv64-linux-gnu",
+        "/lib/ld-linux-riscv64-lp64d.so.1",
+    ),
+    ("armv7", "arm-linux-gnueabihf", "/lib/ld-linux-armhf.so.3"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkerFlavor {
+    /// A C compiler driver, like `cc`, `gcc` or `clang`, it knows where the
+    /// C runtime is.
+    Cc,
+    /// A raw linker like `ld`, `ld.lld` or `mold`, the C runtime startup files
+    /// are passed explicitly.
+    Ld,
+}
+
+impl LinkerFlavor {
+    /// Guess the flavor of the linker by its file name.
+    pub fn from_path(path: &Path) -> LinkerFlavor {
+        let name = path
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        if name == "ld" || name.starts_with("ld.") || name.ends_with("-ld") || name == "mold" {
+            LinkerFlavor::Ld
+        } else {
+            LinkerFlavor::Cc
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LinkError {
+    /// No linker was found on the system, with the ones tried.
+    NotFound(Vec<String>),
+    /// The linker, or the archiver, couldn't be spawned.
+    Spawn(PathBuf, io::Error),
+    /// A file needed to link with a raw linker is missing.
+    MissingRuntime(&'static str),
+    /// The C runtime of the target isn't known, to link with a raw linker.
+    UnknownRuntime(String),
+    /// The linker, or the archiver, ran but failed.
+    Failed(PathBuf, Option<i32>),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::NotFound(tried) => write!(
+                f,
+                "no linker found, tried {}, use `--linker` to give one",
+                tried.join(", ")
+            ),
+            LinkError::Spawn(linker, err) => {
+                write!(f, "couldn't run `{}`, {}", linker.display(), err)
+            }
+            LinkError::MissingRuntime(file) => write!(
+                f,
+                "couldn't find `{file}` of the C runtime, use a C compiler (e.g: `cc`) as linker"
+            ),
+            LinkError::UnknownRuntime(triple) => write!(
+                f,
+                "the C runtime of `{triple}` isn't known, use a C compiler (e.g: `clang`) as linker"
+            ),
+            LinkError::Failed(linker, Some(code)) => {
+                write!(f, "`{}` failed with exit code {}", linker.display(), code)
+            }
+            LinkError::Failed(linker, None) => {
+                write!(f, "`{}` was terminated", linker.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+/// Options of a link, everything except the linker itself.
+#[derive(Debug, Clone, Default)]
+pub struct LinkOptions {
+    /// Object files to link.
+    pub objects: Vec<PathBuf>,
+    /// Libraries to link with, passed as `-l<lib>`.
+    pub libs: Vec<String>,
+    /// Files to link with as-is, e.g: static archives given by path.
+    pub link_files: Vec<PathBuf>,
+    /// Directories where the libraries are searched, passed as `-L<path>`.
+    pub lib_paths: Vec<PathBuf>,
+    /// Link a position dependent executable, needed when the objects aren't
+    /// position independent code.
+    pub no_pie: bool,
+    /// Target triple of the objects, `None` for the host.
+    pub target: Option<String>,
+}
+
+impl LinkOptions {
+    /// Adds the library of an `extern "lib" fn` declaration.
+    ///
+    /// The C library is always linked, so `"C"` and `"c"` add nothing. A
+    /// library given by path, like `"foo/libbar.a"`, is linked as-is, any
+    /// other name is linked as `-l<lib>`.
+    pub fn add_extern_lib(&mut self, lib: &str) {
+        if lib.eq_ignore_ascii_case("c") || lib.is_empty() {
+            return;
+        }
+
+        let path = Path::new(lib);
+        let is_file = path.components().count() > 1
+            || matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("a" | "so" | "o" | "lib" | "dylib")
+            );
+
+        if is_file {
+            if !self.link_files.iter().any(|file| file == path) {
+                self.link_files.push(path.to_owned());
+            }
+        } else if !self.libs.iter().any(|l| l == lib) {
+            self.libs.push(lib.to_owned());
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Linker {
+    program: PathBuf,
+    flavor: LinkerFlavor,
+}
+
+impl Linker {
+    /// Linker given by the user, its flavor is guessed with its name.
+    pub fn new(program: PathBuf) -> Linker {
+        let flavor = LinkerFlavor::from_path(&program);
+        Linker { program, flavor }
+    }
+
+    /// Finds the linker to use, `$CC` if it's set or the first C compiler
+    /// driver found in the `PATH`.
+    ///
+    /// For another target than the host, the cross compiler drivers of the
+    /// triple, like `aarch64-linux-gnu-gcc`, are tried and then `clang`.
+    pub fn detect(target: Option<&str>) -> Result<Linker, LinkError> {
+        if let Some(cc) = env::var_os("CC").filter(|cc| !cc.is_empty()) {
+            return Ok(Linker::new(PathBuf::from(cc)));
+        }
+
+        let drivers = match target {
+            Some(triple) => cross_drivers(triple),
+            None => DEFAULT_DRIVERS.map(str::to_owned).to_vec(),
+        };
+        drivers
+            .iter()
+            .find_map(|driver| find_in_path(driver))
+            .map(Linker::new)
+            .ok_or(LinkError::NotFound(drivers))
+    }
+
+    pub fn program(&self) -> &Path {
+        &self.program
+    }
+
+    pub fn flavor(&self) -> LinkerFlavor {
+        self.flavor
+    }
+
+    /// Builds the command that links the objects into the output executable.
+    pub fn command(&self, opts: &LinkOptions, output: &Path) -> Result<Command, LinkError> {
+        let mut cmd = Command::new(&self.program);
+        cmd.arg("-o").arg(output);
+
+        let crt = match self.flavor {
+            LinkerFlavor::Cc => {
+                // a cross compiler driver already targets its triple
+                if let Some(triple) = opts.target.as_deref().filter(|t| !self.is_cross_driver(t)) {
+                    cmd.arg(format!("--target={triple}"));
+                }
+                // `ld` doesn't make a PIE unless asked to
+                if opts.no_pie {
+                    cmd.arg("-no-pie");
+                }
+                None
+            }
+            LinkerFlavor::Ld => {
+                let runtime = LinuxRuntime::of(opts.target.as_deref())?;
+                let crt1 = runtime.find_crt("crt1.o")?;
+                let crti = runtime.find_crt("crti.o")?;
+                let crtn = runtime.find_crt("crtn.o")?;
+                // the dynamic loader of another target isn't on the host
+                let loader = Path::new(runtime.loader);
+                if opts.target.is_none() && !loader.exists() {
+                    return Err(LinkError::MissingRuntime("dynamic linker"));
+                }
+
+                cmd.arg("-dynamic-linker").arg(loader);
+                // or `-lc` would find the C library of the host
+                if opts.target.is_some() {
+                    for dir in runtime.crt_dirs.iter().filter(|dir| dir.is_dir()) {
+                        cmd.arg(format!("-L{}", dir.display()));
+                    }
+                }
+                cmd.arg(crt1).arg(crti);
+                Some(crtn)
+            }
+        };
+
+        cmd.args(&opts.objects);
+        cmd.args(&opts.link_files);
+        for path in &opts.lib_paths {
+            cmd.arg(format!("-L{}", path.display()));
+        }
+        for lib in &opts.libs {
+            cmd.arg(format!("-l{lib}"));
+        }
+
+        if let Some(crtn) = crt {
+            cmd.arg("-lc").arg(crtn);
+        }
+
+        Ok(cmd)
+    }
+
+    /// Is the linker a cross compiler driver of the triple, named after it,
+    /// e.g: `aarch64-linux-gnu-gcc` for `aarch64-unknown-linux-gnu`?
+    fn is_cross_driver(&self, triple: &str) -> bool {
+        let name = self
+            .program
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        name.starts_with(&format!("{}-", arch(triple)))
+    }
+
+    /// Links the objects into the output executable.
+    pub fn link(&self, opts: &LinkOptions, output: &Path) -> Result<(), LinkError> {
+        let status = self
+            .command(opts, output)?
+            .status()
+            .map_err(|err| LinkError::Spawn(self.program.clone(), err))?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(LinkError::Failed(self.program.clone(), status.code()))
+        }
+    }
+}
+
+/// Bundles the objects into a static library, with `$AR` if it's set or `ar`.
+pub fn archive(objects: &[PathBuf], output: &Path) -> Result<(), LinkError> {
+    let program = env::var_os("AR")
+        .filter(|ar| !ar.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("ar"));
+
+    // `ar` adds to an existing archive, the objects of a previous build
+    // would stay in it.
+    let _ = fs::remove_file(output);
+
+    let status = Command::new(&program)
+        .arg("rcs")
+        .arg(output)
+        .args(objects)
+        .status()
+        .map_err(|err| LinkError::Spawn(program.clone(), err))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(LinkError::Failed(program, status.code()))
+    }
+}
+
+/// Looks for an executable in the directories of the `PATH`.
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let file = match env::consts::EXE_EXTENSION {
+        "" => name.to_owned(),
+        ext => format!("{name}.{ext}"),
+    };
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths)
+        .map(|dir| dir.join(&file))
+        .find(|path| path.is_file())
+}
+
+/// The C compiler drivers tried to link for another target than the host.
+fn cross_drivers(triple: &str) -> Vec<String> {
+    let mut drivers = Vec::new();
+    if let Some((_, multiarch, _)) = LINUX_RUNTIMES.iter().find(|(a, ..)| *a == arch(triple)) {
+        drivers.push(format!("{multiarch}-gcc"));
+    }
+    drivers.push(format!("{triple}-gcc"));
+    drivers.push("clang".to_owned());
+    drivers
+}
+
+/// The architecture of a target triple, e.g: `aarch64`.
+fn arch(triple: &str) -> &str {
+    triple.split('-').next().unwrap_or_default()
+}
+
+/// The C runtime of a Linux target, to link with a raw `ld`.
+struct LinuxRuntime {
+    /// Directories where its startup files are searched.
+    crt_dirs: Vec<PathBuf>,
+    /// Path of its dynamic loader, on the target.
+    loader: &'static str,
+}
+
+impl LinuxRuntime {
+    /// The runtime of the target, `None` for the host.
+    fn of(target: Option<&str>) -> Result<LinuxRuntime, LinkError> {
+        let triple = target.map_or_else(get_target_triple, str::to_owned);
+        let Some(&(_, multiarch, loader)) = LINUX_RUNTIMES
+            .iter()
+            .find(|(a, ..)| *a == arch(&triple))
+            .filter(|_| triple.contains("-linux-"))
+        else {
+            return Err(LinkError::UnknownRuntime(triple));
+        };
+
+        let mut crt_dirs = vec![PathBuf::from(format!("/usr/lib/{multiarch}"))];
+        match target {
+            // where the cross toolchains of Debian put it
+            Some(_) => crt_dirs.push(PathBuf::from(format!("/usr/{multiarch}/lib"))),
+            None => crt_dirs.extend(HOST_CRT_DIRS.iter().map(PathBuf::from)),
+        }
+        Ok(LinuxRuntime { crt_dirs, loader })
+    }
+
+    fn find_crt(&self, file: &'static str) -> Result<PathBuf, LinkError> {
+        self.crt_dirs
+            .iter()
+            .map(|dir| dir.join(file))
+            .find(|path| path.is_file())
+            .ok_or(LinkError::MissingRuntime(file))
+    }
+}
@@ -1,5 +1,12 @@
 pub use inkwell::targets::TargetMachine;
 
+use std::fmt;
+
+use inkwell::{
+    targets::{CodeModel, InitializationConfig, RelocMode, Target, TargetTriple},
+    OptimizationLevel,
+};
+
 /// This function is an abstraction for the `zomc` bin.
 #[inline]
 pub fn get_target_triple() -> String {
@@ -10,3 +17,119 @@ pub fn get_target_triple() -> String {
         .expect("Error while trying to get the target trimple to a &str.")
         .to_owned()
 }
+
+/// The machine the code is generated for.
+#[derive(Debug, Clone)]
+pub struct TargetOptions {
+    /// Target triple, `None` for the host.
+    pub triple: Option<String>,
+    /// CPU name, `None` for the host CPU when compiling for the host and for a
+    /// generic CPU otherwise.
+    pub cpu: Option<String>,
+    /// Features of the CPU, e.g: `+avx2,-sse4.1`. `None` for the features of
+    /// the host CPU when compiling for the host and for no features otherwise.
+    pub features: Option<String>,
+    pub reloc_mode: RelocMode,
+    pub code_model: CodeModel,
+}
+
+impl Default for TargetOptions {
+    /// Options of the host, with position independent code.
+    fn default() -> Self {
+        TargetOptions {
+            triple: None,
+            cpu: None,
+            features: None,
+            reloc_mode: RelocMode::PIC,
+            code_model: CodeModel::Default,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TargetError {
+    /// LLVM doesn't know the target triple or wasn't built with its target.
+    UnknownTriple { triple: String, msg: String },
+    /// The target machine couldn't be created, e.g: the CPU is unknown.
+    Machine { triple: String },
+}
+
+impl fmt::Display for TargetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetError::UnknownTriple { triple, msg } => write!(
+                f,
+                "unknown target triple `{triple}`, {msg}, run `zom get-target --list` to list the supported targets"
+            ),
+            TargetError::Machine { triple } => write!(
+                f,
+                "couldn't create the target machine of `{triple}`, are the CPU and its features valid?"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TargetError {}
+
+impl TargetOptions {
+    /// Creates the target machine emitting the machine code of the target.
+    pub fn create_target_machine(
+        &self,
+        opt_level: OptimizationLevel,
+    ) -> Result<TargetMachine, TargetError> {
+        Target::initialize_all(&InitializationConfig::default());
+
+        let host = TargetMachine::get_default_triple();
+        let triple = match &self.triple {
+            Some(triple) => TargetMachine::normalize_triple(&TargetTriple::create(triple)),
+            None => TargetMachine::get_default_triple(),
+        };
+        let triple_str = triple.as_str().to_string_lossy().into_owned();
+
+        let target = Target::from_triple(&triple).map_err(|msg| TargetError::UnknownTriple {
+            triple: triple_str.clone(),
+            msg: msg.to_string().trim_end_matches('.').to_owned(),
+        })?;
+
+        // the host CPU is only a good default when compiling for the host
+        let is_host = triple == host;
+        let cpu = match &self.cpu {
+            Some(cpu) => cpu.clone(),
+            None if is_host => TargetMachine::get_host_cpu_name().to_string(),
+            None => "generic".to_owned(),
+        };
+        let features = match &self.features {
+            Some(features) => features.clone(),
+            None if is_host => TargetMachine::get_host_cpu_features().to_string(),
+            None => String::new(),
+        };
+
+        target
+            .create_target_machine(
+                &triple,
+                &cpu,
+                &features,
+                opt_level,
+                self.reloc_mode,
+                self.code_model,
+            )
+            .ok_or(TargetError::Machine { triple: triple_str })
+    }
+}
+
+/// Returns the name and the description of every target LLVM was built with.
+pub fn list_targets() -> Vec<(String, String)> {
+    Target::initialize_all(&InitializationConfig::default());
+
+    let mut targets = Vec::new();
+    let mut next = Target::get_first();
+    while let Some(target) = next {
+        targets.push((
+            target.get_name().to_string_lossy().into_owned(),
+            target.get_description().to_string_lossy().into_owned(),
+        ));
+        next = target.get_next();
+    }
+    targets.sort();
+    targets
+}
@@ -0,0 +1,90 @@
+//! JSON rendering of the logs, used with `--message-format=json`.
+//!
+//! A log is a single object, e.g:
+//! ```json
+//! {
+//!     "level": "error",
+//!     "message": "cannot find variable `y` in this scope",
+//!     "span": {
+//!         "file": "b.zom",
+//!         "byte_start": 20, "byte_end": 21,
+//!         "line_start": 2, "column_start": 21,
+//!         "line_end": 2, "column_end": 22
+//!     },
+//!     "cursor_message": "not found in this scope",
+//!     "notes": []
+//! }
+//! ```
+//! The notes are the other parts of the log, with the same fields except
+//! `cursor_message` and `notes`. Lines and columns start at 1, the end of a
+//! span is exclusive.
+
+use serde::Serialize;
+
+use crate::{BuiltLog, BuiltLogPart, CodeSnippet};
+
+#[derive(Serialize)]
+struct JsonLog<'a> {
+    level: &'static str,
+    message: &'a str,
+    span: Option<JsonSpan>,
+    cursor_message: Option<Box<str>>,
+    notes: Vec<JsonNote<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonNote<'a> {
+    level: &'static str,
+    message: &'a str,
+    span: Option<JsonSpan>,
+}
+
+#[derive(Serialize)]
+struct JsonSpan {
+    file: String,
+    byte_start: usize,
+    byte_end: usize,
+    line_start: usize,
+    column_start: usize,
+    line_end: usize,
+    column_end: usize,
+}
+
+impl JsonSpan {
+    fn new(snippet: &CodeSnippet) -> JsonSpan {
+        JsonSpan {
+            file: snippet.path.display().to_string(),
+            byte_start: snippet.span.start,
+            byte_end: snippet.span.end,
+            line_start: snippet.loc.line,
+            column_start: snippet.loc.col,
+            line_end: snippet.end.line,
+            column_end: snippet.end.col,
+        }
+    }
+}
+
+impl<'a> JsonNote<'a> {
+    fn new(part: &'a BuiltLogPart) -> JsonNote<'a> {
+        JsonNote {
+            level: part.lvl.name(),
+            message: &part.msg,
+            span: part.snippet.as_ref().map(JsonSpan::new),
+        }
+    }
+}
+
+impl BuiltLog {
+    /// Renders the log as a JSON object, on a single line.
+    pub fn to_json(&self) -> String {
+        let (main, notes) = self.parts.split_first().expect("a log has a part");
+        let log = JsonLog {
+            level: main.lvl.name(),
+            message: &main.msg,
+            span: main.snippet.as_ref().map(JsonSpan::new),
+            cursor_message: main.snippet.as_ref().and_then(|snip| snip.cursor.msg()),
+            notes: notes.iter().map(JsonNote::new).collect(),
+        };
+        serde_json::to_string(&log).expect("a log can be serialized")
+    }
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 use std::{ops::Range, path::Path};
 
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
 use lazy_static::lazy_static;
@@ -9,7 +9,10 @@ use lazy_static::lazy_static;
 use zom_common::token::Token;
 
 pub mod err;
+mod json;
 pub mod prelude;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 
 lazy_static! {
     static ref BOLD_STYLE: ColorSpec = ColorSpec::new().set_bold(true).clone();
@@ -83,9 +86,15 @@ pub enum FmtToken {
     Package,
     Import,
     As,
+    In,
+    By,
 
     Ident,
 
+    DocComment,
+
+    Invalid,
+
     EOF,
 }
 
@@ -142,9 +151,15 @@ impl FmtToken {
             TT::Package => Package,
             TT::Import => Import,
             TT::As => As,
+            TT::In => In,
+            TT::By => By,
 
             TT::Ident(_) => Ident,
 
+            TT::DocComment(_) | TT::InnerDocComment(_) => DocComment,
+
+            TT::Invalid => Invalid,
+
             TT::EOF => EOF,
         }
     }
@@ -203,9 +218,15 @@ impl fmt::Display for FmtToken {
                 Package => "keyword `package`",
                 Import => "keyword `import`",
                 As => "keyword `as`",
+                In => "keyword `in`",
+                By => "keyword `by`",
 
                 Ident => "identifier",
 
+                DocComment => "doc comment",
+
+                Invalid => "invalid token",
+
                 EOF => "end of file",
             }
         )
@@ -221,6 +242,7 @@ pub enum PartAST {
     Statement,
     LabeledStmt,
     Type,
+    Pattern,
 }
 
 impl fmt::Display for PartAST {
@@ -236,6 +258,7 @@ impl fmt::Display for PartAST {
                 PartAST::Statement => "statement",
                 PartAST::LabeledStmt => "labeled statement",
                 PartAST::Type => "type",
+                PartAST::Pattern => "pattern",
             }
         )
     }
@@ -243,35 +266,84 @@ impl fmt::Display for PartAST {
 
 pub type CodeSpan = Range<usize>;
 
+/// Format the logs are printed in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Rendered for a human, with a snippet of the code.
+    #[default]
+    Human,
+    /// A JSON object per log, on its own line, for tools like editors.
+    Json,
+}
+
+/// How the logs are printed.
+#[derive(Debug, Clone, Copy)]
+pub struct LogOutput {
+    /// Only used with the human format.
+    pub color: ColorChoice,
+    pub format: MessageFormat,
+    /// Print the logs to stderr, because stdout carries an artifact.
+    pub stderr: bool,
+}
+
+impl LogOutput {
+    /// The same output, but to stderr.
+    pub fn to_stderr(self) -> LogOutput {
+        LogOutput {
+            stderr: true,
+            ..self
+        }
+    }
+}
+
+impl Default for LogOutput {
+    fn default() -> Self {
+        LogOutput {
+            color: ColorChoice::Auto,
+            format: MessageFormat::Human,
+            stderr: false,
+        }
+    }
+}
+
+/// Resolves `ColorChoice::Auto` for a stream, termcolor only looks at the
+/// environment, so the logs would be colored in a pipe too.
+fn resolve_color(color: ColorChoice, is_terminal: bool) -> ColorChoice {
+    match color {
+        ColorChoice::Auto if !is_terminal => ColorChoice::Never,
+        color => color,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LogContext<'a> {
     file: &'a str,
     file_path: &'a Path,
     logs: Vec<BuiltLog>,
-    color: ColorChoice,
+    output: LogOutput,
 }
 
 impl<'a> LogContext<'a> {
-    pub fn new(file: &'a str, file_path: &'a Path, color: ColorChoice) -> LogContext<'a> {
+    pub fn new(file: &'a str, file_path: &'a Path, output: LogOutput) -> LogContext<'a> {
         LogContext {
             file,
             file_path,
             logs: Vec::new(),
-            color,
+            output,
         }
     }
 
     pub fn with_stream(
         file: &'a str,
         file_path: &'a Path,
-        color: ColorChoice,
+        output: LogOutput,
         stream: LogStream,
     ) -> LogContext<'a> {
         LogContext {
             file,
             file_path,
             logs: stream.logs,
-            color,
+            output,
         }
     }
 
@@ -317,7 +389,12 @@ impl<'a> LogContext<'a> {
 
     /// Get the line content with a given `CodeLocation`
     pub fn get_line(&self, loc: CodeLocation) -> Box<str> {
-        self.file.lines().nth(loc.line - 1).unwrap().into()
+        // the line after the final newline, or of an empty file, is empty
+        self.file
+            .lines()
+            .nth(loc.line - 1)
+            .unwrap_or_default()
+            .into()
     }
 
     /// Build a `Log` into a `BuiltLog`
@@ -339,8 +416,7 @@ impl<'a> LogContext<'a> {
     }
 
     pub fn print(&self) {
-        let mut stdout = StandardStream::stdout(self.color);
-        self.format(&mut stdout).expect("error formating failed.");
+        self.stream().print();
     }
 
     pub fn format(&self, s: &mut StandardStream) -> Result<(), io::Error> {
@@ -350,12 +426,12 @@ impl<'a> LogContext<'a> {
     pub fn stream(&self) -> LogStream {
         LogStream {
             logs: self.logs.clone(),
-            color: self.color,
+            output: self.output,
         }
     }
 }
 
-pub fn format_tokens(tokens: &Vec<FmtToken>) -> String {
+pub fn format_tokens(tokens: &[FmtToken]) -> String {
     if tokens.len() == 1 {
         format!("{}", tokens[0])
     } else {
@@ -417,6 +493,8 @@ pub trait Log {
                 cursor: LogCursor::new(start.col..end.col, self.cursor_msg()),
                 path: ctx.file_path.into(),
                 loc: start.clone(),
+                end,
+                span: location,
             }),
         }];
 
@@ -448,8 +526,16 @@ pub struct BuiltLog {
 }
 
 impl BuiltLog {
+    /// A log without a snippet of code, e.g: an error that isn't in a source
+    /// file.
+    pub fn message(lvl: LogLevel, msg: impl Into<Box<str>>) -> BuiltLog {
+        BuiltLog {
+            parts: Box::new([BuiltLogPart::new(lvl, msg.into(), None)]),
+        }
+    }
+
     pub fn format(&self, s: &mut StandardStream) -> Result<(), io::Error> {
-        for part in self.parts.into_iter() {
+        for part in self.parts.iter() {
             part.format(s)?;
             writeln!(s)?;
             s.reset()?;
@@ -471,6 +557,14 @@ pub enum LogLevel {
 }
 
 impl LogLevel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+            Self::Note => "note",
+        }
+    }
+
     pub fn format(&self, s: &mut StandardStream) -> Result<ColorSpec, io::Error> {
         match self {
             Self::Warning => {
@@ -497,18 +591,77 @@ pub enum FinalRes<'a, T> {
     Err(LogStream),
 }
 
+#[derive(Debug, Clone)]
 pub struct LogStream {
     logs: Vec<BuiltLog>,
-    color: ColorChoice,
+    output: LogOutput,
 }
 
 impl LogStream {
+    pub fn new(output: LogOutput) -> LogStream {
+        LogStream {
+            logs: Vec::new(),
+            output,
+        }
+    }
+
+    pub fn push_built(&mut self, blog: BuiltLog) {
+        self.logs.push(blog);
+    }
+
+    /// Moves the logs of another stream, e.g: of another file, at the end of
+    /// this one.
+    pub fn append(&mut self, mut other: LogStream) {
+        self.logs.append(&mut other.logs);
+    }
+
+    /// Returns true if their is at least one `Log` with `LogLevel` of `Error`, instead false.
+    pub fn failed(&self) -> bool {
+        self.logs
+            .iter()
+            .any(|log| matches!(log.level(), LogLevel::Error))
+    }
+
     pub fn print(&self) {
-        let mut stdout = StandardStream::stdout(self.color);
-        self.format(&mut stdout).expect("error formating failed.");
+        let LogOutput {
+            color,
+            format,
+            stderr,
+        } = self.output;
+        match format {
+            MessageFormat::Human if stderr => {
+                let color = resolve_color(color, io::stderr().is_terminal());
+                self.format(&mut StandardStream::stderr(color))
+                    .expect("error formating failed.");
+            }
+            MessageFormat::Human => {
+                let color = resolve_color(color, io::stdout().is_terminal());
+                self.format(&mut StandardStream::stdout(color))
+                    .expect("error formating failed.");
+            }
+            MessageFormat::Json if stderr => {
+                self.format_json(&mut io::stderr().lock())
+                    .expect("error formating failed.");
+            }
+            MessageFormat::Json => {
+                self.format_json(&mut io::stdout().lock())
+                    .expect("error formating failed.");
+            }
+        }
+    }
+
+    /// Writes the logs as JSON, one per line.
+    pub fn format_json(&self, s: &mut impl Write) -> Result<(), io::Error> {
+        for log in &self.logs {
+            writeln!(s, "{}", log.to_json())?;
+        }
+        s.flush()
     }
 
     pub fn format(&self, s: &mut StandardStream) -> Result<(), io::Error> {
+        if self.logs.is_empty() {
+            return Ok(());
+        }
         let len = self.logs.len();
         for (i, log) in self.logs.iter().enumerate() {
             log.format(s)?;
@@ -552,7 +705,12 @@ impl LogCursor {
 
 #[derive(Clone, Debug)]
 pub struct CodeSnippet {
+    /// Location of the start of the span.
     pub loc: CodeLocation,
+    /// Location of the end of the span.
+    pub end: CodeLocation,
+    /// Span of the code, in bytes.
+    pub span: CodeSpan,
     pub code: Box<str>,
     pub path: Box<Path>,
     pub cursor: LogCursor,
@@ -589,7 +747,7 @@ impl CodeSnippet {
         s.set_color(&BLUE_STYLE)?;
         write!(s, "{}| {}", margin_str, spaces(self.loc.col - 1),)?;
 
-        s.set_color(&lvl_color)?;
+        s.set_color(lvl_color)?;
         write!(
             s,
             "{}",
@@ -655,6 +813,8 @@ impl LogPart {
                 code: ctx.get_line(start.clone()),
                 path: ctx.file_path.into(),
                 cursor: LogCursor::new(start.col..end.col, None),
+                end,
+                span: location.clone(),
             })
         } else {
             None
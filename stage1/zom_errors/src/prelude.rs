@@ -1,4 +1,4 @@
 pub use super::{
     err::*, BuiltLog, CodeSnippet, CodeSpan, FinalRes, FmtToken, Log, LogContext, LogLevel,
-    LogPart, LogStream, PartAST,
+    LogOutput, LogPart, LogStream, MessageFormat, PartAST,
 };
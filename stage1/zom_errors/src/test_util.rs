@@ -0,0 +1,58 @@
+//! Helpers for the tests of the crates reporting logs, enabled with the
+//! `test-util` feature.
+
+use std::ops::Range;
+
+use serde_json::Value;
+
+use crate::LogStream;
+
+/// A diagnostic, as it's printed with `--message-format=json`.
+#[derive(Debug)]
+pub struct Diag {
+    pub level: String,
+    pub msg: String,
+    pub cursor_msg: Option<String>,
+    /// byte range of the diagnostic in the source, if it points to the code
+    pub span: Option<Range<usize>>,
+}
+
+/// Returns the diagnostics of the logs.
+pub fn diags(logs: &LogStream) -> Vec<Diag> {
+    let mut json = Vec::new();
+    logs.format_json(&mut json).unwrap();
+    String::from_utf8(json)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let log: Value = serde_json::from_str(line).unwrap();
+            let span = &log["span"];
+            Diag {
+                level: log["level"].as_str().unwrap().to_owned(),
+                msg: log["message"].as_str().unwrap().to_owned(),
+                cursor_msg: log["cursor_message"].as_str().map(str::to_owned),
+                span: span["byte_start"]
+                    .as_u64()
+                    .map(|start| start as usize..span["byte_end"].as_u64().unwrap() as usize),
+            }
+        })
+        .collect()
+}
+
+/// Returns the messages of the errors of the diagnostics.
+pub fn errors(diags: &[Diag]) -> Vec<&str> {
+    diags
+        .iter()
+        .filter(|diag| diag.level == "error")
+        .map(|diag| diag.msg.as_str())
+        .collect()
+}
+
+/// Returns the messages of the warnings of the diagnostics.
+pub fn warnings(diags: &[Diag]) -> Vec<&str> {
+    diags
+        .iter()
+        .filter(|diag| diag.level == "warning")
+        .map(|diag| diag.msg.as_str())
+        .collect()
+}
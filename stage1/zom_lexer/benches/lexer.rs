@@ -0,0 +1,60 @@
+//! Lexes generated files of several megabytes, the throughput must stay the
+//! same as the size grows for the lexing to be linear.
+
+use std::path::Path;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use zom_errors::prelude::*;
+use zom_lexer::Lexer;
+
+/// A function with non-ASCII chars in its comments and strings, repeated to
+/// make a file of about `size` bytes.
+fn source_of_size(size: usize) -> String {
+    const FUNCTION: &str = r#"
+/// Computes the answer, à peu près.
+pub fn answer_{}(n: u32) u32 {
+    // ünïcödé in a comment
+    var msg = "日本語 ✓ \u{1F600}\n"
+    var c = 'é'
+    if (n <= 0x2A_u32) {
+        return n * 2 + 0b1010 /* nested /* block */ comment */
+    }
+    return answer_{}(n - 1) + 3.14e0
+}
+"#;
+    let mut source = String::from("package bench\n");
+    let mut i = 0;
+    while source.len() < size {
+        source.push_str(&FUNCTION.replace("{}", &i.to_string()));
+        i += 1;
+    }
+    source
+}
+
+fn lex(c: &mut Criterion) {
+    let path = Path::new("bench.zom");
+    let mut group = c.benchmark_group("lex");
+    group.sample_size(10);
+
+    for mib in [1, 2, 4] {
+        let source = source_of_size(mib * 1024 * 1024);
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{mib}MiB")),
+            &source,
+            |b, source| {
+                b.iter(|| {
+                    let lctx = LogContext::new(source, path, LogOutput::default());
+                    match Lexer::new(source, path, lctx).lex() {
+                        FinalRes::Ok(tokens, _) => tokens,
+                        FinalRes::Err(_) => panic!("the generated source can't be lexed"),
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lex);
+criterion_main!(benches);
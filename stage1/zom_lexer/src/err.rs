@@ -26,8 +26,6 @@ impl Log for UnknownToken {
 pub struct UnknownEscape {
     /// character found
     pub escape: char,
-    /// did the escape came from a string or a char literal
-    pub is_string: bool,
     /// location of the found token
     pub location: CodeSpan,
 }
@@ -47,17 +45,164 @@ impl Log for UnknownEscape {
 
     fn cursor_msg(&self) -> Option<Box<str>> {
         Some(
-            (r"supported escapse sequence are, '\0', '\n', '\r', '\t', '\xNN' (not yet supported) "
-                .to_string() + if self.is_string {
-                r#"and '\"'."#
-            } else {
-                r"and '\''"
-            })
+            r#"supported escape sequences are, '\0', '\n', '\r', '\t', '\\', '\'', '\"', '\xNN' and '\u{NNNN}'"#
+                .into(),
+        )
+    }
+}
+
+/// identifier mixing scripts, with a char that looks like a char of another
+/// script
+pub struct ConfusableIdent {
+    /// the identifier
+    pub ident: String,
+    /// the confusable char
+    pub confusable: char,
+    /// script(s) of the confusable char
+    pub script: String,
+    /// location of the confusable char
+    pub location: CodeSpan,
+}
+
+impl Log for ConfusableIdent {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Warning
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!("identifier `{}` mixes scripts", self.ident).into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some(
+            format!(
+                "`{}` (U+{:04X}) is {}, it looks like a char of another script",
+                self.confusable, self.confusable as u32, self.script
+            )
             .into(),
         )
     }
 }
 
+/// block comment without its closing `*/`
+pub struct UnterminatedBlockComment {
+    /// how many block comments are still open, with the nested ones
+    pub depth: usize,
+    /// location of the `/*` starting the comment
+    pub location: CodeSpan,
+}
+
+impl Log for UnterminatedBlockComment {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        "unterminated block comment".into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some(if self.depth == 1 {
+            "this comment is never closed with a `*/`".into()
+        } else {
+            format!(
+                "this comment is never closed, {} `*/` are missing with the nested comments",
+                self.depth
+            )
+            .into()
+        })
+    }
+}
+
+/// a raw string literal without its closing delimiter
+pub struct UnterminatedRawStr {
+    /// how many `#` extend the delimiter
+    pub hashes: usize,
+    /// location of the opening delimiter, e.g: `r#"`
+    pub location: CodeSpan,
+}
+
+impl Log for UnterminatedRawStr {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        "unterminated raw string literal".into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some(
+            format!(
+                "this string is never closed with a `\"{}`",
+                "#".repeat(self.hashes)
+            )
+            .into(),
+        )
+    }
+}
+
+/// content on the line of the opening `"""` of a multi-line string literal
+pub struct ContentAfterMultilineOpening {
+    /// location of the content
+    pub location: CodeSpan,
+}
+
+impl Log for ContentAfterMultilineOpening {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        "unexpected content after the opening `\"\"\"`".into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some("a multi-line string starts on the next line".into())
+    }
+}
+
+/// line of a multi-line string literal that doesn't start with the
+/// indentation of the closing `"""`
+pub struct InsufficientIndent {
+    /// location of the indentation of the line, or of its first char
+    pub location: CodeSpan,
+}
+
+impl Log for InsufficientIndent {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        "insufficient indentation in a multi-line string".into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some("this line must start with the indentation of the closing `\"\"\"`".into())
+    }
+}
+
 /// unknown token lexing error
 pub struct UnterminatedQuoteLit {
     /// character found
@@ -1,7 +1,14 @@
 //! The module containing the lexer.
+use std::collections::HashSet;
+use std::mem;
 use std::ops::Range;
 use std::path::Path;
 
+use unicode_ident::{is_xid_continue, is_xid_start};
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::{
+    is_potential_mixed_script_confusable_char, mixed_script::AugmentedScriptSet, MixedScript,
+};
 use zom_errors::prelude::*;
 
 use zom_common::operator::Operator;
@@ -34,9 +41,13 @@ impl<'a> ZomFile<'a> {
         self.path
     }
 
-    /// Get the nth char inside the file and returns it.
+    /// Get the char starting at the byte `index` inside the file and returns
+    /// it.
+    ///
+    /// May return `None` if the index is at the end of the file, or isn't at
+    /// the start of a char.
     pub fn get(&self, index: usize) -> Option<char> {
-        self.text.chars().nth(index) // the current implementation of this function is very bad, we would need to improve it, e.g: store the iterator instead of recreating it every time.
+        self.text.get(index..)?.chars().next()
     }
 }
 
@@ -84,11 +95,36 @@ macro_rules! pop_expect {
 }
 
 /// Used to lexe the content of a file into tokens that the parser can understand.
+///
+/// The lexer is a cursor over the chars of the file, its indices are byte
+/// offsets, like the spans of the tokens.
+///
+/// It's an iterator over the tokens of the file, ending with the EOF token.
+/// The lexing doesn't stop at the errors, they are pushed to the log context
+/// and the token that couldn't be lexed is an `Invalid` token.
 pub struct Lexer<'a> {
     file: ZomFile<'a>,
+    /// Byte offset of the start of the token being lexed
     prev_idx: usize,
+    /// Byte offset of the next char
     index: usize,
     pub lctx: LogContext<'a>,
+    /// Are the whitespaces and the comments kept as the trivia of the tokens?
+    lossless: bool,
+    /// The trivia lexed since the end of the line of the last token, it will
+    /// lead the next token.
+    trivia: Vec<Trivia>,
+    /// Has the line of the last token ended?
+    line_ended: bool,
+    /// The identifiers already checked by the confusable lint
+    linted_idents: HashSet<String>,
+    /// The last lexed token, it's kept until the next one is lexed, so the
+    /// trivia on its line can trail it.
+    pending: Option<Token>,
+    /// Has the EOF token been returned?
+    finished: bool,
+    /// The tokens returned so far, if they are recorded.
+    recorded: Option<Vec<Token>>,
 }
 
 impl<'a> Lexer<'a> {
@@ -98,11 +134,40 @@ impl<'a> Lexer<'a> {
             prev_idx: 0,
             index: 0,
             lctx,
+            lossless: false,
+            trivia: Vec::new(),
+            line_ended: true,
+            linted_idents: HashSet::new(),
+            pending: None,
+            finished: false,
+            recorded: None,
+        }
+    }
+
+    /// Creates a lexer that keeps the whitespaces and the comments, as the
+    /// leading and trailing trivia of the tokens. Concatenating the tokens
+    /// with their trivia gives back the source file.
+    pub fn lossless(text: &'a str, path: &'a Path, lctx: LogContext<'a>) -> Lexer<'a> {
+        Lexer {
+            lossless: true,
+            ..Lexer::new(text, path, lctx)
         }
     }
 
+    /// Records a copy of the tokens it returns, so the tokens of a file
+    /// lexed for the parser can be given back without lexing it again.
+    pub fn recording(mut self) -> Lexer<'a> {
+        self.recorded = Some(Vec::new());
+        self
+    }
+
+    /// Takes the tokens recorded so far, none if the lexer isn't recording.
+    pub fn take_recorded(&mut self) -> Vec<Token> {
+        self.recorded.as_mut().map(mem::take).unwrap_or_default()
+    }
+
     /// Get the ZomFile inside the lexer
-    pub fn file(&self) -> &ZomFile {
+    pub fn file(&self) -> &ZomFile<'a> {
         &self.file
     }
 
@@ -116,11 +181,11 @@ impl<'a> Lexer<'a> {
         self.file.text()
     }
 
-    /// Get the char at the current index, then increment the index by one and returns the char he gets before
+    /// Get the char at the current index, then moves the index after it and returns the char he gets before
     pub fn pop(&mut self) -> Option<char> {
-        let c = self.peek();
-        self.index += 1;
-        c
+        let c = self.peek()?;
+        self.index += c.len_utf8();
+        Some(c)
     }
 
     /// Get the char at the current index, and returns it. It returns EOF if the index is out of bounds.
@@ -128,43 +193,17 @@ impl<'a> Lexer<'a> {
         self.file.get(self.index)
     }
 
-    /// Get the nth char in the file, at index + offset
+    /// Get the char that is `offset` chars after the current one
     ///
     /// May return `None` if the index is out of bounds of the file text.
     pub fn peek_nth(&self, offset: usize) -> Option<char> {
-        self.file.get(self.index + offset)
+        self.file_text()[self.index..].chars().nth(offset)
     }
 
     /// Lex the whole file and returns either a vector of Tokens if it succeeds or,
     /// a list of errors if it doesn't.
-    pub fn lex(&mut self) -> FinalRes<Vec<Token>> {
-        let mut tokens = Vec::new();
-
-        loop {
-            self.prev_idx = self.index;
-            match self.make_token() {
-                Tok(tt) => {
-                    if self.push_token(&mut tokens, tt) {
-                        // Reached EOF, breaking out of the loop
-                        break;
-                    }
-                }
-                Error(err) => {
-                    self.lctx.push_built(err);
-                }
-                PartSuccess(tt, errs) => {
-                    debug_assert!(!errs.is_empty());
-
-                    self.lctx.push_many(errs);
-
-                    if self.push_token(&mut tokens, tt) {
-                        // Reached EOF, breaking out of the loop
-                        break;
-                    }
-                }
-                Comment | Whitespace => {}
-            }
-        }
+    pub fn lex(&mut self) -> FinalRes<'a, Vec<Token>> {
+        let tokens = self.by_ref().collect();
 
         if self.lctx.failed() {
             return FinalRes::Err(self.lctx.stream());
@@ -176,28 +215,78 @@ impl<'a> Lexer<'a> {
         self.prev_idx..self.index
     }
 
-    /// This functions takes a vector of tokens, and push a Token containing the given TokenType
-    /// with the start arg and the index at that moment.
+    /// Lexes the next token, the errors are pushed to the log context and a
+    /// token that couldn't be lexed is an `Invalid` token.
     ///
-    /// And if the TokenType is a EOF it returns true, either false.
-    fn push_token(&self, tokens: &mut Vec<Token>, tt: TokenType) -> bool {
-        if tt == EOF {
-            let text_len = self.file_text().len() - 1;
-            // the length of the buffer is used for the span of the EOF, because
-            // the EOF is the last char and its 'text_len..text_len' because if for
-            // some reason we want to show the EOF in an error we can.
-            tokens.push(Token {
-                tt,
-                span: text_len - 1..text_len,
-            });
-            return true;
-        }
-        tokens.push(Token {
-            tt,
-            span: self.prev_idx..self.index,
-        });
+    /// Returns `None` if a whitespace or a comment was lexed.
+    fn lex_token(&mut self) -> Option<Token> {
+        self.prev_idx = self.index;
+        let tt = match self.make_token() {
+            Tok(tt) => tt,
+            Error(err) => {
+                self.lctx.push_built(err);
+                Invalid
+            }
+            PartSuccess(tt, errs) => {
+                debug_assert!(!errs.is_empty());
+
+                self.lctx.push_many(errs);
+                tt
+            }
+            Comment => {
+                self.push_trivia(TriviaKind::Comment);
+                return None;
+            }
+            Whitespace => {
+                self.push_trivia(TriviaKind::Whitespace);
+                return None;
+            }
+        };
+        Some(self.new_token(tt))
+    }
+
+    /// Creates a Token containing the given TokenType with the start of the
+    /// token and the index at that moment, the trivia lexed before leads it.
+    fn new_token(&mut self, tt: TokenType) -> Token {
+        let mut token = if tt == EOF {
+            // the span of the EOF is the last char before the final newline,
+            // so if for some reason we want to show the EOF in an error we can.
+            let text = self.file_text();
+            let text = text.strip_suffix('\n').unwrap_or(text);
+            let last = text.chars().next_back().map_or(0, char::len_utf8);
+            Token::new(tt, text.len() - last..text.len())
+        } else {
+            Token::new(tt, self.prev_idx..self.index)
+        };
+        token.leading = std::mem::take(&mut self.trivia);
+        self.line_ended = false;
+        token
+    }
+
+    /// Keeps the whitespace or the comment that was just lexed, when the
+    /// lexing is lossless. The trivia on the line of the last token trails it,
+    /// the other one leads the next token.
+    fn push_trivia(&mut self, kind: TriviaKind) {
+        if !self.lossless {
+            return;
+        }
+        let span = self.get_pos();
+        let newline = kind == TriviaKind::Whitespace && self.file.get(span.start) == Some('\n');
 
-        false
+        let trivia = match self.pending.as_mut() {
+            Some(token) if !self.line_ended => {
+                self.line_ended = newline;
+                &mut token.trailing
+            }
+            _ => &mut self.trivia,
+        };
+        match trivia.last_mut() {
+            // the whitespaces are lexed one char at a time
+            Some(last) if kind == TriviaKind::Whitespace && last.kind == kind => {
+                last.span.end = span.end
+            }
+            _ => trivia.push(Trivia { kind, span }),
+        }
     }
 
     /// Given the current char (self.peek()) calls a function and returns the result.
@@ -218,17 +307,29 @@ impl<'a> Lexer<'a> {
                 match self.peek() {
                     Some('/') => {
                         self.pop();
-                        self.lex_until('\n');
-                        return Comment;
+                        return self.lex_line_comment();
+                    }
+                    Some('*') => {
+                        self.pop();
+                        return self.lex_block_comment();
+                    }
+                    Some('=') => {
+                        self.pop();
+                        return Tok(Oper(Operator::SlashEqual));
                     }
                     _ => return Tok(Oper(Operator::Slash)),
                 }
             }
+            Some('"') if self.peek_nth(1) == Some('"') && self.peek_nth(2) == Some('"') => {
+                return self.lex_multiline_string()
+            }
             Some('"') => return self.lex_string_literal(),
+            Some('r') if self.is_raw_string_start() => return self.lex_raw_string(),
             Some('\'') => return self.lex_char_literal(),
-            Some('A'..='Z' | 'a'..='z' | '_' | '0'..='9') => return self.lex_word(),
+            Some('0'..='9') => return self.lex_number(),
+            Some(c) if c == '_' || is_xid_start(c) => return self.lex_word(),
             Some(w) if w.is_whitespace() => {
-                self.index += 1;
+                self.pop();
                 return Whitespace;
             }
             Some(c) => {
@@ -243,45 +344,314 @@ impl<'a> Lexer<'a> {
             }
             None => EOF,
         };
-        self.index += 1;
+        self.pop();
 
         Tok(t)
     }
 
-    /// Lexes the input until while the content is alphanumeric with underscore(s) returns the content and if the
-    /// string is numeric, in a tuple.
-    pub fn make_word(&mut self) -> (String, bool) {
-        let mut word = String::new();
-        let mut is_numeric = true;
+    /// Lexes the input while the content is made of XID_Continue chars, like
+    /// letters, digits and underscores, and returns the content in NFC, so
+    /// the different spellings of a word are equal.
+    pub fn make_word(&mut self) -> String {
+        let start = self.index;
+        while self.peek().is_some_and(is_xid_continue) {
+            self.pop();
+        }
 
-        loop {
-            match self.peek() {
-                Some(c @ ('A'..='Z' | 'a'..='z' | '_')) => {
-                    is_numeric = false;
-                    word.push(c);
-                }
-                Some(c @ '0'..='9') => {
-                    word.push(c);
-                }
-                _ => break,
-            }
-            self.index += 1;
+        let word = &self.file_text()[start..self.index];
+        if word.is_ascii() {
+            word.to_owned()
+        } else {
+            word.nfc().collect()
         }
-        (word, is_numeric)
     }
 
-    /// Lexes either an integer or an identifier or a keyword, and returns it.
+    /// Lexes either an identifier or a keyword, and returns it.
     pub fn lex_word(&mut self) -> PartTokenResult {
-        let (word, is_numeric) = self.make_word();
+        let word = self.make_word();
+        if !word.is_ascii() && !word.as_str().is_single_script() {
+            self.lint_confusable(&word);
+        }
+        Tok(self.lex_keyword(word))
+    }
+
+    /// Warns about an identifier mixing scripts if one of its chars looks like
+    /// a char of another script, e.g: the Cyrillic `а` in `pаypal`. The char
+    /// reported is the one of the script with the fewest chars, and each
+    /// identifier is reported once.
+    fn lint_confusable(&mut self, ident: &str) {
+        if !self.linted_idents.insert(ident.to_owned()) {
+            return;
+        }
+        let source = &self.file_text()[self.prev_idx..self.index];
+        let scripts: Vec<AugmentedScriptSet> =
+            source.chars().map(AugmentedScriptSet::for_char).collect();
+        // how many chars of the identifier share a script with the char
+        let same_script = |set: &AugmentedScriptSet| {
+            scripts
+                .iter()
+                .filter(|other| {
+                    let mut common = *set;
+                    common.intersect_with(**other);
+                    !common.is_empty()
+                })
+                .count()
+        };
+
+        let Some((idx, confusable)) = source
+            .char_indices()
+            .zip(&scripts)
+            .filter(|((_, c), set)| is_potential_mixed_script_confusable_char(*c) && !set.is_all())
+            .min_by_key(|(_, set)| same_script(set))
+            .map(|(char_idx, _)| char_idx)
+        else {
+            return;
+        };
+
+        let start = self.prev_idx + idx;
+        self.lctx.push(err::ConfusableIdent {
+            ident: ident.to_owned(),
+            confusable,
+            script: AugmentedScriptSet::for_char(confusable).to_string(),
+            location: start..start + confusable.len_utf8(),
+        });
+    }
 
-        if is_numeric {
-            match self.lex_int(word) {
-                Ok(tt) => Tok(tt),
-                Err(err) => Error(*err),
+    /// Lexes the digits of the given radix, with `_` separators, and returns
+    /// the digits without them.
+    pub fn lex_digits(&mut self, radix: u32) -> String {
+        let mut digits = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_digit(radix) {
+                digits.push(c);
+            } else if c != '_' {
+                break;
             }
-        } else {
-            Tok(self.lex_keyword(word))
+            self.pop();
         }
+        digits
+    }
+
+    /// Lexes the exponent of a float literal, after the `e` or the `p`, an
+    /// optional sign and then decimal digits. Returns `None` if there is no
+    /// digit.
+    fn lex_exponent(&mut self) -> Option<i32> {
+        let negative = match self.peek() {
+            Some(sign @ ('+' | '-')) => {
+                self.pop();
+                sign == '-'
+            }
+            _ => false,
+        };
+        let digits = self.lex_digits(10);
+        if digits.is_empty() {
+            return None;
+        }
+        // an exponent that doesn't fit is out of the range of any float anyway
+        let exp = digits.parse::<i32>().unwrap_or(i32::MAX);
+        Some(if negative { -exp } else { exp })
+    }
+
+    /// Lexes an integer or a float literal, e.g: `42`, `1_000u32`, `0b1010`,
+    /// `0o755`, `0xFF`, `3.14`, `1e-9` or the hexadecimal float `0x1.8p3`.
+    ///
+    /// A float literal has digits after its point, `1.foo` is the integer `1`
+    /// followed by `.foo`.
+    ///
+    /// Like in Rust, the base prefixes are lowercase, `0X1F` is lexed like
+    /// `0x1F` but with an error.
+    pub fn lex_number(&mut self) -> PartTokenResult {
+        let (radix, prefix) = match (self.peek(), self.peek_nth(1)) {
+            (Some('0'), Some(prefix @ ('x' | 'X'))) => (Radix::Hexadecimal, prefix),
+            (Some('0'), Some(prefix @ ('o' | 'O'))) => (Radix::Octal, prefix),
+            (Some('0'), Some(prefix @ ('b' | 'B'))) => (Radix::Binary, prefix),
+            _ => return self.lex_decimal_number(),
+        };
+        let start = self.index;
+        let res = self.lex_prefixed_number(radix);
+        if prefix.is_ascii_lowercase() {
+            return res;
+        }
+
+        let err = self.lctx.build(SimpleLog {
+            level: LogLevel::Error,
+            msg: "invalid base prefix for number literal".into(),
+            cursor_msg: Some(
+                format!("base prefixes are lowercase, like `{}`", radix.prefix()).into(),
+            ),
+            location: start..start + 2,
+        });
+        match res {
+            Tok(tt) => PartSuccess(tt, vec![err]),
+            PartSuccess(tt, mut errs) => {
+                errs.insert(0, err);
+                PartSuccess(tt, errs)
+            }
+            res => res,
+        }
+    }
+
+    /// Lexes a number literal after its base prefix, like `0x`.
+    fn lex_prefixed_number(&mut self, radix: Radix) -> PartTokenResult {
+        self.index += 2;
+        let digits = self.lex_digits(radix.value());
+
+        // a decimal digit too big for the radix, e.g: `2` in `0b102`
+        if let Some(digit) = self.peek().filter(|c| c.is_ascii_digit()) {
+            let location = self.index..self.index + 1;
+            self.lex_digits(10);
+            self.make_word();
+            return self.number_error(
+                Int(IntLit::new(0)),
+                &format!("invalid digit `{digit}` in {} literal", radix.name()),
+                &format!("not a valid {} digit", radix.name()),
+                location,
+            );
+        }
+        if digits.is_empty() {
+            return self.number_error(
+                Int(IntLit::new(0)),
+                "malformed number literal",
+                &format!(
+                    "expected a {} digit after `{}`",
+                    radix.name(),
+                    radix.prefix()
+                ),
+                self.get_pos(),
+            );
+        }
+
+        if radix == Radix::Hexadecimal {
+            if let Some(res) = self.lex_hex_float(&digits) {
+                return res;
+            }
+        }
+        let suffix = self.make_word();
+        self.lex_int(&digits, radix, Some(suffix).filter(|s| !s.is_empty()))
+    }
+
+    /// Lexes a decimal integer, or a float.
+    fn lex_decimal_number(&mut self) -> PartTokenResult {
+        let mut num = self.lex_digits(10);
+        let mut is_float = false;
+        if self.peek() == Some('.') && self.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) {
+            pop_expect!(self => Some('.'));
+            num.push('.');
+            num += &self.lex_digits(10);
+            is_float = true;
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.pop();
+            let Some(exp) = self.lex_exponent() else {
+                return self
+                    .malformed_number(Float(0.0), "expected at least one digit in the exponent");
+            };
+            num += &format!("e{exp}");
+            is_float = true;
+        }
+
+        if !is_float {
+            let suffix = self.make_word();
+            return self.lex_int(&num, Radix::Decimal, Some(suffix).filter(|s| !s.is_empty()));
+        }
+        if let Some(err) = self.lex_invalid_suffix() {
+            return PartSuccess(Float(0.0), vec![err]);
+        }
+        // only contains digits, a point and an exponent
+        self.make_float(num.parse().unwrap())
+    }
+
+    /// Lexes the rest of a hexadecimal float, after the digits of its integer
+    /// part. A float must have an exponent, a power of two, e.g: `0x1.8p3` is
+    /// `1.5 * 2^3`.
+    ///
+    /// Returns `None` if the number is an integer, the point is only part of
+    /// the literal if an exponent follows the fraction, so `0x1.field` is the
+    /// integer `0x1` followed by `.field`.
+    fn lex_hex_float(&mut self, int: &str) -> Option<PartTokenResult> {
+        let mut frac = String::new();
+        if self.peek() == Some('.') && self.has_hex_fraction() {
+            pop_expect!(self => Some('.'));
+            frac = self.lex_digits(16);
+        }
+
+        if !matches!(self.peek(), Some('p' | 'P')) {
+            return None;
+        }
+        self.pop();
+        let Some(exp) = self.lex_exponent() else {
+            return Some(
+                self.malformed_number(Float(0.0), "expected at least one digit in the exponent"),
+            );
+        };
+        if let Some(err) = self.lex_invalid_suffix() {
+            return Some(PartSuccess(Float(0.0), vec![err]));
+        }
+
+        let mut mantissa = 0.0;
+        for digit in int.chars().chain(frac.chars()) {
+            mantissa = mantissa * 16.0 + digit.to_digit(16).unwrap() as f64;
+        }
+        let exp = exp.saturating_sub(4 * frac.len() as i32);
+        Some(self.make_float(mantissa * 2f64.powi(exp)))
+    }
+
+    /// Is the cursor, on a `.`, followed by hexadecimal digits and then by a
+    /// `p` exponent?
+    fn has_hex_fraction(&self) -> bool {
+        let mut rest = self.file_text()[self.index + 1..].chars();
+        if !rest.next().is_some_and(|c| c.is_ascii_hexdigit()) {
+            return false;
+        }
+        let mut rest = rest.skip_while(|&c| c.is_ascii_hexdigit() || c == '_');
+        matches!(rest.next(), Some('p' | 'P'))
+    }
+
+    /// Returns the float token, or an error if the value is too big.
+    fn make_float(&mut self, value: f64) -> PartTokenResult {
+        if value.is_infinite() {
+            return self.malformed_number(Float(0.0), "the literal is too big to be represented");
+        }
+        Tok(Float(value))
+    }
+
+    /// Lexes letters and digits right after a float, like in `1.5abc`, and
+    /// returns the error if there is any.
+    fn lex_invalid_suffix(&mut self) -> Option<BuiltLog> {
+        let start = self.index;
+        let suffix = self.make_word();
+        if suffix.is_empty() {
+            return None;
+        }
+        Some(self.lctx.build(SimpleLog {
+            level: LogLevel::Error,
+            msg: format!("invalid suffix `{suffix}` for float literal").into(),
+            cursor_msg: Some("float literals can't have a suffix".into()),
+            location: start..self.index,
+        }))
+    }
+
+    /// Returns the token with an error on the whole number.
+    fn malformed_number(&mut self, tt: TokenType, cursor_msg: &str) -> PartTokenResult {
+        self.number_error(tt, "malformed number literal", cursor_msg, self.get_pos())
+    }
+
+    /// Returns the token with an error, the token is still produced so the
+    /// parser can go on.
+    fn number_error(
+        &mut self,
+        tt: TokenType,
+        msg: &str,
+        cursor_msg: &str,
+        location: CodeSpan,
+    ) -> PartTokenResult {
+        let err = self.lctx.build(SimpleLog {
+            level: LogLevel::Error,
+            msg: msg.into(),
+            cursor_msg: Some(cursor_msg.into()),
+            location,
+        });
+        PartSuccess(tt, vec![err])
     }
 
     /// Lexes either a keyword if the argument kw match a keyword or an identifier if it doesn't match
@@ -312,21 +682,84 @@ impl<'a> Lexer<'a> {
             KW_PACKAGE => Package,
             KW_IMPORT => Import,
             KW_AS => As,
+            KW_IN => In,
+            KW_BY => By,
             _ => Ident(kw),
         }
     }
 
-    /// Take the string containing the integer (num argument), parses it,
-    /// returns the corresponding TokenType or an error if the parsing failed.
-    pub fn lex_int(&mut self, num: String) -> Result<TokenType, Box<BuiltLog>> {
-        match num.parse() {
-            Ok(i) => Ok(Int(i)),
-            Err(err) => Err(self.lctx.build_boxed(SimpleLog {
-                level: LogLevel::Error,
-                msg: "failed to lex integer literal".into(),
-                cursor_msg: Some(err.to_string().into()),
-                location: self.get_pos(),
+    /// Takes the digits of an integer, without separators, and returns the
+    /// corresponding token, or an error if it doesn't fit in a `u128`. The
+    /// suffix is checked by the parser, it knows the types.
+    pub fn lex_int(
+        &mut self,
+        digits: &str,
+        radix: Radix,
+        suffix: Option<String>,
+    ) -> PartTokenResult {
+        match u128::from_str_radix(digits, radix.value()) {
+            Ok(value) => Tok(Int(IntLit {
+                value,
+                radix,
+                suffix,
             })),
+            Err(_) => self.number_error(
+                Int(IntLit::new(0)),
+                "integer literal is too large",
+                "doesn't fit in `u128`",
+                self.get_pos(),
+            ),
+        }
+    }
+
+    /// Lexes a line comment, after its `//`, `///` and `//!` make doc comments
+    /// but `////` is still a normal comment.
+    pub fn lex_line_comment(&mut self) -> PartTokenResult {
+        let marker = match (self.peek(), self.peek_nth(1)) {
+            (Some('/'), Some('/')) => None,
+            (Some(c @ ('/' | '!')), _) => Some(c),
+            _ => None,
+        };
+        if marker.is_some() {
+            self.pop();
+        }
+
+        let text = self.lex_until('\n').trim_end_matches('\r').to_owned();
+        match marker {
+            Some('/') => Tok(DocComment(text)),
+            Some(_) => Tok(InnerDocComment(text)),
+            None => Comment,
+        }
+    }
+
+    /// Lexes a block comment, after its `/*`, until the matching `*/`, block
+    /// comments can be nested.
+    pub fn lex_block_comment(&mut self) -> PartTokenResult {
+        let start = self.index - 2;
+        let mut depth = 1;
+        loop {
+            match (self.peek(), self.peek_nth(1)) {
+                (Some('/'), Some('*')) => {
+                    self.index += 2;
+                    depth += 1;
+                }
+                (Some('*'), Some('/')) => {
+                    self.index += 2;
+                    depth -= 1;
+                    if depth == 0 {
+                        return Comment;
+                    }
+                }
+                (Some(_), _) => {
+                    self.pop();
+                }
+                (None, _) => {
+                    return Error(self.lctx.build(err::UnterminatedBlockComment {
+                        depth,
+                        location: start..start + 2,
+                    }))
+                }
+            }
         }
     }
 
@@ -346,37 +779,166 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    /// Takes a char and maps it to the corresponding escape sequence
+    /// Lexes an escape sequence in a string or a char literal, starting at its
+    /// backslash, and returns the char it stands for.
+    ///
     /// The argument 'is_string' is used to generate the error message.
-    pub fn make_escape_sequence(
-        &mut self,
-        es: char,
-        is_string: bool,
-    ) -> Result<char, Box<BuiltLog>> {
+    pub fn lex_escape(&mut self, is_string: bool) -> Result<char, Box<BuiltLog>> {
+        let start = self.index;
+        pop_expect!(self => Some('\\'));
+        let Some(es) = self.pop() else {
+            return Err(self.lctx.build_boxed(err::UnterminatedQuoteLit {
+                is_char: !is_string,
+                location: self.prev_idx..self.prev_idx + 1,
+            }));
+        };
+
         Ok(match es {
-            '0' => 0x00,
-            'n' => 0x0A,
-            'r' => 0x0D,
-            't' => 0x09,
-            'x' => todo!(
-                "this escape sequence will be supported but it's not actually implemented yet"
-            ),
-            '\\' => return Ok('\\'),
+            '0' => '\0',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '\\' | '\'' | '"' => es,
+            'x' => return self.lex_hex_escape(start),
+            'u' => return self.lex_unicode_escape(start),
             es => {
                 return Err(self.lctx.build_boxed(err::UnknownEscape {
                     escape: es,
-                    is_string,
-                    location: self.index - 2..self.index,
+                    location: start..self.index,
                 }))
             }
-        } as u8 as char)
+        })
+    }
+
+    /// Lexes the two hex digits of a `\xNN` escape, the char must be ASCII.
+    fn lex_hex_escape(&mut self, start: usize) -> Result<char, Box<BuiltLog>> {
+        let mut value = 0;
+        for _ in 0..2 {
+            match self.peek() {
+                Some(c) if c.is_ascii_hexdigit() => {
+                    self.pop();
+                    value = value * 16 + c.to_digit(16).unwrap();
+                }
+                Some(c) if c != '"' && c != '\'' && !c.is_whitespace() => {
+                    self.pop();
+                    return Err(self.escape_error(
+                        &format!("invalid character in numeric character escape: `{c}`"),
+                        "not a hexadecimal digit",
+                        self.index - c.len_utf8()..self.index,
+                    ));
+                }
+                _ => {
+                    return Err(self.escape_error(
+                        "numeric character escape is too short",
+                        r"expected two hex digits, like `\x7F`",
+                        start..self.index,
+                    ))
+                }
+            }
+        }
+
+        if value > 0x7F {
+            return Err(self.escape_error(
+                "out of range hex escape",
+                r"must be at most `\x7F`, use `\u{..}` for the other characters",
+                start..self.index,
+            ));
+        }
+        Ok(value as u8 as char)
+    }
+
+    /// Lexes the `{...}` of a `\u{...}` escape, with 1 to 6 hex digits of a
+    /// Unicode scalar value.
+    fn lex_unicode_escape(&mut self, start: usize) -> Result<char, Box<BuiltLog>> {
+        // the char after `\u` isn't consumed, it may end the literal
+        if self.peek() != Some('{') {
+            return Err(self.escape_error(
+                "incorrect unicode escape sequence",
+                r"expected `{` after `\u`, like `\u{1F600}`",
+                start..start + 2,
+            ));
+        }
+        self.pop();
+
+        let mut value = 0;
+        let mut digits = 0;
+        let mut invalid = None;
+        loop {
+            match self.peek() {
+                Some('}') => {
+                    self.pop();
+                    break;
+                }
+                Some(c) if c.is_ascii_hexdigit() => {
+                    self.pop();
+                    digits += 1;
+                    if digits <= 6 {
+                        value = value * 16 + c.to_digit(16).unwrap();
+                    }
+                }
+                Some('"' | '\'' | '\n') | None => {
+                    return Err(self.escape_error(
+                        "unterminated unicode escape",
+                        "missing a closing `}`",
+                        start..self.index,
+                    ))
+                }
+                Some(c) => {
+                    self.pop();
+                    // the escape is lexed until its end, to report only the
+                    // first invalid character
+                    invalid.get_or_insert((c, self.index - c.len_utf8()));
+                }
+            }
+        }
+
+        let location = start..self.index;
+        if let Some((c, idx)) = invalid {
+            return Err(self.escape_error(
+                &format!("invalid character in unicode escape: `{c}`"),
+                "not a hexadecimal digit",
+                idx..idx + c.len_utf8(),
+            ));
+        }
+        if digits == 0 {
+            return Err(self.escape_error(
+                "empty unicode escape",
+                "this escape must have at least 1 hex digit",
+                location,
+            ));
+        }
+        if digits > 6 {
+            return Err(self.escape_error(
+                "overlong unicode escape",
+                "must have at most 6 hex digits",
+                location,
+            ));
+        }
+        char::from_u32(value).ok_or_else(|| {
+            let cursor_msg = if (0xD800..=0xDFFF).contains(&value) {
+                "a surrogate isn't a unicode scalar value"
+            } else {
+                "must be at most `10FFFF`"
+            };
+            self.escape_error("invalid unicode character escape", cursor_msg, location)
+        })
+    }
+
+    /// Returns an error in an escape sequence.
+    fn escape_error(&self, msg: &str, cursor_msg: &str, location: CodeSpan) -> Box<BuiltLog> {
+        self.lctx.build_boxed(SimpleLog {
+            level: LogLevel::Error,
+            msg: msg.into(),
+            cursor_msg: Some(cursor_msg.into()),
+            location,
+        })
     }
 
     /// Lexes the input until the end of the string literal, handles escape sequences and replace with the corresponding char.
     ///
-    /// In case of an unknown character escape, both the backslash and the character following it will be ignored, and the error
+    /// In case of an invalid escape sequence, the escape is ignored, and the error
     /// will be pushed to the vector, and returned with the Str tokentype contening the string but without the erronous escape
-    /// character in a PartSuccess enum variant.
+    /// sequence in a PartSuccess enum variant.
     pub fn lex_string_literal(&mut self) -> PartTokenResult {
         pop_expect!(self => Some('"'));
         let mut str = String::new();
@@ -388,24 +950,10 @@ impl<'a> Lexer<'a> {
                     pop_expect!(self => Some('"'));
                     break;
                 }
-                Some('\\') => {
-                    pop_expect!(self => Some('\\'));
-                    let es = match self.pop() {
-                        Some(es) => es,
-                        _ => continue,
-                    };
-                    if es == '"' {
-                        str.push(es);
-                        continue;
-                    }
-                    match self.make_escape_sequence(es, true) {
-                        Ok(res) => str.push(res),
-                        Err(err) => {
-                            errs.push(*err);
-                            continue;
-                        }
-                    }
-                }
+                Some('\\') => match self.lex_escape(true) {
+                    Ok(c) => str.push(c),
+                    Err(err) => errs.push(*err),
+                },
                 Some(c) => {
                     str.push(c);
                     pop_expect!(self => Some(c));
@@ -413,7 +961,7 @@ impl<'a> Lexer<'a> {
                 None => {
                     return Error(self.lctx.build(err::UnterminatedQuoteLit {
                         is_char: false,
-                        location: self.prev_idx..self.index - 1,
+                        location: self.prev_idx..self.prev_idx + 1,
                     }));
                 }
             }
@@ -427,39 +975,196 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// Is the input the start of a raw string literal, a `r` followed by
+    /// zero or more `#` and a `"`?
+    fn is_raw_string_start(&self) -> bool {
+        let mut chars = self.file_text()[self.index..].chars().skip(1);
+        loop {
+            match chars.next() {
+                Some('#') => {}
+                Some('"') => return true,
+                _ => return false,
+            }
+        }
+    }
+
+    /// Lexes a raw string literal, `r"..."`, no escape sequence is processed.
+    ///
+    /// The delimiter can be extended with `#`s, so the string can contain
+    /// quotes, e.g: `r#"a "quoted" word"#`, the string only ends with a `"`
+    /// followed by as many `#` as after the `r`.
+    pub fn lex_raw_string(&mut self) -> PartTokenResult {
+        pop_expect!(self => Some('r'));
+        let mut hashes = 0;
+        while let Some('#') = self.peek() {
+            pop_expect!(self => Some('#'));
+            hashes += 1;
+        }
+        pop_expect!(self => Some('"'));
+        let content_start = self.index;
+
+        loop {
+            match self.pop() {
+                Some('"') => {
+                    let content_end = self.index - 1;
+                    let closing = self.file_text()[self.index..]
+                        .chars()
+                        .take(hashes)
+                        .take_while(|c| *c == '#')
+                        .count();
+                    if closing == hashes {
+                        self.index += hashes;
+                        return Tok(Str(self.file_text()[content_start..content_end].to_owned()));
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    return Error(self.lctx.build(err::UnterminatedRawStr {
+                        hashes,
+                        location: self.prev_idx..content_start,
+                    }));
+                }
+            }
+        }
+    }
+
+    /// Lexes a multi-line string literal, it starts with `"""` at the end of
+    /// a line and ends with `"""` at the start of another line, e.g:
+    ///
+    /// ```text
+    /// query := """
+    ///     SELECT *
+    ///     FROM users
+    ///     """
+    /// ```
+    ///
+    /// The indentation before the closing `"""` is removed from every line,
+    /// so the string above is `"SELECT *\nFROM users"`. Each line that isn't
+    /// blank must start with this indentation. The newline after the opening
+    /// `"""` and the one before the closing `"""` aren't part of the string.
+    /// Escape sequences are processed like in other string literals.
+    pub fn lex_multiline_string(&mut self) -> PartTokenResult {
+        for _ in 0..3 {
+            pop_expect!(self => Some('"'));
+        }
+        let text = self.file.text;
+        let mut errs = Vec::new();
+
+        // nothing can follow the opening delimiter on its line
+        while let Some(' ' | '\t' | '\r') = self.peek() {
+            self.pop();
+        }
+        match self.peek() {
+            Some('\n') => {
+                pop_expect!(self => Some('\n'));
+            }
+            Some(_) => {
+                let start = self.index;
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pop();
+                }
+                errs.push(self.lctx.build(err::ContentAfterMultilineOpening {
+                    location: start..self.index,
+                }));
+                self.pop();
+            }
+            None => {}
+        }
+
+        // every line is kept with its indentation, its start and its content
+        let mut lines: Vec<(&str, usize, String)> = Vec::new();
+        let indent = loop {
+            let line_start = self.index;
+            while let Some(' ' | '\t') = self.peek() {
+                self.pop();
+            }
+            let indent = &text[line_start..self.index];
+
+            if text[self.index..].starts_with(r#"""""#) {
+                self.index += 3;
+                break indent;
+            }
+
+            let mut content = String::new();
+            loop {
+                match self.peek() {
+                    Some('\n') => {
+                        pop_expect!(self => Some('\n'));
+                        break;
+                    }
+                    Some('\r') if self.peek_nth(1) == Some('\n') => {
+                        pop_expect!(self => Some('\r'));
+                    }
+                    Some('\\') => match self.lex_escape(true) {
+                        Ok(c) => content.push(c),
+                        Err(err) => errs.push(*err),
+                    },
+                    Some(c) => {
+                        content.push(c);
+                        pop_expect!(self => Some(c));
+                    }
+                    None => {
+                        return Error(self.lctx.build(err::UnterminatedQuoteLit {
+                            is_char: false,
+                            location: self.prev_idx..self.prev_idx + 3,
+                        }));
+                    }
+                }
+            }
+            lines.push((indent, line_start, content));
+        };
+
+        let mut str = String::new();
+        for (i, (line_indent, line_start, content)) in lines.into_iter().enumerate() {
+            if i != 0 {
+                str.push('\n');
+            }
+            if content.is_empty() {
+                // blank lines don't need the indentation
+                continue;
+            }
+            match line_indent.strip_prefix(indent) {
+                Some(rest) => str.push_str(rest),
+                None => {
+                    let end = line_start + line_indent.len().max(1);
+                    errs.push(self.lctx.build(err::InsufficientIndent {
+                        location: line_start..end,
+                    }));
+                    str.push_str(line_indent);
+                }
+            }
+            str.push_str(&content);
+        }
+        let tt = Str(str);
+
+        if errs.is_empty() {
+            Tok(tt)
+        } else {
+            PartSuccess(tt, errs)
+        }
+    }
+
     /// Lexe a char literal, and return it.
     pub fn lex_char_literal(&mut self) -> PartTokenResult {
+        let start = self.index;
         pop_expect!(self => Some('\''));
         let content: char;
 
         match self.peek() {
             Some('\\') => {
-                pop_expect!(self => Some('\\'));
-
-                content = match self.peek() {
-                    Some(es) => 'a: {
-                        pop_expect!(self => Some(es));
-                        if es == '\'' {
-                            break 'a es;
+                content = match self.lex_escape(false) {
+                    Ok(c) => c,
+                    Err(err) => {
+                        // the closing quote would start another char literal
+                        if let Some('\'') = self.peek() {
+                            pop_expect!(self => Some('\''));
                         }
-                        match self.make_escape_sequence(es, false) {
-                            Ok(c) => c,
-                            Err(err) => return Error(*err),
-                        }
-                    }
-                    None => {
-                        return Error(self.lctx.build(err::UnterminatedQuoteLit {
-                            is_char: true,
-                            location: self.get_pos(),
-                        }));
+                        return Error(*err);
                     }
                 };
-                pop_expect!(self => Some('\'');
-                    return Error(self.lctx.build(err::UnterminatedQuoteLit {
-                        is_char: true,
-                        location: self.prev_idx..self.index - 1,
-                    }))
-                );
+                if let Err(err) = self.lex_char_literal_end(start) {
+                    return Error(err);
+                }
             }
             Some('\'') => {
                 pop_expect!(self => Some('\''));
@@ -483,12 +1188,9 @@ impl<'a> Lexer<'a> {
                 pop_expect!(self => Some(c));
 
                 content = c;
-                pop_expect!(self => Some('\'');
-                    return Error(self.lctx.build(err::UnterminatedQuoteLit {
-                        is_char: true,
-                        location: self.prev_idx..self.index - 1,
-                    }))
-                );
+                if let Err(err) = self.lex_char_literal_end(start) {
+                    return Error(err);
+                }
             }
             None => return Error(self.lctx.build(UnexpectedEOF(self.get_pos()))),
         }
@@ -496,21 +1198,68 @@ impl<'a> Lexer<'a> {
         Tok(Char(content))
     }
 
+    /// Lexes the closing quote of the char literal starting at `start`, after
+    /// its char.
+    ///
+    /// If other chars come before a closing quote on the same line, they are
+    /// all skipped, e.g: `'ab'` is a single invalid char literal.
+    fn lex_char_literal_end(&mut self, start: usize) -> Result<(), BuiltLog> {
+        let line = self.file_text()[self.index..].split('\n').next().unwrap();
+        let Some(quote) = line.find('\'') else {
+            self.pop();
+            return Err(self.lctx.build(err::UnterminatedQuoteLit {
+                is_char: true,
+                location: self.prev_idx..self.prev_idx + 1,
+            }));
+        };
+        if quote == 0 {
+            pop_expect!(self => Some('\''));
+            return Ok(());
+        }
+
+        let end = self.index + quote + 1;
+        while self.index < end {
+            self.pop();
+        }
+        Err(self.lctx.build(SimpleLog {
+            level: LogLevel::Error,
+            msg: "char literal must contain one character".into(),
+            cursor_msg: Some("consider a string literal, between `\"`".into()),
+            location: start..end,
+        }))
+    }
+
     /// Lexes an operator if it matches an operators and return which operator was been lexed
     pub fn lex_operator(&mut self) -> Option<Operator> {
         use zom_common::operator::Operator::*;
-        match (self.peek(), self.peek_nth(1)) {
-            (Some(o1), wo2) => {
+        match (self.peek(), self.peek_nth(1), self.peek_nth(2)) {
+            (Some(o1), wo2, wo3) => {
                 let o2 = wo2.unwrap_or(' ');
-                let (op, len) = match (o1, o2) {
-                    ('>', '>') => (RArrow2, 2),
-                    ('>', '=') => (RArrowEqual, 2),
-                    ('<', '<') => (LArrow2, 2),
-                    ('<', '=') => (LArrowEqual, 2),
-                    ('=', '=') => (Equal2, 2),
-                    ('!', '=') => (ExclamationmarkEqual, 2),
-                    ('.', '*') => (DotAsterisk, 2),
-                    ('|', '|') => (Pipe2, 2),
+                let o3 = wo3.unwrap_or(' ');
+                let (op, len) = match (o1, o2, o3) {
+                    ('>', '>', '=') => (RArrow2Equal, 3),
+                    ('<', '<', '=') => (LArrow2Equal, 3),
+                    ('.', '.', '=') => (Dot2Equal, 3),
+                    ('>', '>', ..) => (RArrow2, 2),
+                    ('>', '=', ..) => (RArrowEqual, 2),
+                    ('<', '<', ..) => (LArrow2, 2),
+                    ('<', '=', ..) => (LArrowEqual, 2),
+                    ('=', '=', ..) => (Equal2, 2),
+                    ('!', '=', ..) => (ExclamationmarkEqual, 2),
+                    ('.', '*', ..) => (DotAsterisk, 2),
+                    ('.', '.', ..) => (Dot2, 2),
+                    ('=', '>', ..) => (EqualRArrow, 2),
+                    ('|', '|', ..) => (Pipe2, 2),
+                    ('|', '=', ..) => (PipeEqual, 2),
+                    ('&', '&', ..) => (Ampersand2, 2),
+                    ('&', '=', ..) => (AmpersandEqual, 2),
+                    ('*', '=', ..) => (AsteriskEqual, 2),
+                    ('^', '=', ..) => (CaretEqual, 2),
+                    ('-', '=', ..) => (MinusEqual, 2),
+                    ('-', '>', ..) => (MinusRArrow, 2),
+                    ('%', '=', ..) => (PercentEqual, 2),
+                    ('+', '=', ..) => (PlusEqual, 2),
+                    ('/', '=', ..) => (SlashEqual, 2),
                     ('&', ..) => (Ampersand, 1),
                     ('*', ..) => (Asterisk, 1),
                     ('^', ..) => (Caret, 1),
@@ -520,9 +1269,11 @@ impl<'a> Lexer<'a> {
                     ('<', ..) => (LArrow, 1),
                     ('-', ..) => (Minus, 1),
                     ('%', ..) => (Percent, 1),
+                    ('|', ..) => (Pipe, 1),
                     ('+', ..) => (Plus, 1),
                     ('>', ..) => (RArrow, 1),
                     ('/', ..) => (Slash, 1),
+                    ('~', ..) => (Tilde, 1),
                     _ => return None,
                 };
                 self.index += len;
@@ -532,3 +1283,27 @@ impl<'a> Lexer<'a> {
         }
     }
 }
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        let token = loop {
+            if self.finished || self.pending.as_ref().is_some_and(|t| t.tt == EOF) {
+                self.finished = true;
+                break self.pending.take()?;
+            }
+            let Some(token) = self.lex_token() else {
+                continue;
+            };
+            // the token before is returned now that its line is lexed
+            if let Some(prev) = self.pending.replace(token) {
+                break prev;
+            }
+        };
+        if let Some(recorded) = &mut self.recorded {
+            recorded.push(token.clone());
+        }
+        Some(token)
+    }
+}
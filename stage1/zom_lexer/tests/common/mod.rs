@@ -0,0 +1,33 @@
+//! Helpers shared by the tests of the lexer.
+
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use zom_common::token::{Token, TokenType};
+use zom_errors::prelude::*;
+use zom_errors::test_util::{diags, Diag};
+use zom_lexer::Lexer;
+
+/// Lexes the whole source, even after errors, and returns the tokens, without
+/// the EOF, and the diagnostics.
+pub fn lex_all(source: &str) -> (Vec<Token>, Vec<Diag>) {
+    let path = Path::new("test.zom");
+    let lctx = LogContext::new(source, path, LogOutput::default());
+    let mut lexer = Lexer::new(source, path, lctx);
+    let tokens = lexer
+        .by_ref()
+        .filter(|token| token.tt != TokenType::EOF)
+        .collect();
+    (tokens, diags(&lexer.lctx.stream()))
+}
+
+/// Returns the only diagnostic of the source.
+///
+/// Panic:
+///   If the source doesn't have exactly one diagnostic.
+pub fn single_diag(source: &str) -> Diag {
+    let (_, mut diags) = lex_all(source);
+    assert_eq!(diags.len(), 1, "{source:?}: {diags:?}");
+    diags.pop().unwrap()
+}
@@ -0,0 +1,133 @@
+//! Escape sequences in string and char literals.
+
+mod common;
+
+use common::{lex_all, single_diag};
+use zom_common::token::TokenType;
+use zom_errors::test_util::errors;
+
+/// Lexes a source made of a single literal, without errors.
+fn lex_lit(source: &str) -> TokenType {
+    let (mut tokens, diags) = lex_all(source);
+    assert!(diags.is_empty(), "{source:?}: {diags:?}");
+    assert_eq!(tokens.len(), 1, "{source:?}");
+    tokens.pop().unwrap().tt
+}
+
+fn str_lit(s: &str) -> TokenType {
+    TokenType::Str(s.to_owned())
+}
+
+#[test]
+fn simple_escapes() {
+    assert_eq!(lex_lit(r#""\0\n\r\t\\\'\"""#), str_lit("\0\n\r\t\\'\""));
+    assert_eq!(lex_lit(r"'\\'"), TokenType::Char('\\'));
+    assert_eq!(lex_lit(r"'\''"), TokenType::Char('\''));
+}
+
+#[test]
+fn hex_escapes() {
+    assert_eq!(lex_lit(r#""\x41\x7f\x00""#), str_lit("A\x7f\0"));
+    assert_eq!(lex_lit(r"'\x7F'"), TokenType::Char('\x7f'));
+
+    let diag = single_diag(r#""\xFF""#);
+    assert_eq!(diag.msg, "out of range hex escape");
+    assert_eq!(diag.span, Some(1..5));
+
+    let diag = single_diag(r#""\xG1""#);
+    assert_eq!(
+        diag.msg,
+        "invalid character in numeric character escape: `G`"
+    );
+    assert_eq!(diag.cursor_msg.as_deref(), Some("not a hexadecimal digit"));
+    assert_eq!(diag.span, Some(3..4));
+
+    let diag = single_diag(r#""\x4""#);
+    assert_eq!(diag.msg, "numeric character escape is too short");
+    assert_eq!(diag.span, Some(1..4));
+}
+
+#[test]
+fn unicode_escapes() {
+    assert_eq!(
+        lex_lit(r#""\u{48}\u{e9}\u{1F600}\u{10FFFF}""#),
+        str_lit("H\u{e9}\u{1F600}\u{10FFFF}")
+    );
+    assert_eq!(lex_lit(r"'\u{3bb}'"), TokenType::Char('λ'));
+
+    let diag = single_diag(r#""\u1234""#);
+    assert_eq!(diag.msg, "incorrect unicode escape sequence");
+    assert_eq!(diag.span, Some(1..3));
+
+    // the closing quote after `\u` still ends the literal
+    let diag = single_diag(r#""\u""#);
+    assert_eq!(diag.msg, "incorrect unicode escape sequence");
+    assert_eq!(
+        diag.cursor_msg.as_deref(),
+        Some(r"expected `{` after `\u`, like `\u{1F600}`")
+    );
+    assert_eq!(diag.span, Some(1..3));
+
+    let diag = single_diag(r"'\u'");
+    assert_eq!(diag.msg, "incorrect unicode escape sequence");
+
+    let diag = single_diag(r#""\u{12""#);
+    assert_eq!(diag.msg, "unterminated unicode escape");
+    assert_eq!(diag.cursor_msg.as_deref(), Some("missing a closing `}`"));
+
+    // only the first invalid character is reported
+    let diag = single_diag(r#""\u{1GH}""#);
+    assert_eq!(diag.msg, "invalid character in unicode escape: `G`");
+    assert_eq!(diag.span, Some(5..6));
+
+    let diag = single_diag(r#""\u{}""#);
+    assert_eq!(diag.msg, "empty unicode escape");
+    assert_eq!(diag.span, Some(1..5));
+
+    let diag = single_diag(r#""\u{0000041}""#);
+    assert_eq!(diag.msg, "overlong unicode escape");
+
+    let diag = single_diag(r#""\u{D800}""#);
+    assert_eq!(diag.msg, "invalid unicode character escape");
+    assert_eq!(
+        diag.cursor_msg.as_deref(),
+        Some("a surrogate isn't a unicode scalar value")
+    );
+    assert_eq!(diag.span, Some(1..9));
+
+    let diag = single_diag(r"'\u{110000}'");
+    assert_eq!(diag.msg, "invalid unicode character escape");
+    assert_eq!(diag.cursor_msg.as_deref(), Some("must be at most `10FFFF`"));
+}
+
+#[test]
+fn unknown_escapes() {
+    let diag = single_diag(r#""\q""#);
+    assert_eq!(diag.msg, "unknown character escape: 'q'");
+    assert_eq!(diag.span, Some(1..3));
+
+    // the string is still a token, without the invalid escapes
+    let (tokens, diags) = lex_all(r#""a\qb\xZZc""#);
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].tt, str_lit("abZc"));
+    assert_eq!(
+        errors(&diags),
+        [
+            "unknown character escape: 'q'",
+            "invalid character in numeric character escape: `Z`"
+        ]
+    );
+
+    // the closing quote of a char literal doesn't start another literal
+    let (tokens, diags) = lex_all(r"'\y' x");
+    assert_eq!(errors(&diags), ["unknown character escape: 'y'"]);
+    assert_eq!(tokens.last().unwrap().tt, TokenType::Ident("x".to_owned()));
+}
+
+#[test]
+fn unterminated_literals() {
+    assert_eq!(single_diag(r#""abc"#).msg, "unterminated string literal");
+    assert_eq!(single_diag(r"'a").msg, "unterminated char literal");
+    assert_eq!(single_diag(r"'\").msg, "unterminated char literal");
+    assert_eq!(single_diag(r"'\n").msg, "unterminated char literal");
+}
@@ -0,0 +1,87 @@
+//! Unicode identifiers, their normalization and the lint of the confusable
+//! ones.
+
+mod common;
+
+use common::{lex_all, single_diag};
+use zom_common::token::TokenType;
+use zom_errors::test_util::{errors, warnings};
+
+fn ident(name: &str) -> TokenType {
+    TokenType::Ident(name.to_owned())
+}
+
+/// Lexes the source, without diagnostics, and returns the types of its
+/// tokens.
+fn lex_tts(source: &str) -> Vec<TokenType> {
+    let (tokens, diags) = lex_all(source);
+    assert!(diags.is_empty(), "{source:?}: {diags:?}");
+    tokens.into_iter().map(|token| token.tt).collect()
+}
+
+#[test]
+fn unicode_identifiers() {
+    assert_eq!(
+        lex_tts("größe _名前 λόγος данные x_1"),
+        [
+            ident("größe"),
+            ident("_名前"),
+            ident("λόγος"),
+            ident("данные"),
+            ident("x_1"),
+        ]
+    );
+    // NFC doesn't fold the compatibility chars, the fullwidth `ｆｎ` isn't a
+    // keyword
+    assert_eq!(lex_tts("fn ｆｎ"), [TokenType::Fn, ident("ｆｎ")]);
+
+    // not XID_Start
+    let diag = single_diag("€uro");
+    assert_eq!(diag.msg, "unknown start of token, '€'");
+    assert_eq!(diag.span, Some(0..3));
+}
+
+#[test]
+fn nfc_normalization() {
+    let composed = "caf\u{e9}";
+    let decomposed = "cafe\u{301}";
+    let (tokens, diags) = lex_all(&format!("{composed} {decomposed}"));
+    assert!(diags.is_empty(), "{diags:?}");
+    assert_eq!(tokens[0].tt, ident(composed));
+    assert_eq!(tokens[1].tt, ident(composed));
+    // the span is still the one of the source
+    assert_eq!(tokens[1].span, 6..12);
+
+    // the Angstrom sign is the letter `Å` in NFC
+    assert_eq!(lex_tts("\u{212b}"), [ident("\u{c5}")]);
+}
+
+#[test]
+fn confusable_identifiers() {
+    // the `а` is Cyrillic
+    let source = "p\u{430}ypal";
+    let diag = single_diag(source);
+    assert_eq!(diag.level, "warning");
+    assert_eq!(diag.msg, "identifier `p\u{430}ypal` mixes scripts");
+    assert_eq!(
+        diag.cursor_msg.as_deref(),
+        Some("`\u{430}` (U+0430) is Cyrillic, it looks like a char of another script")
+    );
+    assert_eq!(diag.span, Some(1..3));
+
+    // the char of the minority script is reported, here the Latin `o`
+    let diag = single_diag("д\u{43e}м\u{43e}o");
+    assert_eq!(diag.span, Some(8..9));
+
+    // each identifier is reported once, and it's only a warning
+    let (tokens, diags) = lex_all("p\u{430}ypal + p\u{430}ypal + sc\u{43e}pe");
+    assert_eq!(tokens.len(), 5);
+    assert!(errors(&diags).is_empty());
+    assert_eq!(
+        warnings(&diags),
+        [
+            "identifier `p\u{430}ypal` mixes scripts",
+            "identifier `sc\u{43e}pe` mixes scripts"
+        ]
+    );
+}
@@ -0,0 +1,62 @@
+//! Lossless lexing must give back the source file byte for byte.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use zom_common::token::{Token, TokenType};
+use zom_errors::prelude::*;
+use zom_lexer::Lexer;
+
+fn collect_zom_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_zom_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "zom") {
+            files.push(path);
+        }
+    }
+}
+
+/// Concatenates the tokens with their trivia.
+fn reconstruct(tokens: &[Token], source: &str) -> String {
+    let mut out = String::new();
+    for token in tokens {
+        for trivia in &token.leading {
+            out.push_str(&source[trivia.span.clone()]);
+        }
+        if token.tt != TokenType::EOF {
+            out.push_str(&source[token.span.clone()]);
+        }
+        for trivia in &token.trailing {
+            out.push_str(&source[trivia.span.clone()]);
+        }
+    }
+    out
+}
+
+#[test]
+fn round_trip_examples() {
+    let example = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../example");
+    let mut files = Vec::new();
+    collect_zom_files(&example, &mut files);
+    assert!(!files.is_empty(), "no Zom file in `{}`", example.display());
+
+    for path in files {
+        let source = fs::read_to_string(&path).unwrap();
+        let lctx = LogContext::new(&source, &path, LogOutput::default());
+        let mut lexer = Lexer::lossless(&source, &path, lctx);
+        let tokens = match lexer.lex() {
+            FinalRes::Ok(tokens, _) => tokens,
+            FinalRes::Err(_) => panic!("couldn't lex `{}`", path.display()),
+        };
+        assert_eq!(
+            reconstruct(&tokens, &source),
+            source,
+            "`{}` isn't lexed losslessly",
+            path.display()
+        );
+    }
+}
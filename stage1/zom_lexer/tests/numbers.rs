@@ -0,0 +1,200 @@
+//! Integer and float literals.
+
+mod common;
+
+use std::path::Path;
+
+use common::{lex_all, single_diag};
+use zom_common::{
+    operator::Operator,
+    token::{IntLit, Radix, Token, TokenType},
+};
+use zom_errors::{prelude::*, test_util::errors};
+use zom_lexer::Lexer;
+
+fn lex(source: &str) -> Option<Vec<Token>> {
+    let path = Path::new("numbers.zom");
+    let lctx = LogContext::new(source, path, LogOutput::default());
+    match Lexer::new(source, path, lctx).lex() {
+        FinalRes::Ok(tokens, _) => Some(tokens),
+        FinalRes::Err(_) => None,
+    }
+}
+
+/// Lexes a source and returns the types of its tokens, without the EOF.
+fn lex_tts(source: &str) -> Vec<TokenType> {
+    let tokens = lex(source).unwrap_or_else(|| panic!("couldn't lex {source:?}"));
+    tokens
+        .into_iter()
+        .map(|t| t.tt)
+        .filter(|tt| *tt != TokenType::EOF)
+        .collect()
+}
+
+fn int(value: u128, radix: Radix) -> TokenType {
+    TokenType::Int(IntLit {
+        value,
+        radix,
+        suffix: None,
+    })
+}
+
+fn suffixed(value: u128, radix: Radix, suffix: &str) -> TokenType {
+    TokenType::Int(IntLit {
+        value,
+        radix,
+        suffix: Some(suffix.to_owned()),
+    })
+}
+
+#[test]
+fn radixes() {
+    assert_eq!(lex_tts("0xFF"), [int(255, Radix::Hexadecimal)]);
+    assert_eq!(
+        lex_tts("0xdead_BEEF"),
+        [int(0xdead_beef, Radix::Hexadecimal)]
+    );
+    assert_eq!(lex_tts("0o755"), [int(0o755, Radix::Octal)]);
+    assert_eq!(lex_tts("0b1010"), [int(10, Radix::Binary)]);
+    assert_eq!(lex_tts("0b_1111_0000"), [int(0xF0, Radix::Binary)]);
+    assert_eq!(lex_tts("1_000_000"), [int(1_000_000, Radix::Decimal)]);
+    assert_eq!(lex_tts("0"), [int(0, Radix::Decimal)]);
+}
+
+#[test]
+fn suffixes() {
+    assert_eq!(lex_tts("255u8"), [suffixed(255, Radix::Decimal, "u8")]);
+    assert_eq!(
+        lex_tts("1_000_i64"),
+        [suffixed(1000, Radix::Decimal, "i64")]
+    );
+    assert_eq!(
+        lex_tts("0xFFusize"),
+        [suffixed(255, Radix::Hexadecimal, "usize")]
+    );
+    assert_eq!(lex_tts("0b1u1"), [suffixed(1, Radix::Binary, "u1")]);
+    // the lexer keeps any suffix, the parser checks it's a primitive type
+    assert_eq!(lex_tts("7foo"), [suffixed(7, Radix::Decimal, "foo")]);
+}
+
+#[test]
+fn full_width_values() {
+    assert_eq!(
+        lex_tts("340_282_366_920_938_463_463_374_607_431_768_211_455"),
+        [int(u128::MAX, Radix::Decimal)]
+    );
+    assert_eq!(
+        lex_tts("0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF"),
+        [int(u128::MAX, Radix::Hexadecimal)]
+    );
+
+    let diag = single_diag("x = 340282366920938463463374607431768211456;");
+    assert_eq!(diag.msg, "integer literal is too large");
+    assert_eq!(diag.cursor_msg.as_deref(), Some("doesn't fit in `u128`"));
+    assert_eq!(diag.span, Some(4..43));
+}
+
+#[test]
+fn invalid_digits() {
+    let diag = single_diag("0b102");
+    assert_eq!(diag.msg, "invalid digit `2` in binary literal");
+    assert_eq!(diag.cursor_msg.as_deref(), Some("not a valid binary digit"));
+    assert_eq!(diag.span, Some(4..5));
+
+    let diag = single_diag("a + 0o7_8");
+    assert_eq!(diag.msg, "invalid digit `8` in octal literal");
+    assert_eq!(diag.span, Some(8..9));
+
+    let diag = single_diag("0x");
+    assert_eq!(diag.msg, "malformed number literal");
+    assert_eq!(
+        diag.cursor_msg.as_deref(),
+        Some("expected a hexadecimal digit after `0x`")
+    );
+
+    // the literal is still a token, and the lexer goes on
+    let (tokens, diags) = lex_all("0b2 + 0b3");
+    assert_eq!(tokens.len(), 3);
+    assert_eq!(
+        errors(&diags),
+        [
+            "invalid digit `2` in binary literal",
+            "invalid digit `3` in binary literal"
+        ]
+    );
+}
+
+#[test]
+fn uppercase_prefixes() {
+    // every base prefix is lowercase, the literal is still lexed
+    for (source, prefix, radix) in [
+        ("0XFF", "0x", Radix::Hexadecimal),
+        ("0O17", "0o", Radix::Octal),
+        ("0B101", "0b", Radix::Binary),
+    ] {
+        let (tokens, diags) = lex_all(source);
+        assert_eq!(tokens.len(), 1);
+        assert!(matches!(tokens[0].tt, TokenType::Int(IntLit { radix: r, .. }) if r == radix));
+        assert_eq!(errors(&diags), ["invalid base prefix for number literal"]);
+        assert_eq!(
+            diags[0].cursor_msg.as_deref(),
+            Some(format!("base prefixes are lowercase, like `{prefix}`").as_str())
+        );
+        assert_eq!(diags[0].span, Some(0..2));
+    }
+
+    // the errors of the literal itself are still reported
+    let (_, diags) = lex_all("0B102");
+    assert_eq!(
+        errors(&diags),
+        [
+            "invalid base prefix for number literal",
+            "invalid digit `2` in binary literal"
+        ]
+    );
+}
+
+#[test]
+fn floats() {
+    assert_eq!(lex_tts("2.75"), [TokenType::Float(2.75)]);
+    assert_eq!(lex_tts("1_000.5"), [TokenType::Float(1000.5)]);
+    assert_eq!(lex_tts("1e-9"), [TokenType::Float(1e-9)]);
+    assert_eq!(lex_tts("2.5E+3"), [TokenType::Float(2500.0)]);
+
+    let diag = single_diag("1.5f32");
+    assert_eq!(diag.msg, "invalid suffix `f32` for float literal");
+    assert_eq!(diag.span, Some(3..6));
+    let diag = single_diag("1e+");
+    assert_eq!(
+        diag.cursor_msg.as_deref(),
+        Some("expected at least one digit in the exponent")
+    );
+}
+
+#[test]
+fn hex_floats() {
+    assert_eq!(lex_tts("0x1.8p3"), [TokenType::Float(12.0)]);
+    assert_eq!(lex_tts("0x1p-2"), [TokenType::Float(0.25)]);
+    assert_eq!(lex_tts("0xA_Bp0"), [TokenType::Float(171.0)]);
+    assert!(lex("0x1p").is_none());
+}
+
+#[test]
+fn member_access_on_hex_integer() {
+    assert_eq!(
+        lex_tts("0x1.field"),
+        [
+            int(1, Radix::Hexadecimal),
+            TokenType::Oper(Operator::Dot),
+            TokenType::Ident("field".to_owned()),
+        ]
+    );
+    assert_eq!(
+        lex_tts("0xF.abc"),
+        [
+            int(15, Radix::Hexadecimal),
+            TokenType::Oper(Operator::Dot),
+            TokenType::Ident("abc".to_owned()),
+        ]
+    );
+}
@@ -0,0 +1,88 @@
+//! Operators, the longest operator is always lexed.
+
+mod common;
+
+use common::lex_all;
+use zom_common::{
+    operator::{Operator, OPERATORS},
+    token::TokenType,
+};
+
+/// Lexes the source, without errors, and returns the text of the operators
+/// and of the other tokens.
+fn lex_texts(source: &str) -> Vec<String> {
+    let (tokens, diags) = lex_all(source);
+    assert!(diags.is_empty(), "{source:?}: {diags:?}");
+    tokens
+        .into_iter()
+        .map(|token| match token.tt {
+            TokenType::Oper(op) => op.to_string(),
+            _ => source[token.span].to_owned(),
+        })
+        .collect()
+}
+
+#[test]
+fn every_operator() {
+    for op in OPERATORS {
+        let (tokens, diags) = lex_all(op);
+        assert!(diags.is_empty(), "{op:?}: {diags:?}");
+        match &tokens[..] {
+            [token] => {
+                let TokenType::Oper(lexed) = &token.tt else {
+                    panic!("{op:?} isn't an operator: {token:?}");
+                };
+                assert_eq!(lexed.to_string(), op);
+                assert_eq!(token.span, 0..op.len());
+            }
+            _ => panic!("{op:?} isn't a single token: {tokens:?}"),
+        }
+    }
+}
+
+#[test]
+fn longest_operator() {
+    assert_eq!(lex_texts("a>>=b"), ["a", ">>=", "b"]);
+    assert_eq!(lex_texts("a<<=1"), ["a", "<<=", "1"]);
+    assert_eq!(lex_texts("a>>>b"), ["a", ">>", ">", "b"]);
+    assert_eq!(lex_texts("0..=9"), ["0", "..=", "9"]);
+    assert_eq!(lex_texts("0..9"), ["0", "..", "9"]);
+    assert_eq!(lex_texts("a&&b&c"), ["a", "&&", "b", "&", "c"]);
+    assert_eq!(lex_texts("a||b|c"), ["a", "||", "b", "|", "c"]);
+    assert_eq!(lex_texts("a|=~b"), ["a", "|=", "~", "b"]);
+    assert_eq!(lex_texts("a/=b%=c"), ["a", "/=", "b", "%=", "c"]);
+}
+
+#[test]
+fn arrows() {
+    assert_eq!(
+        lex_texts("self->count += 1"),
+        ["self", "->", "count", "+=", "1"]
+    );
+    assert_eq!(lex_texts("a - >b"), ["a", "-", ">", "b"]);
+    assert_eq!(lex_texts("a-->b"), ["a", "-", "->", "b"]);
+    assert_eq!(lex_texts("x => y"), ["x", "=>", "y"]);
+    assert_eq!(lex_texts("p.*"), ["p", ".*"]);
+}
+
+#[test]
+fn compound_assignments() {
+    let (tokens, _) = lex_all("a += 1; a -= 1; a *= 1; a ^= 1; a &= 1;");
+    let ops: Vec<Operator> = tokens
+        .into_iter()
+        .filter_map(|token| match token.tt {
+            TokenType::Oper(op) => Some(op),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        ops,
+        [
+            Operator::PlusEqual,
+            Operator::MinusEqual,
+            Operator::AsteriskEqual,
+            Operator::CaretEqual,
+            Operator::AmpersandEqual,
+        ]
+    );
+}
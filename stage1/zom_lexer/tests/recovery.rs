@@ -0,0 +1,64 @@
+//! The lexer keeps lexing after an error.
+
+mod common;
+
+use std::path::Path;
+
+use common::lex_all;
+
+use zom_common::token::TokenType;
+use zom_errors::{prelude::*, test_util::errors};
+use zom_lexer::Lexer;
+
+#[test]
+fn invalid_tokens() {
+    let source = "a $ b # c `";
+    let path = Path::new("recovery.zom");
+    let lctx = LogContext::new(source, path, LogOutput::default());
+    let mut lexer = Lexer::new(source, path, lctx);
+
+    let tokens: Vec<TokenType> = lexer.by_ref().map(|token| token.tt).collect();
+    assert_eq!(
+        tokens,
+        [
+            TokenType::Ident("a".to_owned()),
+            TokenType::Invalid,
+            TokenType::Ident("b".to_owned()),
+            TokenType::Invalid,
+            TokenType::Ident("c".to_owned()),
+            TokenType::Invalid,
+            TokenType::EOF,
+        ]
+    );
+    assert!(lexer.lctx.failed());
+    assert_eq!(lexer.next(), None);
+}
+
+#[test]
+fn multi_char_literals() {
+    // the whole literal is a single invalid token, with a single error
+    let (tokens, diags) = lex_all("'ab' c '\\nx' 'd'");
+    let tokens: Vec<TokenType> = tokens.into_iter().map(|token| token.tt).collect();
+    assert_eq!(
+        tokens,
+        [
+            TokenType::Invalid,
+            TokenType::Ident("c".to_owned()),
+            TokenType::Invalid,
+            TokenType::Char('d'),
+        ]
+    );
+    assert_eq!(
+        errors(&diags),
+        [
+            "char literal must contain one character",
+            "char literal must contain one character"
+        ]
+    );
+    assert_eq!(diags[0].span, Some(0..4));
+    assert_eq!(diags[1].span, Some(7..12));
+
+    // without a closing quote on the line, it's unterminated
+    let (_, diags) = lex_all("'ab\n'c'");
+    assert_eq!(errors(&diags)[0], "unterminated char literal");
+}
@@ -0,0 +1,73 @@
+//! The spans of the tokens are byte ranges in the source, even after
+//! non-ASCII text.
+
+mod common;
+
+use common::{lex_all, single_diag};
+
+/// Lexes the source, without errors, and returns the text of each token.
+fn token_texts(source: &str) -> Vec<&str> {
+    let (tokens, diags) = lex_all(source);
+    assert!(diags.is_empty(), "{source:?}: {diags:?}");
+    tokens
+        .iter()
+        .map(|token| &source[token.span.clone()])
+        .collect()
+}
+
+#[test]
+fn after_non_ascii_strings() {
+    assert_eq!(
+        token_texts(r#"print("héllo wörld", "日本語"); x"#),
+        [
+            "print",
+            "(",
+            r#""héllo wörld""#,
+            ",",
+            r#""日本語""#,
+            ")",
+            ";",
+            "x"
+        ]
+    );
+    assert_eq!(
+        token_texts("r#\"ünï\"# \"\"\"\n  ç\n  \"\"\" y"),
+        ["r#\"ünï\"#", "\"\"\"\n  ç\n  \"\"\"", "y"]
+    );
+}
+
+#[test]
+fn after_non_ascii_chars_and_comments() {
+    assert_eq!(
+        token_texts("'é' '\u{1F600}' // ça va\nz /* 🦀 /* ñ */ */ w"),
+        ["'é'", "'\u{1F600}'", "z", "w"]
+    );
+    assert_eq!(
+        token_texts("/// Crée un point.\nfn"),
+        ["/// Crée un point.", "fn"]
+    );
+}
+
+#[test]
+fn non_ascii_identifiers() {
+    assert_eq!(
+        token_texts("var größe = straße + 名前;"),
+        ["var", "größe", "=", "straße", "+", "名前", ";"]
+    );
+}
+
+#[test]
+fn diagnostics_after_non_ascii() {
+    let source = "\"ünïcödé\" $";
+    let diag = single_diag(source);
+    assert_eq!(diag.msg, "unknown start of token, '$'");
+    assert_eq!(&source[diag.span.unwrap()], "$");
+
+    let source = "// 日本語\n\"\\q\"";
+    let diag = single_diag(source);
+    assert_eq!(&source[diag.span.unwrap()], "\\q");
+
+    let source = "'é' 0b12";
+    let diag = single_diag(source);
+    assert_eq!(&source[diag.span.unwrap()], "2");
+}
@@ -0,0 +1,85 @@
+//! Raw and multi-line string literals.
+
+mod common;
+
+use std::path::Path;
+
+use common::single_diag;
+
+use zom_common::token::{Token, TokenType};
+use zom_errors::prelude::*;
+use zom_lexer::Lexer;
+
+fn lex(source: &str) -> Option<Vec<Token>> {
+    let path = Path::new("strings.zom");
+    let lctx = LogContext::new(source, path, LogOutput::default());
+    match Lexer::new(source, path, lctx).lex() {
+        FinalRes::Ok(tokens, _) => Some(tokens),
+        FinalRes::Err(_) => None,
+    }
+}
+
+/// Lexes a source made of a single string literal, and returns its content
+/// and its span.
+fn lex_str(source: &str) -> (String, std::ops::Range<usize>) {
+    let tokens = lex(source).unwrap_or_else(|| panic!("couldn't lex {source:?}"));
+    match &tokens[0].tt {
+        TokenType::Str(s) => (s.clone(), tokens[0].span.clone()),
+        tt => panic!("expected a string literal, found {tt:?}"),
+    }
+}
+
+#[test]
+fn raw_strings() {
+    assert_eq!(lex_str(r#"r"a\nb""#), (r"a\nb".to_owned(), 0..7));
+    assert_eq!(
+        lex_str(r##"r#"say "hi""#"##),
+        (r#"say "hi""#.to_owned(), 0..13)
+    );
+    assert_eq!(
+        lex_str(r###"r##"a "# b"## x"###),
+        (r##"a "# b"##.to_owned(), 0..13)
+    );
+    assert!(lex(r###"r##"never closed"#"###).is_none());
+}
+
+#[test]
+fn multiline_strings() {
+    let source = "\"\"\"\n    SELECT *\n      FROM t\n\n    WHERE a = \\\"1\\\"\n    \"\"\"";
+    assert_eq!(
+        lex_str(source),
+        (
+            "SELECT *\n  FROM t\n\nWHERE a = \"1\"".to_owned(),
+            0..source.len()
+        )
+    );
+    assert_eq!(lex_str("\"\"\"\r\n  a\r\n  \"\"\"").0, "a");
+    assert!(lex("\"\"\"\n    a\n  b\n    \"\"\"").is_none());
+    assert!(lex("\"\"\"\n    a\n").is_none());
+}
+
+#[test]
+fn multiline_string_layout() {
+    let diag = single_diag("\"\"\" a\n    b\n    \"\"\"");
+    assert_eq!(diag.msg, "unexpected content after the opening `\"\"\"`");
+    assert_eq!(
+        diag.cursor_msg.as_deref(),
+        Some("a multi-line string starts on the next line")
+    );
+    assert_eq!(diag.span, Some(4..5));
+
+    let diag = single_diag("\"\"\"\n    a\n  b\n    \"\"\"");
+    assert_eq!(diag.msg, "insufficient indentation in a multi-line string");
+    assert_eq!(
+        diag.cursor_msg.as_deref(),
+        Some("this line must start with the indentation of the closing `\"\"\"`")
+    );
+    assert_eq!(diag.span, Some(10..12));
+
+    let diag = single_diag(r#"r#"never closed""#);
+    assert_eq!(diag.msg, "unterminated raw string literal");
+    assert_eq!(
+        diag.cursor_msg.as_deref(),
+        Some("this string is never closed with a `\"#`")
+    );
+}
@@ -2,7 +2,7 @@
 use crate::prelude::*;
 use crate::stmt::Statement;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Block {
     pub stmts: Vec<Statement>,
     pub span: Range<usize>,
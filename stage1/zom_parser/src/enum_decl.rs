@@ -0,0 +1,107 @@
+//! Module responsible for parsing enum declarations.
+use crate::{expr::Expression, prelude::*, types::Type};
+
+#[derive(Debug, Serialize)]
+pub struct EnumDecl {
+    pub name: String,
+    /// The integer type of the discriminants, e.g: `u8` in `enum Color: u8`
+    pub repr: Option<Type>,
+    pub variants: Vec<Variant>,
+    /// Span of `enum NAME`, the variants aren't in it
+    pub span: Range<usize>,
+}
+
+impl Parse for EnumDecl {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+
+        expect_token!(parser => [T::Enum, ()], Enum, parsed_tokens);
+        let start = span_toks!(start parsed_tokens);
+
+        let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+        let end = span_toks!(end parsed_tokens);
+
+        let repr = if token_parteq!(parser.last(), T::Colon) {
+            expect_token!(parser => [T::Colon, ()], Colon, parsed_tokens);
+            Some(parse_try!(parser => Type, parsed_tokens))
+        } else {
+            None
+        };
+
+        expect_token!(parser => [T::OpenBrace, ()], OpenBrace, parsed_tokens);
+
+        let mut variants = Vec::new();
+        while !token_parteq!(parser.last(), T::CloseBrace) {
+            variants.push(parse_try!(parser => Variant, parsed_tokens));
+            expect_token!(parser => [T::Comma, (); T::CloseBrace, break], [Comma, CloseBrace], parsed_tokens);
+        }
+
+        expect_token!(parser => [T::CloseBrace, ()], CloseBrace, parsed_tokens);
+
+        Good(
+            EnumDecl {
+                name,
+                repr,
+                variants,
+                span: start..end,
+            },
+            parsed_tokens,
+        )
+    }
+}
+
+/// A variant of an enum, `IDENT [ ( TYPE, TYPE, .. ) ] [ = EXPR ]`
+#[derive(Debug, Serialize)]
+pub struct Variant {
+    /// The `///` doc comments before the variant
+    pub docs: Vec<String>,
+    pub name: String,
+    /// Types of the payload of the variant, empty if it has none
+    pub fields: Vec<Type>,
+    pub discriminant: Option<Expression>,
+    pub span: Range<usize>,
+}
+
+impl Parse for Variant {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+
+        let docs = parser.take_docs();
+        let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+        let start = span_toks!(start parsed_tokens);
+
+        let mut fields = Vec::new();
+        if token_parteq!(parser.last(), T::OpenParen) {
+            expect_token!(parser => [T::OpenParen, ()], OpenParen, parsed_tokens);
+            while !token_parteq!(parser.last(), T::CloseParen) {
+                fields.push(parse_try!(parser => Type, parsed_tokens));
+                expect_token!(parser => [T::Comma, (); T::CloseParen, break], [Comma, CloseParen], parsed_tokens);
+            }
+            expect_token!(parser => [T::CloseParen, ()], CloseParen, parsed_tokens);
+        }
+
+        let discriminant = if token_parteq!(parser.last(), T::Oper(Operator::Equal)) {
+            expect_token!(parser => [T::Oper(Operator::Equal), ()], T::Oper(Operator::Equal), parsed_tokens);
+            Some(parse_try!(parser => Expression, parsed_tokens))
+        } else {
+            None
+        };
+
+        let end = span_toks!(end parsed_tokens);
+
+        Good(
+            Variant {
+                docs,
+                name,
+                fields,
+                discriminant,
+                span: start..end,
+            },
+            parsed_tokens,
+        )
+    }
+}
@@ -1,7 +1,16 @@
 //! Module responsible for parsing expression.
-use crate::prelude::*;
+use std::fmt;
 
-#[derive(Debug, Clone)]
+use zom_common::token::Radix;
+
+use crate::{
+    block::Block,
+    pattern::Pattern,
+    prelude::*,
+    types::{PrimitiveTy, Type},
+};
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Expression {
     pub expr: Expr,
     pub span: CodeSpan,
@@ -29,6 +38,12 @@ impl Parse for Expression {
                 T::Oper(Operator::Dot) => {
                     parse_try!(fn; parser => parse_member_access_expr, parsed_tokens, &result)
                 }
+                T::Oper(Operator::MinusRArrow) => {
+                    parse_try!(fn; parser => parse_pointer_member_access_expr, parsed_tokens, &result)
+                }
+                T::OpenBracket => {
+                    parse_try!(fn; parser => parse_index_expr, parsed_tokens, &result)
+                }
                 T::Oper(op) if UnaryOperation::from_op(op.clone(), true).is_some() => {
                     parse_try!(fn; parser => parse_post_unary_expr, parsed_tokens, &result)
                 }
@@ -44,7 +59,7 @@ impl Parse for Expression {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum Expr {
     BinaryExpr {
         lhs: Box<Expression>,
@@ -59,6 +74,10 @@ pub enum Expr {
         expr: Box<Expression>,
         member_name: String,
     },
+    PointerMemberAccessExpr {
+        expr: Box<Expression>,
+        member_name: String,
+    },
     UnaryExpr {
         op: UnaryOperation,
         expr: Box<Expression>,
@@ -68,9 +87,37 @@ pub enum Expr {
         predicate: Box<Expression>,
         false_expr: Box<Expression>,
     },
+    StructLitExpr {
+        ty: Type,
+        fields: Vec<FieldInit>,
+    },
+    MatchExpr {
+        scrutinee: Box<Expression>,
+        arms: Vec<MatchArm>,
+    },
+    /// `EXPR [ EXPR ]`, an element of an array or of a slice
+    IndexExpr {
+        expr: Box<Expression>,
+        index: Box<Expression>,
+    },
+    /// `EXPR [ [ EXPR ] .. [ EXPR ] ]`, the slice of the elements from `start`
+    /// to `end`, excluded
+    SliceExpr {
+        expr: Box<Expression>,
+        start: Option<Box<Expression>>,
+        end: Option<Box<Expression>>,
+    },
+    /// `[ EXPR, EXPR, .. ]`
+    ArrayLitExpr(Vec<Expression>),
 
     // Primary Expression
-    IntLitExpr(u64),
+    IntLitExpr {
+        value: u128,
+        radix: Radix,
+        /// The type given by the suffix, e.g: `u8` in `255u8`.
+        suffix: Option<PrimitiveTy>,
+    },
+    FloatLitExpr(f64),
     CharLitExpr(char),
     StrLitExpr(String),
     BoolLitExpr(bool),
@@ -84,13 +131,19 @@ impl Parse for Expr {
     /// Parsing for Primary Expressions
     fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
         // Only parses Primary Expression, so not BinaryExpr and UnaryExpr
+        if is_struct_lit(parser) {
+            return parse_struct_lit_expr(parser);
+        }
         match &parser.last().tt {
             T::Int(_) => parse_intlit_expr(parser),
+            T::Float(_) => parse_floatlit_expr(parser),
             T::Char(_) => parse_charlit_expr(parser),
             T::Str(_) => parse_strlit_expr(parser),
             T::True | T::False => parse_boollit_expr(parser),
             T::Ident(_) => parse_identifier_expr(parser),
             T::OpenParen => parse_parenthesized_expr(parser),
+            T::OpenBracket => parse_array_lit_expr(parser),
+            T::Match => parse_match_expr(parser),
             T::Oper(op) if UnaryOperation::from_op(op.clone(), false).is_some() => {
                 parse_pre_unary_expr(parser)
             }
@@ -107,10 +160,53 @@ pub fn parse_intlit_expr(parser: &mut Parser) -> ParsingResult<Expression> {
     let mut parsed_tokens = Vec::new();
 
     let int = expect_token!(parser => [T::Int(i), i.clone()], IntLit, parsed_tokens);
+    let span = span_toks!(parsed_tokens);
+
+    let suffix = match &int.suffix {
+        Some(name) => match PrimitiveTy::from_name(name) {
+            Some(ty) if ty.is_int() => Some(ty),
+            ty => {
+                // the suffix is at the end of the literal
+                let location = span.end - name.len()..span.end;
+                return Error(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("invalid suffix `{name}` for integer literal").into(),
+                    cursor_msg: Some(
+                        match ty {
+                            Some(_) => format!("`{name}` isn't an integer type"),
+                            None => "expected an integer type, like `u8` or `i64`".to_owned(),
+                        }
+                        .into(),
+                    ),
+                    location,
+                }));
+            }
+        },
+        None => None,
+    };
 
     Good(
         Expression {
-            expr: Expr::IntLitExpr(int),
+            expr: Expr::IntLitExpr {
+                value: int.value,
+                radix: int.radix,
+                suffix,
+            },
+            span,
+        },
+        parsed_tokens,
+    )
+}
+
+/// Parsing for `FLOAT_LITERAL` expression
+pub fn parse_floatlit_expr(parser: &mut Parser) -> ParsingResult<Expression> {
+    let mut parsed_tokens = Vec::new();
+
+    let float = expect_token!(parser => [T::Float(f), *f], FloatLit, parsed_tokens);
+
+    Good(
+        Expression {
+            expr: Expr::FloatLitExpr(float),
             span: span_toks!(parsed_tokens),
         },
         parsed_tokens,
@@ -121,7 +217,7 @@ pub fn parse_intlit_expr(parser: &mut Parser) -> ParsingResult<Expression> {
 pub fn parse_charlit_expr(parser: &mut Parser) -> ParsingResult<Expression> {
     let mut parsed_tokens = Vec::new();
 
-    let char = expect_token!(parser => [T::Char(c), c.clone()], IntLit, parsed_tokens);
+    let char = expect_token!(parser => [T::Char(c), *c], IntLit, parsed_tokens);
 
     Good(
         Expression {
@@ -177,7 +273,7 @@ pub fn parse_identifier_expr(parser: &mut Parser) -> ParsingResult<Expression> {
     )
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize)]
 pub enum BinOperation {
     Mul,
     Div,
@@ -195,6 +291,8 @@ pub enum BinOperation {
     And,
     Or,
     Xor,
+    LogicalAnd,
+    LogicalOr,
 }
 
 impl TryFrom<Operator> for BinOperation {
@@ -205,6 +303,7 @@ impl TryFrom<Operator> for BinOperation {
         use zom_common::operator::Operator::*;
         Ok(match op {
             Ampersand => BOp::And,
+            Ampersand2 => BOp::LogicalAnd,
             Asterisk => BOp::Mul,
             Caret => BOp::Xor,
             Equal2 => BOp::CompEq,
@@ -214,7 +313,8 @@ impl TryFrom<Operator> for BinOperation {
             LArrowEqual => BOp::CompLTE,
             Minus => BOp::Sub,
             Percent => BOp::Rem,
-            Pipe2 => BOp::Or,
+            Pipe => BOp::Or,
+            Pipe2 => BOp::LogicalOr,
             Plus => BOp::Add,
             RArrow => BOp::CompGT,
             RArrow2 => BOp::RShift,
@@ -225,6 +325,67 @@ impl TryFrom<Operator> for BinOperation {
     }
 }
 
+impl BinOperation {
+    /// Is the operation a comparison, resulting in a `bool`?
+    pub fn is_comparison(&self) -> bool {
+        use self::BinOperation::*;
+        matches!(self, CompLT | CompGT | CompLTE | CompGTE | CompEq | CompNe)
+    }
+
+    /// Is the operation a short-circuiting logical operation, `&&` or `||`?
+    pub fn is_logical(&self) -> bool {
+        matches!(self, BinOperation::LogicalAnd | BinOperation::LogicalOr)
+    }
+
+    /// Returns the binary operation applied by a compound assignment
+    /// operator, e.g: `Add` for `+=`
+    pub fn from_compound(op: &Operator) -> Option<BinOperation> {
+        use self::BinOperation as BOp;
+        use zom_common::operator::Operator::*;
+        Some(match op {
+            AmpersandEqual => BOp::And,
+            AsteriskEqual => BOp::Mul,
+            CaretEqual => BOp::Xor,
+            LArrow2Equal => BOp::LShift,
+            MinusEqual => BOp::Sub,
+            PercentEqual => BOp::Rem,
+            PipeEqual => BOp::Or,
+            PlusEqual => BOp::Add,
+            RArrow2Equal => BOp::RShift,
+            SlashEqual => BOp::Div,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for BinOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::BinOperation::*;
+        use zom_common::operator::*;
+        let op = match self {
+            Mul => OP_ASTERISK,
+            Div => OP_SLASH,
+            Rem => OP_PERCENT,
+            Add => OP_PLUS,
+            Sub => OP_MINUS,
+            RShift => OP_RARROW2,
+            LShift => OP_LARROW2,
+            CompLT => OP_LARROW,
+            CompGT => OP_RARROW,
+            CompLTE => OP_LARROWEQUAL,
+            CompGTE => OP_RARROWEQUAL,
+            CompEq => OP_EQUAL2,
+            CompNe => OP_EXCLAMATIONMARKEQUAL,
+            And => OP_AMPERSAND,
+            Or => OP_PIPE,
+            Xor => OP_CARET,
+            LogicalAnd => OP_AMPERSAND2,
+            LogicalOr => OP_PIPE2,
+        };
+        write!(f, "{op}")
+    }
+}
+
 #[repr(u16)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum Associativity {
@@ -264,7 +425,8 @@ pub fn parse_binary_expr(
         parser.pop();
 
         // parse the right-hand side of the binary expr
-        let mut rhs = parse_try!(parser => Expr, parsed_tokens);
+        let rhs = parse_try!(parser => Expr, parsed_tokens);
+        let mut rhs = parse_try!(fn; parser => parse_postfix_ops, parsed_tokens, &rhs);
 
         while let Token {
             tt: T::Oper(lh_op), ..
@@ -305,6 +467,32 @@ pub fn parse_binary_expr(
     Good(lhs, parsed_tokens)
 }
 
+/// Parsing of the calls, member accesses, indexes and post unary operators following
+/// the operand of a binary expression, e.g: `foo(a).bar` in `1 + foo(a).bar`
+pub fn parse_postfix_ops(parser: &mut Parser, operand: &Expression) -> ParsingResult<Expression> {
+    let mut parsed_tokens = Vec::new();
+    let mut result = operand.clone();
+
+    loop {
+        result = match &parser.last().tt {
+            T::OpenParen => parse_try!(fn; parser => parse_call_expr, parsed_tokens, &result),
+            T::Oper(Operator::Dot) => {
+                parse_try!(fn; parser => parse_member_access_expr, parsed_tokens, &result)
+            }
+            T::Oper(Operator::MinusRArrow) => {
+                parse_try!(fn; parser => parse_pointer_member_access_expr, parsed_tokens, &result)
+            }
+            T::OpenBracket => parse_try!(fn; parser => parse_index_expr, parsed_tokens, &result),
+            T::Oper(op) if UnaryOperation::from_op(op.clone(), true).is_some() => {
+                parse_try!(fn; parser => parse_post_unary_expr, parsed_tokens, &result)
+            }
+            _ => break,
+        };
+    }
+
+    Good(result, parsed_tokens)
+}
+
 /// Parsing for `EXPR ( EXPR, EXPR, .. )`
 pub fn parse_call_expr(parser: &mut Parser, lhs: &Expression) -> ParsingResult<Expression> {
     let mut parsed_tokens = Vec::new();
@@ -316,7 +504,9 @@ pub fn parse_call_expr(parser: &mut Parser, lhs: &Expression) -> ParsingResult<E
 
     let mut args = Vec::new();
     while !token_parteq!(parser.last(), T::CloseParen) {
-        args.push(parse_try!(parser => Expression, parsed_tokens));
+        args.push(
+            parse_try!(fn; parser => Parser::with_struct_lits, parsed_tokens, true, Expression::parse),
+        );
         expect_token!(parser => [T::Comma, (); T::CloseParen, break], [Comma, CloseParen], parsed_tokens);
     }
 
@@ -341,7 +531,8 @@ pub fn parse_parenthesized_expr(parser: &mut Parser) -> ParsingResult<Expression
     let opening_span = span_toks!(parsed_tokens);
     let start = span_toks!(start parsed_tokens);
 
-    let expr = parse_try!(parser => Expression, parsed_tokens);
+    let expr =
+        parse_try!(fn; parser => Parser::with_struct_lits, parsed_tokens, true, Expression::parse);
 
     expect_token!(parser => [T::CloseParen, ()] -> ExpectedToken::with_note(&parser.pop(), CloseParen, "opening parenthesis found here".into(), opening_span), parsed_tokens);
     let end = span_toks!(end parsed_tokens);
@@ -355,6 +546,199 @@ pub fn parse_parenthesized_expr(parser: &mut Parser) -> ParsingResult<Expression
     )
 }
 
+/// Parsing for `[ EXPR, EXPR, .. ]`
+pub fn parse_array_lit_expr(parser: &mut Parser) -> ParsingResult<Expression> {
+    let mut parsed_tokens = Vec::new();
+
+    expect_token!(parser => [T::OpenBracket, ()], OpenBracket, parsed_tokens);
+    let start = span_toks!(start parsed_tokens);
+
+    let mut elems = Vec::new();
+    while !token_parteq!(parser.last(), T::CloseBracket) {
+        elems.push(
+            parse_try!(fn; parser => Parser::with_struct_lits, parsed_tokens, true, Expression::parse),
+        );
+        expect_token!(parser => [T::Comma, (); T::CloseBracket, break], [Comma, CloseBracket], parsed_tokens);
+    }
+
+    expect_token!(parser => [T::CloseBracket, ()], CloseBracket, parsed_tokens);
+    let end = span_toks!(end parsed_tokens);
+
+    Good(
+        Expression {
+            expr: Expr::ArrayLitExpr(elems),
+            span: start..end,
+        },
+        parsed_tokens,
+    )
+}
+
+/// Is the next expression a struct literal? It's looked ahead for
+/// `[ IDENT . ] IDENT { }` or `[ IDENT . ] IDENT { IDENT :`, so a block after
+/// an identifier isn't taken for a struct literal. It's never one where struct
+/// literals aren't allowed, e.g: `match x {}` matches on `x`.
+fn is_struct_lit(parser: &mut Parser) -> bool {
+    if !parser.struct_lits || !token_parteq!(parser.end_nth(1), T::Ident(_)) {
+        return false;
+    }
+    let mut brace = 2;
+    if token_parteq!(parser.end_nth(2), T::Oper(Operator::Dot)) {
+        if !token_parteq!(parser.end_nth(3), T::Ident(_)) {
+            return false;
+        }
+        brace = 4;
+    }
+    if !token_parteq!(parser.end_nth(brace), T::OpenBrace) {
+        return false;
+    }
+    match parser.end_nth(brace + 1).tt {
+        T::CloseBrace => true,
+        T::Ident(_) => token_parteq!(parser.end_nth(brace + 2), T::Colon),
+        _ => false,
+    }
+}
+
+/// Parsing for `TYPE { IDENT: EXPR, IDENT: EXPR, .. }`
+pub fn parse_struct_lit_expr(parser: &mut Parser) -> ParsingResult<Expression> {
+    let mut parsed_tokens = Vec::new();
+
+    let ty = parse_try!(parser => Type, parsed_tokens);
+    let start = ty.span.start;
+
+    expect_token!(parser => [T::OpenBrace, ()], OpenBrace, parsed_tokens);
+
+    let mut fields = Vec::new();
+    while !token_parteq!(parser.last(), T::CloseBrace) {
+        fields.push(parse_try!(parser => FieldInit, parsed_tokens));
+        expect_token!(parser => [T::Comma, (); T::CloseBrace, break], [Comma, CloseBrace], parsed_tokens);
+    }
+
+    expect_token!(parser => [T::CloseBrace, ()], CloseBrace, parsed_tokens);
+    let end = span_toks!(end parsed_tokens);
+
+    Good(
+        Expression {
+            expr: Expr::StructLitExpr { ty, fields },
+            span: start..end,
+        },
+        parsed_tokens,
+    )
+}
+
+/// The initialization of a field in a struct literal, `IDENT: EXPR`
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldInit {
+    pub name: String,
+    pub expr: Expression,
+    /// Span of the name of the field
+    pub span: CodeSpan,
+}
+
+impl Parse for FieldInit {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+
+        let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+        let span = span_toks!(parsed_tokens);
+
+        expect_token!(parser => [T::Colon, ()], Colon, parsed_tokens);
+        let expr = parse_try!(parser => Expression, parsed_tokens);
+
+        Good(FieldInit { name, expr, span }, parsed_tokens)
+    }
+}
+
+/// Parsing for `KW_MATCH EXPR { ARM, ARM, .. }`
+pub fn parse_match_expr(parser: &mut Parser) -> ParsingResult<Expression> {
+    let mut parsed_tokens = Vec::new();
+
+    expect_token!(parser => [T::Match, ()], Match, parsed_tokens);
+    let start = span_toks!(start parsed_tokens);
+
+    let scrutinee = Box::new(
+        parse_try!(fn; parser => Parser::with_struct_lits, parsed_tokens, false, Expression::parse),
+    );
+
+    expect_token!(parser => [T::OpenBrace, ()], OpenBrace, parsed_tokens);
+
+    let mut arms = Vec::new();
+    while !token_parteq!(parser.last(), T::CloseBrace) {
+        let arm = parse_try!(parser => MatchArm, parsed_tokens);
+        // the comma is optional after a block
+        if matches!(arm.body, ArmBody::Block(_)) {
+            expect_token!(parser => [T::Comma, ()] else {}, parsed_tokens);
+            arms.push(arm);
+            continue;
+        }
+        arms.push(arm);
+        expect_token!(parser => [T::Comma, (); T::CloseBrace, break], [Comma, CloseBrace], parsed_tokens);
+    }
+
+    expect_token!(parser => [T::CloseBrace, ()], CloseBrace, parsed_tokens);
+    let end = span_toks!(end parsed_tokens);
+
+    Good(
+        Expression {
+            expr: Expr::MatchExpr { scrutinee, arms },
+            span: start..end,
+        },
+        parsed_tokens,
+    )
+}
+
+/// An arm of a match, `PAT [ KW_IF EXPR ] => EXPR` or
+/// `PAT [ KW_IF EXPR ] => BLOCK`
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    /// The condition the value must also meet, after `if`
+    pub guard: Option<Expression>,
+    pub body: ArmBody,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum ArmBody {
+    Expr(Expression),
+    /// A block, the arm is then `void`
+    Block(Block),
+}
+
+impl Parse for MatchArm {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+
+        let pattern = parse_try!(parser => Pattern, parsed_tokens);
+
+        let guard = if token_parteq!(parser.last(), T::If) {
+            expect_token!(parser => [T::If, ()], If, parsed_tokens);
+            Some(parse_try!(parser => Expression, parsed_tokens))
+        } else {
+            None
+        };
+
+        expect_token!(parser => [T::Oper(Operator::EqualRArrow), ()], T::Oper(Operator::EqualRArrow), parsed_tokens);
+
+        let body = if token_parteq!(parser.last(), T::OpenBrace) {
+            ArmBody::Block(parse_try!(parser => Block, parsed_tokens))
+        } else {
+            ArmBody::Expr(parse_try!(parser => Expression, parsed_tokens))
+        };
+
+        Good(
+            MatchArm {
+                pattern,
+                guard,
+                body,
+            },
+            parsed_tokens,
+        )
+    }
+}
+
 /// Parsing for `EXPR . IDENT`
 pub fn parse_member_access_expr(
     parser: &mut Parser,
@@ -379,7 +763,81 @@ pub fn parse_member_access_expr(
     )
 }
 
-#[derive(Debug)]
+/// Parsing for `EXPR -> IDENT`
+pub fn parse_pointer_member_access_expr(
+    parser: &mut Parser,
+    lhs: &Expression,
+) -> ParsingResult<Expression> {
+    let mut parsed_tokens = Vec::new();
+
+    let expr = Box::new(lhs.clone());
+    let start = expr.span.start;
+
+    expect_token!(parser => [T::Oper(Operator::MinusRArrow), ()], T::Oper(Operator::MinusRArrow), parsed_tokens);
+
+    let member_name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+    let end = span_toks!(end parsed_tokens);
+
+    Good(
+        Expression {
+            expr: Expr::PointerMemberAccessExpr { expr, member_name },
+            span: start..end,
+        },
+        parsed_tokens,
+    )
+}
+
+/// Parsing for `EXPR [ EXPR ]` and `EXPR [ [ EXPR ] .. [ EXPR ] ]`
+pub fn parse_index_expr(parser: &mut Parser, lhs: &Expression) -> ParsingResult<Expression> {
+    let mut parsed_tokens = Vec::new();
+
+    let expr = Box::new(lhs.clone());
+    let start = expr.span.start;
+
+    expect_token!(parser => [T::OpenBracket, ()], OpenBracket, parsed_tokens);
+
+    let index = if token_parteq!(parser.last(), T::Oper(Operator::Dot2)) {
+        None
+    } else {
+        Some(Box::new(
+            parse_try!(fn; parser => Parser::with_struct_lits, parsed_tokens, true, Expression::parse),
+        ))
+    };
+
+    let expr = match index {
+        Some(index) if !token_parteq!(parser.last(), T::Oper(Operator::Dot2)) => {
+            Expr::IndexExpr { expr, index }
+        }
+        slice_start => {
+            expect_token!(parser => [T::Oper(Operator::Dot2), ()], T::Oper(Operator::Dot2), parsed_tokens);
+            let slice_end = if token_parteq!(parser.last(), T::CloseBracket) {
+                None
+            } else {
+                Some(Box::new(
+                    parse_try!(fn; parser => Parser::with_struct_lits, parsed_tokens, true, Expression::parse),
+                ))
+            };
+            Expr::SliceExpr {
+                expr,
+                start: slice_start,
+                end: slice_end,
+            }
+        }
+    };
+
+    expect_token!(parser => [T::CloseBracket, ()], CloseBracket, parsed_tokens);
+    let end = span_toks!(end parsed_tokens);
+
+    Good(
+        Expression {
+            expr,
+            span: start..end,
+        },
+        parsed_tokens,
+    )
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ExpressionList(pub Vec<Expression>);
 
 impl ExpressionList {
@@ -400,8 +858,8 @@ impl ExpressionList {
         let mut parsed_tokens = Vec::new();
 
         let mut exprs = vec![first];
-        loop {
-            expect_token!(parser => [T::Comma, ()] else { break }, parsed_tokens);
+        while token_parteq!(parser.last(), T::Comma) {
+            expect_token!(parser => [T::Comma, ()], Comma, parsed_tokens);
             exprs.push(parse_try!(parser => Expression, parsed_tokens));
         }
 
@@ -425,12 +883,13 @@ impl Parse for ExpressionList {
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize)]
 pub enum UnaryOperation {
-    // LEFT: &a, -a, !a
+    // LEFT: &a, -a, !a, ~a
     AddressOf,
     Negation,
     Not,
+    BitwiseNot,
     // RIGHT: a.*
     Dereference,
 }
@@ -444,13 +903,28 @@ impl UnaryOperation {
             Operator::Ampersand if left => Some(AddressOf),
             Operator::Minus if left => Some(Negation),
             Operator::Exclamationmark if left => Some(Not),
+            Operator::Tilde if left => Some(BitwiseNot),
             Operator::DotAsterisk if right => Some(Dereference),
             _ => None,
         }
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+impl fmt::Display for UnaryOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use zom_common::operator::*;
+        let op = match self {
+            UnaryOperation::AddressOf => OP_AMPERSAND,
+            UnaryOperation::Negation => OP_MINUS,
+            UnaryOperation::Not => OP_EXCLAMATIONMARK,
+            UnaryOperation::BitwiseNot => OP_TILDE,
+            UnaryOperation::Dereference => OP_DOTASTERISK,
+        };
+        write!(f, "{op}")
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize)]
 pub enum Operation {
     Binary(BinOperation),
     Unary(UnaryOperation),
@@ -485,8 +959,11 @@ pub fn parse_pre_unary_expr(parser: &mut Parser) -> ParsingResult<Expression> {
         }
     };
 
+    // the precedence is restored, so it doesn't leak into the next expression
+    let prev_precedence = parser.default_precedence;
     parser.default_precedence = parser.pr_get(op.clone()).1;
     let expr = Box::new(parse_try!(parser => Expression, parsed_tokens));
+    parser.default_precedence = prev_precedence;
     let end = span_toks!(end parsed_tokens);
 
     Good(
@@ -0,0 +1,77 @@
+//! Module responsible for parsing impl blocks.
+use crate::{block::Block, prelude::*, toplvldecl::Prototype, types::Type};
+
+#[derive(Debug, Serialize)]
+pub struct ImplDecl {
+    pub ty: Type,
+    pub fns: Vec<ImplFn>,
+    /// Span of `impl TYPE`, the functions aren't in it
+    pub span: Range<usize>,
+}
+
+impl Parse for ImplDecl {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+
+        expect_token!(parser => [T::Impl, ()], Impl, parsed_tokens);
+        let start = span_toks!(start parsed_tokens);
+
+        let ty = parse_try!(parser => Type, parsed_tokens);
+        let end = ty.span.end;
+
+        expect_token!(parser => [T::OpenBrace, ()], OpenBrace, parsed_tokens);
+
+        let mut fns = Vec::new();
+        while !token_parteq!(parser.last(), T::CloseBrace) {
+            fns.push(parse_try!(parser => ImplFn, parsed_tokens));
+        }
+
+        expect_token!(parser => [T::CloseBrace, ()], CloseBrace, parsed_tokens);
+
+        Good(
+            ImplDecl {
+                ty,
+                fns,
+                span: start..end,
+            },
+            parsed_tokens,
+        )
+    }
+}
+
+/// A function of an impl block, it's a method if it has a receiver
+#[derive(Debug, Serialize)]
+pub struct ImplFn {
+    /// The `///` doc comments before the function
+    pub docs: Vec<String>,
+    pub public: bool,
+    pub proto: Prototype,
+    pub body: Block,
+}
+
+impl Parse for ImplFn {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+
+        let docs = parser.take_docs();
+        let public = expect_token!(parser => [T::Pub, true] else { false }, parsed_tokens);
+        expect_token!(parser => [T::Fn, ()], Fn, parsed_tokens);
+
+        let proto = parse_try!(parser => Prototype, parsed_tokens);
+        let body = parse_try!(parser => Block, parsed_tokens);
+
+        Good(
+            ImplFn {
+                docs,
+                public,
+                proto,
+                body,
+            },
+            parsed_tokens,
+        )
+    }
+}
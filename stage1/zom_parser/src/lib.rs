@@ -10,21 +10,27 @@
 //! [1]: https://en.wikipedia.org/wiki/Recursive_descent_parser
 //! [2]: https://en.wikipedia.org/wiki/Operator-precedence_parser
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::mem;
 
 use lazy_static::lazy_static;
+use zom_lexer::Lexer;
 
 use crate::expr::Operation;
 use crate::prelude::*;
 use crate::source_file::SourceFile;
 
 pub mod block;
+pub mod enum_decl;
 pub(crate) mod err;
 pub mod expr;
+pub mod impl_decl;
+pub mod pattern;
 pub(crate) mod prelude;
 pub mod source_file;
 pub mod stmt;
+pub mod struct_decl;
 pub mod toplvldecl;
 pub mod types;
 pub mod var_decl;
@@ -33,6 +39,7 @@ lazy_static! {
     static ref PR_TABLE: HashMap<Operation, (Associativity, u16)> = {
         use zom_common::operator::{
             PR_DEREFERENCE, PR_UNARY, PR_ADD_SUB, PR_AND, PR_COMP, PR_COMP_EQ_NE, PR_MUL_DIV_REM, PR_OR, PR_SHIFT, PR_XOR,
+            PR_LOGICAL_AND, PR_LOGICAL_OR,
         };
         use Associativity::*;
         use BinOperation::*;
@@ -44,6 +51,7 @@ lazy_static! {
             (Unary(AddressOf), (R2L, PR_UNARY)),
             (Unary(Negation), (R2L, PR_UNARY)),
             (Unary(Not), (R2L, PR_UNARY)),
+            (Unary(BitwiseNot), (R2L, PR_UNARY)),
             // ..
             (Binary(Mul), (L2R, PR_MUL_DIV_REM)),
             (Binary(Div), (L2R, PR_MUL_DIV_REM)),
@@ -66,49 +74,164 @@ lazy_static! {
             (Binary(And), (L2R, PR_AND)),
             (Binary(Xor), (L2R, PR_XOR)),
             (Binary(Or), (L2R, PR_OR)),
+            // ..
+            (Binary(LogicalAnd), (L2R, PR_LOGICAL_AND)),
+            (Binary(LogicalOr), (L2R, PR_LOGICAL_OR)),
         ])
     };
 }
 
 pub struct Parser<'a> {
-    /// Reversed list of token
-    tokens: Vec<Token>,
-    lctx: LogContext<'a>,
+    /// The lexer giving the tokens, they are lexed when the parser needs them
+    lexer: Lexer<'a>,
+    /// The tokens lexed but not poped yet, in order
+    tokens: VecDeque<Token>,
+    /// The doc comments lexed, in order, they are taken by the node they
+    /// document
+    docs: VecDeque<Token>,
+    /// End of the last poped token
+    prev_end: usize,
+    /// Can an expression be a struct literal? It can't be in the scrutinee of
+    /// a match, outside of parentheses, because `x {` starts the arms.
+    struct_lits: bool,
     pub default_precedence: u16,
 }
 
 impl<'a> Parser<'a> {
-    pub fn new(tokens: &'a [Token], lctx: LogContext<'a>) -> Parser<'a> {
-        let mut rest = tokens.to_vec();
-        rest.reverse();
-        Parser {
-            tokens: rest,
-            lctx,
+    /// Creates a parser taking its tokens from the lexer, the diagnostics of
+    /// both go to the log context of the lexer.
+    pub fn new(lexer: Lexer<'a>) -> Parser<'a> {
+        let mut parser = Parser {
+            lexer,
+            tokens: VecDeque::new(),
+            docs: VecDeque::new(),
+            prev_end: 0,
+            struct_lits: true,
             default_precedence: 0,
-        }
+        };
+        parser.fill(1);
+        parser
     }
 
     pub fn parse(mut self) -> FinalRes<'a, SourceFile> {
-        match SourceFile::parse(&mut self) {
-            Good(ast, ..) => FinalRes::Ok(ast, self.lctx.clone()),
+        self.parse_file()
+    }
+
+    /// Parses the source file, and returns the tokens the lexer recorded with
+    /// it, see [`Lexer::recording`]. The file is lexed once for both.
+    pub fn parse_with_tokens(mut self) -> (FinalRes<'a, SourceFile>, Vec<Token>) {
+        let res = self.parse_file();
+        (res, self.lexer.take_recorded())
+    }
+
+    fn parse_file(&mut self) -> FinalRes<'a, SourceFile> {
+        match SourceFile::parse(self) {
+            Good(ast, ..) => {
+                while let Some(doc) = self.docs.pop_front() {
+                    self.unused_doc(&doc);
+                }
+                FinalRes::Ok(ast, self.lexer.lctx.clone())
+            }
             Error(err) => {
-                self.lctx.push_boxed(err);
-                FinalRes::Err(self.lctx.stream())
+                self.lexer.lctx.push_boxed(err);
+                // the lexing errors after the parsing error are reported too
+                self.lexer.by_ref().for_each(drop);
+                FinalRes::Err(self.lexer.lctx.stream())
             }
         }
     }
 
-    /// Returns and removes the last token of the reverse token stream
+    /// Calls `parse` with struct literals allowed or not, and then restores
+    /// the previous restriction.
+    pub fn with_struct_lits<T>(
+        &mut self,
+        allowed: bool,
+        parse: impl FnOnce(&mut Parser<'a>) -> T,
+    ) -> T {
+        let prev = mem::replace(&mut self.struct_lits, allowed);
+        let res = parse(self);
+        self.struct_lits = prev;
+        res
+    }
+
+    /// Lexes tokens until `n` tokens wait to be poped, or until the EOF token.
+    ///
+    /// The doc comments are put apart, and the invalid tokens are skipped,
+    /// their error is already reported.
+    fn fill(&mut self, n: usize) {
+        while self.tokens.len() < n {
+            let Some(token) = self.lexer.next() else {
+                break;
+            };
+            match token.tt {
+                T::DocComment(_) | T::InnerDocComment(_) => self.docs.push_back(token),
+                T::Invalid => {}
+                _ => self.tokens.push_back(token),
+            }
+        }
+    }
+
+    /// Returns and removes the next token of the token stream
     fn pop(&mut self) -> Token {
         // here we unwrap because when the EOF token comes, we stop pop token
-        self.tokens
-            .pop()
-            .expect("another token has been poped after the EOF token")
+        let token = self
+            .tokens
+            .pop_front()
+            .expect("another token has been poped after the EOF token");
+        self.prev_end = token.span.end;
+        self.fill(1);
+        token
+    }
+
+    /// Takes the `///` doc comments right before the next token, the lines
+    /// are returned in order.
+    ///
+    /// The doc comments before them, that no node has taken, are reported.
+    pub fn take_docs(&mut self) -> Vec<String> {
+        let next_start = self.last().span.start;
+        let mut lines = Vec::new();
+        while self
+            .docs
+            .front()
+            .is_some_and(|doc| doc.span.start < next_start)
+        {
+            let doc = self.docs.pop_front().unwrap();
+            match doc.tt {
+                T::DocComment(line) if doc.span.start >= self.prev_end => lines.push(line),
+                _ => self.unused_doc(&doc),
+            }
+        }
+        lines
+    }
+
+    /// Takes the `//!` doc comments at the start of the file, the lines are
+    /// returned in order.
+    pub fn take_inner_docs(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        while let Some(T::InnerDocComment(line)) = self.docs.front().map(|doc| &doc.tt) {
+            lines.push(line.clone());
+            self.docs.pop_front();
+        }
+        lines
+    }
+
+    fn unused_doc(&mut self, doc: &Token) {
+        let cursor_msg = if token_parteq!(doc, T::InnerDocComment(_)) {
+            "`//!` doc comments document the file, they go at its start"
+        } else {
+            "`///` doc comments go before a declaration or a parameter"
+        };
+        self.lexer.lctx.push(SimpleLog {
+            level: LogLevel::Warning,
+            msg: "unused doc comment".into(),
+            cursor_msg: Some(cursor_msg.into()),
+            location: doc.span.clone(),
+        });
     }
 
-    /// Returns the last tokens without removing it from the reversed token stream
+    /// Returns the next token without removing it from the token stream
     fn last(&self) -> &Token {
-        self.tokens.last().unwrap()
+        self.tokens.front().unwrap()
     }
 
     /// Did the EOF token has been reached?
@@ -133,19 +256,21 @@ impl<'a> Parser<'a> {
             .expect("Binary operator not in binary table of precedence, impossible in theory")
     }
 
-    /// Get the nth starting at the end.
+    /// Get the nth next token, the tokens up to it are lexed if they aren't
+    /// yet.
     ///
     /// It may panic, if:
-    /// - the offset is greater than the amount of tokens
-    /// - the index is not in range of the tokens list
+    /// - the offset is zero
+    /// - the token is after the EOF token
     ///
     /// **e.g:**
     /// ```text
     ///    fn test
     ///    ^1 ^2 offset of end_nth
     /// ```
-    pub fn end_nth(&self, offset: usize) -> &Token {
-        self.tokens.get(self.tokens.len() - offset).unwrap()
+    pub fn end_nth(&mut self, offset: usize) -> &Token {
+        self.fill(offset);
+        self.tokens.get(offset - 1).unwrap()
     }
 }
 
@@ -167,7 +292,7 @@ macro_rules! expect_token {
         match &$parser.last().tt {
             $(
                 // used, because if $result is a no return expression, it will throw those 2 warnings
-                #[allow(unreachable_code, unused_variables)]
+                #[allow(unreachable_code, unused_variables, clippy::diverging_sub_expression)]
                 $token => {
                     let res = $result;
                     $parsed_tokens.push($parser.pop());
@@ -204,7 +329,7 @@ macro_rules! parse_try {
                 if $parser.reached_eof() {
                     return Error(err);
                 }
-                $parser.lctx.push_boxed(err);
+                $parser.lexer.lctx.push_boxed(err);
                 continue;
             }
         }
@@ -0,0 +1,275 @@
+//! Module responsible for parsing patterns, matched by the arms of a `match`.
+use crate::{
+    expr::{
+        parse_boollit_expr, parse_charlit_expr, parse_identifier_expr, parse_intlit_expr,
+        parse_member_access_expr, Expr, Expression,
+    },
+    prelude::*,
+    types::Type,
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Pattern {
+    pub pat: Pat,
+    pub span: CodeSpan,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum Pat {
+    /// `_`, matches any value
+    Wildcard,
+    /// `IDENT`, matches any value and binds it to a constant
+    Binding(String),
+    /// An integer, char or bool literal, maybe negated, e.g: `-1`
+    Literal(Expression),
+    /// `LIT .. LIT`, without the end, or `LIT ..= LIT`, with the end
+    Range {
+        start: Expression,
+        end: Expression,
+        inclusive: bool,
+    },
+    /// `[ IDENT . ] IDENT . IDENT [ ( PAT, PAT, .. ) ]`, the path is the
+    /// member access naming the variant, e.g: `Shape.Circle`
+    Variant {
+        path: Expression,
+        /// Patterns of the payload, `None` without parentheses
+        fields: Option<Vec<Pattern>>,
+    },
+    /// `TYPE { IDENT: PAT, IDENT, .. }`
+    Struct {
+        ty: Type,
+        fields: Vec<FieldPat>,
+        /// Is there a `..` ignoring the fields not mentioned?
+        rest: bool,
+    },
+}
+
+impl Parse for Pattern {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        // the lookahead lexes the tokens it looks at, so it's done before
+        if is_struct_pattern(parser) {
+            return parse_struct_pattern(parser);
+        }
+        let is_path = token_parteq!(parser.last(), T::Ident(_))
+            && token_parteq!(parser.end_nth(2), T::Oper(Operator::Dot));
+
+        match &parser.last().tt {
+            T::Ident(_) if is_path => parse_variant_pattern(parser),
+            T::Ident(_) => parse_binding_pattern(parser),
+            T::Int(_) | T::Char(_) | T::True | T::False | T::Oper(Operator::Minus) => {
+                parse_lit_pattern(parser)
+            }
+            _ => Error(Box::new(ExpectedToken::from(
+                parser.last(),
+                PartAST::Pattern,
+            ))),
+        }
+    }
+}
+
+/// Is the next pattern a struct pattern? It's looked ahead for
+/// `[ IDENT . ] IDENT {`.
+fn is_struct_pattern(parser: &mut Parser) -> bool {
+    if !token_parteq!(parser.end_nth(1), T::Ident(_)) {
+        return false;
+    }
+    if token_parteq!(parser.end_nth(2), T::OpenBrace) {
+        return true;
+    }
+    token_parteq!(parser.end_nth(2), T::Oper(Operator::Dot))
+        && token_parteq!(parser.end_nth(3), T::Ident(_))
+        && token_parteq!(parser.end_nth(4), T::OpenBrace)
+}
+
+/// Parsing for `_` and `IDENT` patterns
+pub fn parse_binding_pattern(parser: &mut Parser) -> ParsingResult<Pattern> {
+    let mut parsed_tokens = Vec::new();
+
+    let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+    let pat = if name == "_" {
+        Pat::Wildcard
+    } else {
+        Pat::Binding(name)
+    };
+
+    Good(
+        Pattern {
+            pat,
+            span: span_toks!(parsed_tokens),
+        },
+        parsed_tokens,
+    )
+}
+
+/// Parsing for `LIT`, `LIT .. LIT` and `LIT ..= LIT` patterns
+pub fn parse_lit_pattern(parser: &mut Parser) -> ParsingResult<Pattern> {
+    let mut parsed_tokens = Vec::new();
+
+    let start = parse_try!(fn; parser => parse_pattern_lit, parsed_tokens);
+
+    let inclusive = match &parser.last().tt {
+        T::Oper(Operator::Dot2) => false,
+        T::Oper(Operator::Dot2Equal) => true,
+        _ => {
+            let span = start.span.clone();
+            return Good(
+                Pattern {
+                    pat: Pat::Literal(start),
+                    span,
+                },
+                parsed_tokens,
+            );
+        }
+    };
+    expect_token!(parser => [T::Oper(Operator::Dot2 | Operator::Dot2Equal), ()], Operator, parsed_tokens);
+
+    let end = parse_try!(fn; parser => parse_pattern_lit, parsed_tokens);
+    let span = start.span.start..end.span.end;
+
+    Good(
+        Pattern {
+            pat: Pat::Range {
+                start,
+                end,
+                inclusive,
+            },
+            span,
+        },
+        parsed_tokens,
+    )
+}
+
+/// Parsing for the literal of a pattern, `[ - ] INT_LITERAL`, `CHAR_LITERAL`
+/// or `KW_true` / `KW_false`
+pub fn parse_pattern_lit(parser: &mut Parser) -> ParsingResult<Expression> {
+    let mut parsed_tokens = Vec::new();
+
+    let lit = match &parser.last().tt {
+        T::Char(_) => parse_try!(fn; parser => parse_charlit_expr, parsed_tokens),
+        T::True | T::False => parse_try!(fn; parser => parse_boollit_expr, parsed_tokens),
+        T::Oper(Operator::Minus) => {
+            expect_token!(parser => [T::Oper(Operator::Minus), ()], T::Oper(Operator::Minus), parsed_tokens);
+            let start = span_toks!(start parsed_tokens);
+            let int = parse_try!(fn; parser => parse_intlit_expr, parsed_tokens);
+            let span = start..int.span.end;
+            Expression {
+                expr: Expr::UnaryExpr {
+                    op: UnaryOperation::Negation,
+                    expr: Box::new(int),
+                },
+                span,
+            }
+        }
+        _ => parse_try!(fn; parser => parse_intlit_expr, parsed_tokens),
+    };
+
+    Good(lit, parsed_tokens)
+}
+
+/// Parsing for `[ IDENT . ] IDENT . IDENT [ ( PAT, PAT, .. ) ]` patterns
+pub fn parse_variant_pattern(parser: &mut Parser) -> ParsingResult<Pattern> {
+    let mut parsed_tokens = Vec::new();
+
+    let mut path = parse_try!(fn; parser => parse_identifier_expr, parsed_tokens);
+    while token_parteq!(parser.last(), T::Oper(Operator::Dot)) {
+        path = parse_try!(fn; parser => parse_member_access_expr, parsed_tokens, &path);
+    }
+
+    let fields = if token_parteq!(parser.last(), T::OpenParen) {
+        expect_token!(parser => [T::OpenParen, ()], OpenParen, parsed_tokens);
+        let mut fields = Vec::new();
+        while !token_parteq!(parser.last(), T::CloseParen) {
+            fields.push(parse_try!(parser => Pattern, parsed_tokens));
+            expect_token!(parser => [T::Comma, (); T::CloseParen, break], [Comma, CloseParen], parsed_tokens);
+        }
+        expect_token!(parser => [T::CloseParen, ()], CloseParen, parsed_tokens);
+        Some(fields)
+    } else {
+        None
+    };
+    let span = path.span.start..span_toks!(end parsed_tokens);
+
+    Good(
+        Pattern {
+            pat: Pat::Variant { path, fields },
+            span,
+        },
+        parsed_tokens,
+    )
+}
+
+/// Parsing for `TYPE { IDENT: PAT, IDENT, .. }` patterns
+pub fn parse_struct_pattern(parser: &mut Parser) -> ParsingResult<Pattern> {
+    let mut parsed_tokens = Vec::new();
+
+    let ty = parse_try!(parser => Type, parsed_tokens);
+    let start = ty.span.start;
+
+    expect_token!(parser => [T::OpenBrace, ()], OpenBrace, parsed_tokens);
+
+    let mut fields = Vec::new();
+    let mut rest = false;
+    while !token_parteq!(parser.last(), T::CloseBrace) {
+        if token_parteq!(parser.last(), T::Oper(Operator::Dot2)) {
+            // the `..` is the last thing of the pattern
+            expect_token!(parser => [T::Oper(Operator::Dot2), ()], T::Oper(Operator::Dot2), parsed_tokens);
+            rest = true;
+            break;
+        }
+        fields.push(parse_try!(parser => FieldPat, parsed_tokens));
+        expect_token!(parser => [T::Comma, (); T::CloseBrace, break], [Comma, CloseBrace], parsed_tokens);
+    }
+
+    expect_token!(parser => [T::CloseBrace, ()], CloseBrace, parsed_tokens);
+    let end = span_toks!(end parsed_tokens);
+
+    Good(
+        Pattern {
+            pat: Pat::Struct { ty, fields, rest },
+            span: start..end,
+        },
+        parsed_tokens,
+    )
+}
+
+/// The pattern of a field in a struct pattern, `IDENT: PAT`, or `IDENT` that
+/// binds the field to a constant of the same name
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldPat {
+    pub name: String,
+    pub pattern: Pattern,
+    /// Span of the name of the field
+    pub span: CodeSpan,
+}
+
+impl Parse for FieldPat {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+
+        let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+        let span = span_toks!(parsed_tokens);
+
+        let pattern = if token_parteq!(parser.last(), T::Colon) {
+            expect_token!(parser => [T::Colon, ()], Colon, parsed_tokens);
+            parse_try!(parser => Pattern, parsed_tokens)
+        } else {
+            Pattern {
+                pat: Pat::Binding(name.clone()),
+                span: span.clone(),
+            }
+        };
+
+        Good(
+            FieldPat {
+                name,
+                pattern,
+                span,
+            },
+            parsed_tokens,
+        )
+    }
+}
@@ -8,6 +8,8 @@ pub use crate::expr::{Associativity, BinOperation, UnaryOperation};
 
 pub use std::ops::Range;
 
+pub use serde::Serialize;
+
 pub use zom_common::operator::Operator;
 pub use zom_common::token::Token;
 pub use zom_common::token::TokenType as T;
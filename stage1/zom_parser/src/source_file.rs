@@ -1,8 +1,10 @@
 //! Module responsible for parsing the top level source file.
 use crate::{prelude::*, toplvldecl::TopLevelDeclaration};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct SourceFile {
+    /// The `//!` doc comments at the start of the file
+    pub docs: Vec<String>,
     pub pkg_path: QualifiedIdentifier,
     pub import_decls: Vec<ImportDecl>,
     pub decls: Vec<TopLevelDeclaration>,
@@ -15,6 +17,7 @@ impl Parse for SourceFile {
     fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
         let mut parsed_tokens = Vec::new();
 
+        let docs = parser.take_inner_docs();
         expect_token!(parser => [T::Package, ()], Package, parsed_tokens);
 
         let start = span_toks!(start parsed_tokens);
@@ -36,6 +39,7 @@ impl Parse for SourceFile {
 
         Good(
             SourceFile {
+                docs,
                 pkg_path,
                 import_decls,
                 decls,
@@ -46,7 +50,7 @@ impl Parse for SourceFile {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct QualifiedIdentifier {
     pub path: Vec<String>,
     pub span: Range<usize>,
@@ -83,7 +87,7 @@ impl Parse for QualifiedIdentifier {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ImportDecl {
     pub path: QualifiedIdentifier,
     pub alias: Option<String>,
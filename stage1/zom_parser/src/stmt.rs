@@ -1,12 +1,12 @@
 //! Module responsible for parsing statement.
 use crate::{
     block::Block,
-    expr::{Expression, ExpressionList},
+    expr::{parse_match_expr, BinOperation, Expression, ExpressionList},
     prelude::*,
     var_decl::VarDecl,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Statement {
     pub stmt: Stmt,
     pub span: Range<usize>,
@@ -20,7 +20,7 @@ impl Parse for Statement {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub enum Stmt {
     ExprStmt(Expression),
     IfElseStmt {
@@ -38,6 +38,16 @@ pub enum Stmt {
         ctrling_expr: Expression,
         loop_body: Block,
     },
+    /// `[ IDENT : ] for ( [ IDENT , ] IDENT in ITER ) BLOCK`
+    ForStmt {
+        label: Option<String>,
+        /// The variable of the index of the element, when iterating over an
+        /// array or a slice
+        index: Option<String>,
+        var: String,
+        iter: ForIter,
+        loop_body: Block,
+    },
     BreakStmt {
         label: Option<String>,
         expr: Option<Expression>,
@@ -49,6 +59,11 @@ pub enum Stmt {
         lhs: ExpressionList,
         rhs: ExpressionList,
     },
+    CompoundAssignStmt {
+        place: Expression,
+        op: BinOperation,
+        value: Expression,
+    },
     ShortVarDeclStmt {
         names: Vec<String>,
         exprs: Vec<Expression>,
@@ -60,16 +75,25 @@ impl Parse for Stmt {
     type Output = Statement;
 
     fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        // the lookahead lexes the tokens it looks at, so it's done before
+        if token_parteq!(parser.last(), T::Ident(_)) {
+            if is_labeled_stmt(parser) {
+                return parse_labeled_stmt(parser);
+            }
+            if is_short_var_decl(parser) {
+                return parse_short_var_decl(parser);
+            }
+        }
         match &parser.last().tt {
             T::If => parse_if_else_stmt(parser),
             T::OpenBrace => parse_block_stmt(parser),
             T::Return => parse_return_stmt(parser),
-            T::Ident(_) if is_labeled_stmt(parser) => parse_labeled_stmt(parser),
             T::While => parse_while_stmt(parser),
+            T::For => parse_for_stmt(parser),
             T::Break => parse_break_stmt(parser),
             T::Continue => parse_continue_stmt(parser),
-            T::Ident(_) if is_short_var_decl(parser) => parse_short_var_decl(parser),
             T::Var | T::Const => parse_var_decl_stmt(parser),
+            T::Match => parse_match_stmt(parser),
             _ => parse_expr_stmt(parser),
         }
     }
@@ -85,6 +109,11 @@ pub fn parse_expr_stmt(parser: &mut Parser) -> ParsingResult<Statement> {
 
         return parse_assignement_stmt(parser, expr_list, start);
     }
+    if let T::Oper(op) = &parser.last().tt {
+        if BinOperation::from_compound(op).is_some() {
+            return parse_compound_assign_stmt(parser, expr, start);
+        }
+    }
 
     let end = span_toks!(end parsed_tokens);
 
@@ -97,6 +126,23 @@ pub fn parse_expr_stmt(parser: &mut Parser) -> ParsingResult<Statement> {
     )
 }
 
+/// Parsing for a match statement, it's only the match expression, the
+/// operators after it aren't parsed, like the ones after a block.
+pub fn parse_match_stmt(parser: &mut Parser) -> ParsingResult<Statement> {
+    let mut parsed_tokens = Vec::new();
+
+    let expr = parse_try!(fn; parser => parse_match_expr, parsed_tokens);
+    let span = expr.span.clone();
+
+    Good(
+        Statement {
+            stmt: Stmt::ExprStmt(expr),
+            span,
+        },
+        parsed_tokens,
+    )
+}
+
 pub fn parse_if_else_stmt(parser: &mut Parser) -> ParsingResult<Statement> {
     let mut parsed_tokens = Vec::new();
     expect_token!(parser => [T::If, ()], If, parsed_tokens);
@@ -176,13 +222,17 @@ pub fn parse_return_stmt(parser: &mut Parser) -> ParsingResult<Statement> {
     )
 }
 
-pub fn is_labeled_stmt(parser: &Parser) -> bool {
-    matches!(parser.end_nth(1).tt, T::Ident(_)) && matches!(parser.end_nth(2).tt, T::Colon)
+pub fn is_labeled_stmt(parser: &mut Parser) -> bool {
+    // `IDENT : =` is the start of a short variable declaration, not a label
+    matches!(parser.end_nth(1).tt, T::Ident(_))
+        && matches!(parser.end_nth(2).tt, T::Colon)
+        && !matches!(parser.end_nth(3).tt, T::Oper(Operator::Equal))
 }
 
 pub fn parse_labeled_stmt(parser: &mut Parser) -> ParsingResult<Statement> {
     match &parser.end_nth(3).tt {
         T::While => parse_while_stmt(parser),
+        T::For => parse_for_stmt(parser),
         T::OpenBrace => parse_block_stmt(parser),
         _ => Error(Box::new(ExpectedToken::from(
             parser.end_nth(3),
@@ -225,6 +275,89 @@ pub fn parse_while_stmt(parser: &mut Parser) -> ParsingResult<Statement> {
     )
 }
 
+/// What a `for` loop iterates over
+#[derive(Debug, Clone, Serialize)]
+pub enum ForIter {
+    Range(Box<ForRange>),
+    /// The elements of an array or of a slice
+    Elems(Expression),
+}
+
+/// The range iterated by a `for` loop, `EXPR .. EXPR [ by EXPR ]`, it includes
+/// its end with `..=`
+#[derive(Debug, Clone, Serialize)]
+pub struct ForRange {
+    pub start: Expression,
+    pub end: Expression,
+    pub inclusive: bool,
+    pub step: Option<Expression>,
+}
+
+pub fn parse_for_stmt(parser: &mut Parser) -> ParsingResult<Statement> {
+    let mut parsed_tokens = Vec::new();
+
+    let label = if token_parteq!(parser.last(), T::Ident(_)) {
+        let l = expect_token!(parser => [T::Ident(label), label.clone()], Ident, parsed_tokens);
+        expect_token!(parser => [T::Colon, ()], Colon, parsed_tokens);
+        Some(l)
+    } else {
+        None
+    };
+
+    expect_token!(parser => [T::For, ()], For, parsed_tokens);
+    let start_span = span_toks!(start first parsed_tokens);
+
+    expect_token!(parser => [T::OpenParen, ()], OpenParen, parsed_tokens);
+    let mut var = expect_token!(parser => [T::Ident(var), var.clone()], Ident, parsed_tokens);
+    let index = if token_parteq!(parser.last(), T::Comma) {
+        expect_token!(parser => [T::Comma, ()], Comma, parsed_tokens);
+        let elem = expect_token!(parser => [T::Ident(var), var.clone()], Ident, parsed_tokens);
+        Some(std::mem::replace(&mut var, elem))
+    } else {
+        None
+    };
+    expect_token!(parser => [T::In, ()], In, parsed_tokens);
+
+    let start = parse_try!(parser => Expression, parsed_tokens);
+    let iter = if token_parteq!(parser.last(), T::Oper(Operator::Dot2 | Operator::Dot2Equal)) {
+        let inclusive = expect_token!(parser => [T::Oper(Operator::Dot2), false; T::Oper(Operator::Dot2Equal), true], Operator, parsed_tokens);
+        let end = parse_try!(parser => Expression, parsed_tokens);
+
+        let step = if token_parteq!(parser.last(), T::By) {
+            expect_token!(parser => [T::By, ()], By, parsed_tokens);
+            Some(parse_try!(parser => Expression, parsed_tokens))
+        } else {
+            None
+        };
+        ForIter::Range(Box::new(ForRange {
+            start,
+            end,
+            inclusive,
+            step,
+        }))
+    } else {
+        ForIter::Elems(start)
+    };
+    expect_token!(parser => [T::CloseParen, ()], CloseParen, parsed_tokens);
+
+    let loop_body = parse_try!(parser => Block, parsed_tokens);
+
+    let end_span = span_toks!(end parsed_tokens);
+    Good(
+        Statement {
+            stmt: Stmt::ForStmt {
+                label,
+                index,
+                var,
+                iter,
+                loop_body,
+            },
+            span: start_span..end_span,
+        },
+        parsed_tokens,
+    )
+}
+
 pub fn parse_break_stmt(parser: &mut Parser) -> ParsingResult<Statement> {
     let mut parsed_tokens = Vec::new();
 
@@ -283,8 +416,6 @@ pub fn parse_assignement_stmt(
     lhs: ExpressionList,
     start: usize,
 ) -> ParsingResult<Statement> {
-    // TODO(Larsouille25): Add ability to use +=, etc..
-    //                     a += 1
     let mut parsed_tokens = Vec::new();
 
     expect_token!(parser => [T::Oper(Operator::Equal), ()], T::Oper(Operator::Equal), parsed_tokens);
@@ -302,7 +433,38 @@ pub fn parse_assignement_stmt(
     )
 }
 
-pub fn is_short_var_decl(parser: &Parser) -> bool {
+pub fn parse_compound_assign_stmt(
+    parser: &mut Parser,
+    place: Expression,
+    start: usize,
+) -> ParsingResult<Statement> {
+    let mut parsed_tokens = Vec::new();
+
+    let op = expect_token!(parser => [T::Oper(op), op.clone()], Operator, parsed_tokens);
+    let op = match BinOperation::from_compound(&op) {
+        Some(v) => v,
+        None => {
+            return Error(Box::new(ExpectedToken::from(
+                parsed_tokens.first().unwrap(),
+                Operator,
+            )))
+        }
+    };
+
+    let value = parse_try!(parser => Expression, parsed_tokens);
+
+    let end = span_toks!(end parsed_tokens);
+
+    Good(
+        Statement {
+            stmt: Stmt::CompoundAssignStmt { place, op, value },
+            span: start..end,
+        },
+        parsed_tokens,
+    )
+}
+
+pub fn is_short_var_decl(parser: &mut Parser) -> bool {
     let mut i = 1;
     loop {
         if !token_parteq!(parser.end_nth(i), T::Ident(_)) {
@@ -0,0 +1,82 @@
+//! Module responsible for parsing struct declarations.
+use crate::{prelude::*, types::Type};
+
+#[derive(Debug, Serialize)]
+pub struct StructDecl {
+    pub name: String,
+    pub fields: Vec<Field>,
+    /// Span of `struct NAME`, the fields aren't in it
+    pub span: Range<usize>,
+}
+
+impl Parse for StructDecl {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+
+        expect_token!(parser => [T::Struct, ()], Struct, parsed_tokens);
+        let start = span_toks!(start parsed_tokens);
+
+        let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+        let end = span_toks!(end parsed_tokens);
+
+        expect_token!(parser => [T::OpenBrace, ()], OpenBrace, parsed_tokens);
+
+        let mut fields = Vec::new();
+        while !token_parteq!(parser.last(), T::CloseBrace) {
+            fields.push(parse_try!(parser => Field, parsed_tokens));
+            expect_token!(parser => [T::Comma, (); T::CloseBrace, break], [Comma, CloseBrace], parsed_tokens);
+        }
+
+        expect_token!(parser => [T::CloseBrace, ()], CloseBrace, parsed_tokens);
+
+        Good(
+            StructDecl {
+                name,
+                fields,
+                span: start..end,
+            },
+            parsed_tokens,
+        )
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Field {
+    /// The `///` doc comments before the field
+    pub docs: Vec<String>,
+    pub public: bool,
+    pub name: String,
+    pub ty: Type,
+    pub span: Range<usize>,
+}
+
+impl Parse for Field {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+
+        let docs = parser.take_docs();
+        let public = expect_token!(parser => [T::Pub, true] else { false }, parsed_tokens);
+        let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+        let start = span_toks!(start first parsed_tokens);
+
+        expect_token!(parser => [T::Colon, ()], Colon, parsed_tokens);
+        let ty = parse_try!(parser => Type, parsed_tokens);
+
+        let end = span_toks!(end parsed_tokens);
+
+        Good(
+            Field {
+                docs,
+                public,
+                name,
+                ty,
+                span: start..end,
+            },
+            parsed_tokens,
+        )
+    }
+}
@@ -1,8 +1,13 @@
 //! Module responsible for parsing top level declarations.
-use crate::{block::Block, prelude::*, types::Type, var_decl::VarDecl};
+use crate::{
+    block::Block, enum_decl::EnumDecl, impl_decl::ImplDecl, prelude::*, struct_decl::StructDecl,
+    types::Type, var_decl::VarDecl,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct TopLevelDeclaration {
+    /// The `///` doc comments before the declaration
+    pub docs: Vec<String>,
     pub public: bool,
     pub decl: TopLvlDecl,
     pub span: Range<usize>,
@@ -14,11 +19,12 @@ impl Parse for TopLevelDeclaration {
     fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
         let mut parsed_tokens = Vec::new();
 
+        let docs = parser.take_docs();
         let (public, start) = if token_parteq!(parser.last(), T::Pub) {
             expect_token!(parser => [T::Pub, ()], Pub, parsed_tokens);
             (true, span_toks!(start parsed_tokens))
         } else {
-            (false, span_toks!(start parser.tokens))
+            (false, parser.last().span.start)
         };
         let decl = parse_try!(parser => TopLvlDecl, parsed_tokens);
 
@@ -26,6 +32,7 @@ impl Parse for TopLevelDeclaration {
 
         Good(
             TopLevelDeclaration {
+                docs,
                 public,
                 decl,
                 span: start..end,
@@ -35,7 +42,7 @@ impl Parse for TopLevelDeclaration {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub enum TopLvlDecl {
     Function {
         lib: Option<String>,
@@ -43,6 +50,9 @@ pub enum TopLvlDecl {
         body: Option<Block>,
     },
     GlobalVarDecl(VarDecl),
+    Struct(StructDecl),
+    Enum(EnumDecl),
+    Impl(ImplDecl),
 }
 
 impl Parse for TopLvlDecl {
@@ -53,6 +63,9 @@ impl Parse for TopLvlDecl {
             T::Fn => parse_fn_decl(parser),
             T::Extern => parse_extern_decl(parser),
             T::Const | T::Var => parse_global_var_decl(parser),
+            T::Struct => parse_struct_decl(parser),
+            T::Enum => parse_enum_decl(parser),
+            T::Impl => parse_impl_decl(parser),
             _ => Error(Box::new(ExpectedToken::from(
                 parser.last(),
                 PartAST::Declaration,
@@ -61,11 +74,15 @@ impl Parse for TopLvlDecl {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Prototype {
     pub name: String,
+    /// `self`, `*self` or `*const self` before the arguments, only the
+    /// functions of an impl block can have one
+    pub receiver: Option<Receiver>,
     pub args: Vec<Arg>,
     pub ret_ty: Type,
+    pub span: Range<usize>,
 }
 
 impl Parse for Prototype {
@@ -75,20 +92,105 @@ impl Parse for Prototype {
         let mut parsed_tokens = Vec::new();
 
         let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+        let start = span_toks!(start parsed_tokens);
 
         expect_token!(parser => [T::OpenParen, ()], OpenParen, parsed_tokens);
 
+        let mut receiver = None;
         let mut args = Vec::new();
-        loop {
-            args.push(parse_try!(parser => Arg, parsed_tokens));
-            expect_token!(parser => [T::Comma, (); T::CloseParen, break], Comma, parsed_tokens);
+        while !token_parteq!(parser.last(), T::CloseParen) {
+            if receiver.is_none() && args.is_empty() && is_receiver(parser) {
+                receiver = Some(parse_try!(parser => Receiver, parsed_tokens));
+            } else {
+                args.push(parse_try!(parser => Arg, parsed_tokens));
+            }
+            expect_token!(parser => [T::Comma, (); T::CloseParen, break], [Comma, CloseParen], parsed_tokens);
         }
 
         expect_token!(parser => [T::CloseParen, ()], CloseParen, parsed_tokens);
 
         let ret_ty = parse_try!(parser => Type, parsed_tokens);
+        let end = span_toks!(end parsed_tokens);
+
+        Good(
+            Prototype {
+                name,
+                receiver,
+                args,
+                ret_ty,
+                span: start..end,
+            },
+            parsed_tokens,
+        )
+    }
+}
 
-        Good(Prototype { name, args, ret_ty }, parsed_tokens)
+impl Prototype {
+    /// Returns the names of the arguments, with `self` first if the function
+    /// has a receiver.
+    pub fn arg_names(&self) -> impl Iterator<Item = &str> {
+        let receiver = self.receiver.as_ref().map(|_| "self");
+        receiver
+            .into_iter()
+            .chain(self.args.iter().map(|arg| arg.name.as_str()))
+    }
+}
+
+/// Is the next argument a receiver? It's looked ahead for `self`, `*self` or
+/// `*const self`, not followed by `:`.
+fn is_receiver(parser: &mut Parser) -> bool {
+    let pointer = token_parteq!(parser.end_nth(1), T::Oper(Operator::Asterisk));
+    let is_const = pointer && token_parteq!(parser.end_nth(2), T::Const);
+    let offset = 1 + pointer as usize + is_const as usize;
+
+    matches!(&parser.end_nth(offset).tt, T::Ident(name) if name == "self")
+        && !token_parteq!(parser.end_nth(offset + 1), T::Colon)
+}
+
+#[derive(Debug, Serialize)]
+pub struct Receiver {
+    pub kind: ReceiverKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ReceiverKind {
+    /// `self`, the method takes a copy of the value
+    Value,
+    /// `*self`
+    Pointer,
+    /// `*const self`
+    ConstPointer,
+}
+
+impl Parse for Receiver {
+    type Output = Self;
+
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+
+        let kind = if token_parteq!(parser.last(), T::Oper(Operator::Asterisk)) {
+            expect_token!(parser => [T::Oper(Operator::Asterisk), ()], T::Oper(Operator::Asterisk), parsed_tokens);
+            if expect_token!(parser => [T::Const, true] else { false }, parsed_tokens) {
+                ReceiverKind::ConstPointer
+            } else {
+                ReceiverKind::Pointer
+            }
+        } else {
+            ReceiverKind::Value
+        };
+        expect_token!(parser => [T::Ident(_), ()], Ident, parsed_tokens);
+
+        let start = span_toks!(start first parsed_tokens);
+        let end = span_toks!(end parsed_tokens);
+
+        Good(
+            Receiver {
+                kind,
+                span: start..end,
+            },
+            parsed_tokens,
+        )
     }
 }
 
@@ -110,8 +212,10 @@ pub fn parse_fn_decl(parser: &mut Parser) -> ParsingResult<TopLvlDecl> {
     )
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Arg {
+    /// The `///` doc comments before the argument
+    pub docs: Vec<String>,
     pub name: String,
     pub ty: Type,
     pub span: Range<usize>,
@@ -123,6 +227,7 @@ impl Parse for Arg {
     fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
         let mut parsed_tokens = Vec::new();
 
+        let docs = parser.take_docs();
         let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
         let start = span_toks!(start parsed_tokens);
 
@@ -133,6 +238,7 @@ impl Parse for Arg {
 
         Good(
             Arg {
+                docs,
                 name,
                 ty,
                 span: start..end,
@@ -150,6 +256,30 @@ pub fn parse_global_var_decl(parser: &mut Parser) -> ParsingResult<TopLvlDecl> {
     Good(TopLvlDecl::GlobalVarDecl(var_decl), parsed_tokens)
 }
 
+pub fn parse_struct_decl(parser: &mut Parser) -> ParsingResult<TopLvlDecl> {
+    let mut parsed_tokens = Vec::new();
+
+    let struct_decl = parse_try!(parser => StructDecl, parsed_tokens);
+
+    Good(TopLvlDecl::Struct(struct_decl), parsed_tokens)
+}
+
+pub fn parse_enum_decl(parser: &mut Parser) -> ParsingResult<TopLvlDecl> {
+    let mut parsed_tokens = Vec::new();
+
+    let enum_decl = parse_try!(parser => EnumDecl, parsed_tokens);
+
+    Good(TopLvlDecl::Enum(enum_decl), parsed_tokens)
+}
+
+pub fn parse_impl_decl(parser: &mut Parser) -> ParsingResult<TopLvlDecl> {
+    let mut parsed_tokens = Vec::new();
+
+    let impl_decl = parse_try!(parser => ImplDecl, parsed_tokens);
+
+    Good(TopLvlDecl::Impl(impl_decl), parsed_tokens)
+}
+
 pub fn parse_extern_decl(parser: &mut Parser) -> ParsingResult<TopLvlDecl> {
     let mut parsed_tokens = Vec::new();
 
@@ -1,8 +1,10 @@
 //! Module responsible for parsing types.
-use crate::prelude::*;
+use std::fmt;
+
+use crate::{expr::Expression, prelude::*};
 use PrimitiveTy::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Type {
     pub ty: Ty,
     pub span: Range<usize>,
@@ -29,13 +31,29 @@ impl Parse for Type {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub enum Ty {
     PrimTy(PrimitiveTy),
     PointerTy {
         is_const: bool,
         pointed_ty: Box<Type>,
     },
+    /// `[ EXPR ] TYPE`, the length is known at compile-time
+    ArrayTy {
+        len: Box<Expression>,
+        elem_ty: Box<Type>,
+    },
+    /// `[ ] [ "const" ] TYPE`, a pointer with a runtime length
+    SliceTy {
+        is_const: bool,
+        elem_ty: Box<Type>,
+    },
+    /// A type declared in a package, e.g: `Point` or `geo.Point` for the
+    /// one of an imported package
+    NamedTy {
+        package: Option<String>,
+        name: String,
+    },
 }
 
 impl Parse for Ty {
@@ -45,6 +63,8 @@ impl Parse for Ty {
         match &parser.last().tt {
             T::Ident(name) if PRIM_TYPES.contains(&name.as_str()) => PrimitiveTy::parse(parser),
             T::Oper(Operator::Asterisk) => parse_pointer_ty(parser),
+            T::OpenBracket => parse_array_or_slice_ty(parser),
+            T::Ident(_) => parse_named_ty(parser),
             _ => Error(Box::new(ExpectedToken::from(parser.last(), PartAST::Type))),
         }
     }
@@ -77,7 +97,7 @@ pub const PRIM_TYPES: &[&str] = &[
     I16_TYPE, I32_TYPE, I64_TYPE, I128_TYPE, ISIZE_TYPE, F16_TYPE, F32_TYPE, F64_TYPE, F128_TYPE,
 ];
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum PrimitiveTy {
     Void,
     Bool,
@@ -105,15 +125,39 @@ pub enum PrimitiveTy {
     F128,
 }
 
-impl Parse for PrimitiveTy {
-    type Output = Ty;
+impl fmt::Display for PrimitiveTy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Void => VOID_TYPE,
+            Bool => BOOL_TYPE,
 
-    /// Parsing for primitive types
-    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
-        let mut parsed_tokens = Vec::new();
-        let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+            U8 => U8_TYPE,
+            U16 => U16_TYPE,
+            U32 => U32_TYPE,
+            U64 => U64_TYPE,
+            U128 => U128_TYPE,
+            USize => USIZE_TYPE,
+
+            I8 => I8_TYPE,
+            I16 => I16_TYPE,
+            I32 => I32_TYPE,
+            I64 => I64_TYPE,
+            I128 => I128_TYPE,
+            ISize => ISIZE_TYPE,
 
-        let prim_ty = match name.as_str() {
+            F16 => F16_TYPE,
+            F32 => F32_TYPE,
+            F64 => F64_TYPE,
+            F128 => F128_TYPE,
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl PrimitiveTy {
+    /// Returns the primitive type with this name, one of [`PRIM_TYPES`].
+    pub fn from_name(name: &str) -> Option<PrimitiveTy> {
+        Some(match name {
             VOID_TYPE => Void,
             BOOL_TYPE => Bool,
 
@@ -136,9 +180,28 @@ impl Parse for PrimitiveTy {
             F64_TYPE => F64,
             F128_TYPE => F128,
 
-            _ => unreachable!(),
-        };
+            _ => return None,
+        })
+    }
 
+    pub fn is_int(self) -> bool {
+        matches!(
+            self,
+            U8 | U16 | U32 | U64 | U128 | USize | I8 | I16 | I32 | I64 | I128 | ISize
+        )
+    }
+}
+
+impl Parse for PrimitiveTy {
+    type Output = Ty;
+
+    /// Parsing for primitive types
+    fn parse(parser: &mut Parser) -> ParsingResult<Self::Output> {
+        let mut parsed_tokens = Vec::new();
+        let name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+
+        // `Ty::parse` only parses the names of `PRIM_TYPES`
+        let prim_ty = PrimitiveTy::from_name(&name).unwrap();
         Good(Ty::PrimTy(prim_ty), parsed_tokens)
     }
 }
@@ -161,3 +224,41 @@ pub fn parse_pointer_ty(parser: &mut Parser) -> ParsingResult<Ty> {
         parsed_tokens,
     )
 }
+
+/// Parsing for `[ EXPR ] TYPE` and `[ ] [ "const" ] TYPE` types
+pub fn parse_array_or_slice_ty(parser: &mut Parser) -> ParsingResult<Ty> {
+    let mut parsed_tokens = Vec::new();
+
+    expect_token!(parser => [T::OpenBracket, ()], OpenBracket, parsed_tokens);
+
+    if token_parteq!(parser.last(), T::CloseBracket) {
+        expect_token!(parser => [T::CloseBracket, ()], CloseBracket, parsed_tokens);
+        let is_const = expect_token!(parser => [T::Const, true] else { false }, parsed_tokens);
+        let elem_ty = Box::new(parse_try!(parser => Type, parsed_tokens));
+
+        return Good(Ty::SliceTy { is_const, elem_ty }, parsed_tokens);
+    }
+
+    let len = Box::new(parse_try!(parser => Expression, parsed_tokens));
+    expect_token!(parser => [T::CloseBracket, ()], CloseBracket, parsed_tokens);
+    let elem_ty = Box::new(parse_try!(parser => Type, parsed_tokens));
+
+    Good(Ty::ArrayTy { len, elem_ty }, parsed_tokens)
+}
+
+/// Parsing for `[ IDENT . ] IDENT` type
+pub fn parse_named_ty(parser: &mut Parser) -> ParsingResult<Ty> {
+    let mut parsed_tokens = Vec::new();
+
+    let mut name = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+
+    let package = if token_parteq!(parser.last(), T::Oper(Operator::Dot)) {
+        expect_token!(parser => [T::Oper(Operator::Dot), ()], Dot, parsed_tokens);
+        let item = expect_token!(parser => [T::Ident(name), name.clone()], Ident, parsed_tokens);
+        Some(std::mem::replace(&mut name, item))
+    } else {
+        None
+    };
+
+    Good(Ty::NamedTy { package, name }, parsed_tokens)
+}
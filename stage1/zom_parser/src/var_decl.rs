@@ -2,13 +2,13 @@
 use crate::{expr::Expression, prelude::*, types::Type};
 
 #[repr(u8)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub enum VarType {
     ConstVar,
     VariableVar,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct VarDecl {
     pub var_type: VarType,
     pub name: String,
@@ -0,0 +1,147 @@
+//! Doc comments, attached to the nodes they document.
+
+use std::path::Path;
+
+use serde_json::Value;
+use zom_errors::prelude::*;
+use zom_lexer::Lexer;
+use zom_parser::{source_file::SourceFile, toplvldecl::TopLvlDecl, Parser};
+
+/// Parses the source, and returns its AST and the messages and cursor
+/// messages of the warnings.
+fn parse(source: &str) -> (SourceFile, Vec<(String, String)>) {
+    let path = Path::new("docs.zom");
+    let lctx = LogContext::new(source, path, LogOutput::default());
+    let (ast, lctx) = match Parser::new(Lexer::new(source, path, lctx)).parse() {
+        FinalRes::Ok(ast, lctx) => (ast, lctx),
+        FinalRes::Err(_) => panic!("couldn't parse {source:?}"),
+    };
+    let mut json = Vec::new();
+    lctx.stream().format_json(&mut json).unwrap();
+    let warnings = String::from_utf8(json)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let log: Value = serde_json::from_str(line).unwrap();
+            assert_eq!(log["level"], "warning", "{line}");
+            (
+                log["message"].as_str().unwrap().to_owned(),
+                log["cursor_message"].as_str().unwrap().to_owned(),
+            )
+        })
+        .collect();
+    (ast, warnings)
+}
+
+#[test]
+fn file_docs() {
+    let (ast, warnings) = parse("//! Geometry.\n//!\n//! Points and lines.\npackage geo\n");
+    assert_eq!(ast.docs, [" Geometry.", "", " Points and lines."]);
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn item_docs() {
+    let source = "package geo
+
+/// A point.
+/// In 2D.
+struct Point {
+    /// Abscissa.
+    pub x: i32,
+    y: i32,
+}
+
+/// A direction.
+enum Dir {
+    /// Up.
+    Up,
+    Down,
+}
+
+impl Point {
+    /// Moves the point.
+    pub fn move(self, /// Offset.
+        dx: i32) void {}
+}
+
+// not a doc comment
+/// Origin.
+const ORIGIN: i32 = 0
+
+//// not a doc comment either
+fn main(/// Unused.
+    argc: i32) void {}
+";
+    let (ast, warnings) = parse(source);
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert!(ast.docs.is_empty());
+
+    let docs: Vec<_> = ast.decls.iter().map(|decl| decl.docs.clone()).collect();
+    assert_eq!(
+        docs,
+        [
+            vec![" A point.", " In 2D."],
+            vec![" A direction."],
+            vec![],
+            vec![" Origin."],
+            vec![],
+        ]
+    );
+
+    let TopLvlDecl::Struct(point) = &ast.decls[0].decl else {
+        panic!("expected a struct");
+    };
+    assert_eq!(point.fields[0].docs, [" Abscissa."]);
+    assert!(point.fields[1].docs.is_empty());
+
+    let TopLvlDecl::Enum(dir) = &ast.decls[1].decl else {
+        panic!("expected an enum");
+    };
+    assert_eq!(dir.variants[0].docs, [" Up."]);
+    assert!(dir.variants[1].docs.is_empty());
+
+    let TopLvlDecl::Impl(impl_decl) = &ast.decls[2].decl else {
+        panic!("expected an impl block");
+    };
+    assert_eq!(impl_decl.fns[0].docs, [" Moves the point."]);
+    assert_eq!(impl_decl.fns[0].proto.args[0].docs, [" Offset."]);
+
+    let TopLvlDecl::Function { proto, .. } = &ast.decls[4].decl else {
+        panic!("expected a function");
+    };
+    assert_eq!(proto.args[0].docs, [" Unused."]);
+}
+
+#[test]
+fn unused_docs() {
+    let item_doc = "`///` doc comments go before a declaration or a parameter";
+    let file_doc = "`//!` doc comments document the file, they go at its start";
+
+    // inside a body, and at the end of the file
+    let (_, warnings) = parse("package a\nfn f() void {\n    /// x\n    return;\n}\n/// end\n");
+    assert_eq!(
+        warnings,
+        [
+            ("unused doc comment".to_owned(), item_doc.to_owned()),
+            ("unused doc comment".to_owned(), item_doc.to_owned()),
+        ]
+    );
+
+    // after the package clause
+    let (ast, warnings) = parse("package a\n//! late\nfn f() void {}\n");
+    assert!(ast.decls[0].docs.is_empty());
+    assert_eq!(
+        warnings,
+        [("unused doc comment".to_owned(), file_doc.to_owned())]
+    );
+
+    // before the package clause, an item doc documents nothing
+    let (ast, warnings) = parse("/// not the file\npackage a\nfn f() void {}\n");
+    assert!(ast.docs.is_empty());
+    assert!(ast.decls[0].docs.is_empty());
+    assert_eq!(
+        warnings,
+        [("unused doc comment".to_owned(), item_doc.to_owned())]
+    );
+}
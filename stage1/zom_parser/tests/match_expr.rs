@@ -0,0 +1,81 @@
+//! Match expressions.
+
+use std::path::Path;
+
+use zom_errors::prelude::*;
+use zom_lexer::Lexer;
+use zom_parser::{
+    expr::{Expr, Expression},
+    source_file::SourceFile,
+    stmt::Stmt,
+    toplvldecl::TopLvlDecl,
+    Parser,
+};
+
+fn parse(source: &str) -> Option<SourceFile> {
+    let path = Path::new("match_expr.zom");
+    let lctx = LogContext::new(source, path, LogOutput::default());
+    match Parser::new(Lexer::new(source, path, lctx)).parse() {
+        FinalRes::Ok(ast, _) => Some(ast),
+        FinalRes::Err(_) => None,
+    }
+}
+
+/// Parses the body of a function made of a single match statement, and
+/// returns its scrutinee and its number of arms.
+fn parse_match(body: &str) -> (Expression, usize) {
+    let source = format!("package test fn f() void {{ {body} }}");
+    let file = parse(&source).unwrap_or_else(|| panic!("couldn't parse {body:?}"));
+    let TopLvlDecl::Function {
+        body: Some(block), ..
+    } = &file.decls[0].decl
+    else {
+        panic!("expected a function");
+    };
+    match &block.stmts[0].stmt {
+        Stmt::ExprStmt(Expression {
+            expr: Expr::MatchExpr { scrutinee, arms },
+            ..
+        }) => ((**scrutinee).clone(), arms.len()),
+        stmt => panic!("expected a match, found {stmt:?}"),
+    }
+}
+
+#[test]
+fn no_struct_literal_in_scrutinee() {
+    let (scrutinee, arms) = parse_match("match x {}");
+    assert!(matches!(scrutinee.expr, Expr::IdentifierExpr(ref x) if x == "x"));
+    assert_eq!(arms, 0);
+
+    let (scrutinee, arms) = parse_match("match p.kind { _ => 1 }");
+    assert!(matches!(scrutinee.expr, Expr::MemberAccessExpr { .. }));
+    assert_eq!(arms, 1);
+}
+
+#[test]
+fn struct_literal_in_parenthesized_scrutinee() {
+    let (scrutinee, _) = parse_match("match (P { a: 1 }) { _ => 1 }");
+    let Expr::ParenthesizedExpr(inner) = scrutinee.expr else {
+        panic!("expected a parenthesized expression");
+    };
+    assert!(matches!(inner.expr, Expr::StructLitExpr { .. }));
+
+    let (scrutinee, _) = parse_match("match f(P {}) { _ => 1 }");
+    let Expr::CallExpr { args, .. } = scrutinee.expr else {
+        panic!("expected a call");
+    };
+    assert!(matches!(args[0].expr, Expr::StructLitExpr { .. }));
+}
+
+#[test]
+fn struct_literal_after_match() {
+    let source = "package test fn f() void { match x { _ => {} } var p = P { a: 1 }; }";
+    let file = parse(source).expect("couldn't parse");
+    let TopLvlDecl::Function {
+        body: Some(block), ..
+    } = &file.decls[0].decl
+    else {
+        panic!("expected a function");
+    };
+    assert_eq!(block.stmts.len(), 2);
+}
@@ -0,0 +1,433 @@
+use zom_errors::prelude::*;
+
+use crate::ty::Ty;
+
+/// use of a name that isn't declared
+pub struct Undeclared {
+    /// what kind of thing was expected, e.g: "variable", "function"
+    pub kind: &'static str,
+    /// name that was used
+    pub name: String,
+    /// location of the name
+    pub location: CodeSpan,
+}
+
+impl Log for Undeclared {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!("cannot find {} `{}` in this scope", self.kind, self.name).into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some("not found in this scope".into())
+    }
+}
+
+/// a value of a type was found where another type was expected
+pub struct MismatchedTypes {
+    /// expected type
+    pub expected: Ty,
+    /// found type
+    pub found: Ty,
+    /// location of the value with the wrong type
+    pub location: CodeSpan,
+}
+
+impl Log for MismatchedTypes {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        "mismatched types".into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some(format!("expected `{}`, found `{}`", self.expected, self.found).into())
+    }
+}
+
+/// an operator was used with operand(s) of a type it doesn't support
+pub struct InvalidOperand {
+    /// the operator, formatted
+    pub op: String,
+    /// type of the operand
+    pub ty: Ty,
+    /// location of the operation
+    pub location: CodeSpan,
+}
+
+impl Log for InvalidOperand {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!("cannot apply operator `{}` to type `{}`", self.op, self.ty).into()
+    }
+}
+
+/// a function was called with the wrong amount of arguments
+pub struct WrongArgCount {
+    /// name of the function
+    pub name: String,
+    /// amount of arguments of the function
+    pub expected: usize,
+    /// amount of arguments given
+    pub found: usize,
+    /// location of the call
+    pub location: CodeSpan,
+}
+
+impl Log for WrongArgCount {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!(
+            "function `{}` takes {} argument{} but {} {} supplied",
+            self.name,
+            self.expected,
+            if self.expected == 1 { "" } else { "s" },
+            self.found,
+            if self.found == 1 { "was" } else { "were" },
+        )
+        .into()
+    }
+}
+
+/// the end of a non-void function can be reached without a return statement
+pub struct MissingReturn {
+    /// name of the function
+    pub name: String,
+    /// return type of the function
+    pub ret_ty: Ty,
+    /// location of the return type
+    pub location: CodeSpan,
+}
+
+impl Log for MissingReturn {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!("function `{}` may end without returning a value", self.name).into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some(format!("expected `{}` because of the return type", self.ret_ty).into())
+    }
+}
+
+/// the construct is parsed but the code generation doesn't support it yet
+pub struct Unsupported {
+    /// what isn't supported, e.g: "member access"
+    pub what: String,
+    /// location of the construct
+    pub location: CodeSpan,
+}
+
+impl Log for Unsupported {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!("{} is not supported yet", self.what).into()
+    }
+}
+
+/// an imported package has no source file in the source roots
+pub struct MissingPackage {
+    /// qualified name of the package
+    pub name: String,
+    /// path of the file where the package was expected, relative to a source
+    /// root
+    pub expected_file: String,
+    /// location of the import
+    pub location: CodeSpan,
+}
+
+impl Log for MissingPackage {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!("cannot find package `{}`", self.name).into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some(format!("no `{}` in the source root(s)", self.expected_file).into())
+    }
+}
+
+/// a package imports itself, directly or through other packages
+pub struct ImportCycle {
+    /// qualified names of the packages of the cycle, starting and ending with
+    /// the package containing the import
+    pub cycle: Vec<String>,
+    /// location of the import closing the cycle
+    pub location: CodeSpan,
+}
+
+impl Log for ImportCycle {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!(
+            "import cycle between packages `{}`",
+            self.cycle.join("` -> `")
+        )
+        .into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some("imported here".into())
+    }
+}
+
+/// the package clause of an imported file doesn't match its path
+pub struct PackageMismatch {
+    /// qualified name the file was imported with
+    pub expected: String,
+    /// qualified name in the package clause
+    pub found: String,
+    /// location of the package clause
+    pub location: CodeSpan,
+}
+
+impl Log for PackageMismatch {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!(
+            "file of package `{}` declares package `{}`",
+            self.expected, self.found
+        )
+        .into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some(format!("expected `package {}`", self.expected).into())
+    }
+}
+
+/// an item of an imported package isn't `pub`
+pub struct PrivateItem {
+    /// what kind of item it is, e.g: "function", "global"
+    pub kind: &'static str,
+    /// name of the item
+    pub name: String,
+    /// qualified name of the package
+    pub package: String,
+    /// location of the use of the item
+    pub location: CodeSpan,
+}
+
+impl Log for PrivateItem {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!(
+            "{} `{}` of package `{}` is private",
+            self.kind, self.name, self.package
+        )
+        .into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some("only `pub` items can be used by other packages".into())
+    }
+}
+
+/// access to a field that the type doesn't have
+pub struct NoField {
+    /// type of the value
+    pub ty: Ty,
+    /// name of the field
+    pub name: String,
+    /// location of the name of the field
+    pub location: CodeSpan,
+}
+
+impl Log for NoField {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!("no field `{}` on type `{}`", self.name, self.ty).into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some("unknown field".into())
+    }
+}
+
+/// use of a variant that the enum doesn't have
+pub struct NoVariant {
+    /// the enum
+    pub ty: Ty,
+    /// name of the variant
+    pub name: String,
+    /// location of the name of the variant
+    pub location: CodeSpan,
+}
+
+impl Log for NoVariant {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!("no variant `{}` in enum `{}`", self.name, self.ty).into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some("unknown variant".into())
+    }
+}
+
+/// use of a function of an `impl` block that the type doesn't have
+pub struct NoMethod {
+    /// the type of the `impl` blocks
+    pub ty: Ty,
+    /// name of the function
+    pub name: String,
+    /// location of the name of the function
+    pub location: CodeSpan,
+}
+
+impl Log for NoMethod {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        format!(
+            "no function or method `{}` for type `{}`",
+            self.name, self.ty
+        )
+        .into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some(format!("not found in the `impl` blocks of `{}`", self.ty).into())
+    }
+}
+
+/// values of the scrutinee of a `match` aren't matched by any arm
+pub struct NonExhaustive {
+    /// type of the scrutinee
+    pub ty: Ty,
+    /// examples of the values not matched, formatted as patterns
+    pub missing: Vec<String>,
+    /// location of the scrutinee
+    pub location: CodeSpan,
+}
+
+impl NonExhaustive {
+    /// The amount of examples of the values not matched shown.
+    const SHOWN: usize = 3;
+}
+
+impl Log for NonExhaustive {
+    fn location(&self) -> CodeSpan {
+        self.location.clone()
+    }
+
+    fn level(&self) -> LogLevel {
+        LogLevel::Error
+    }
+
+    fn msg(&self) -> Box<str> {
+        let shown: Vec<_> = self
+            .missing
+            .iter()
+            .take(Self::SHOWN)
+            .map(|pat| format!("`{pat}`"))
+            .collect();
+        let patterns = match self.missing.len() {
+            1 => shown[0].clone(),
+            len if len <= Self::SHOWN => {
+                format!("{} and {}", shown[..len - 1].join(", "), shown[len - 1])
+            }
+            len => format!("{} and {} more", shown.join(", "), len - Self::SHOWN),
+        };
+        format!("non-exhaustive patterns: {patterns} not covered").into()
+    }
+
+    fn cursor_msg(&self) -> Option<Box<str>> {
+        Some(
+            format!(
+                "the value of type `{}` may not match any arm, consider adding arms or a `_` arm",
+                self.ty
+            )
+            .into(),
+        )
+    }
+}
@@ -0,0 +1,9 @@
+//! Zom crate responsible for the semantic analysis of the AST.
+//!
+//! It doesn't depend on LLVM, so a source file can be checked quickly.
+
+pub mod err;
+pub mod loader;
+pub mod ty;
+pub mod typeck;
+pub mod usefulness;
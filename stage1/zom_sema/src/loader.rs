@@ -0,0 +1,364 @@
+//! Module responsible for loading a program, its source files and the packages
+//! they import.
+//!
+//! A package is a source file, the package `std.fmt` is in the file
+//! `std/fmt.zom` of a source root. Every reachable file is parsed once and the
+//! packages are ordered so a package comes after the packages it imports.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use zom_common::token::Token;
+use zom_errors::prelude::*;
+use zom_lexer::Lexer;
+use zom_parser::{
+    source_file::{ImportDecl, SourceFile},
+    Parser,
+};
+
+use crate::err::*;
+
+/// Index of a package in the [`Program`].
+pub type PackageId = usize;
+
+/// A source file of the program, parsed.
+#[derive(Debug)]
+pub struct Package {
+    pub path: PathBuf,
+    pub source: String,
+    pub ast: SourceFile,
+    /// The tokens of the file, with the EOF, only kept for the files given to
+    /// [`Loader::load`] by a loader [keeping them](Loader::keeping_tokens),
+    /// unless another of these files imports it first.
+    pub tokens: Vec<Token>,
+    /// The imports of the file, resolved.
+    pub imports: Vec<Import>,
+    /// Warnings of the lexer and the parser.
+    logs: LogStream,
+}
+
+impl Package {
+    /// Qualified name of the package, e.g: `std.fmt`.
+    pub fn name(&self) -> String {
+        self.ast.pkg_path.path.join(".")
+    }
+}
+
+/// An import declaration of a source file.
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// Name the import is used with in the file, its alias or the last part
+    /// of the imported path.
+    pub name: String,
+    /// The imported package.
+    pub package: PackageId,
+    /// The imported item, for an import of an item like `import std.fmt.println`.
+    pub item: Option<String>,
+    pub span: CodeSpan,
+}
+
+/// Every package of a program.
+#[derive(Debug)]
+pub struct Program {
+    /// A package comes after the packages it imports.
+    pub packages: Vec<Package>,
+    /// Errors of the files that couldn't be loaded, they aren't packages of
+    /// the program.
+    errors: LogStream,
+    /// Amount of files that couldn't be loaded.
+    failed: usize,
+    output: LogOutput,
+}
+
+impl Program {
+    /// Returns the log context of a package, containing the warnings of its
+    /// lexing and parsing.
+    pub fn lctx(&self, id: PackageId) -> LogContext<'_> {
+        let package = &self.packages[id];
+        LogContext::with_stream(
+            &package.source,
+            &package.path,
+            self.output,
+            package.logs.clone(),
+        )
+    }
+
+    /// Returns the amount of files that couldn't be loaded, because they
+    /// contain an error or import a package that does.
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    /// Returns the errors of the files that couldn't be loaded.
+    pub fn errors(&self) -> &LogStream {
+        &self.errors
+    }
+}
+
+/// A source file couldn't be read.
+#[derive(Debug)]
+pub struct LoadError {
+    pub path: PathBuf,
+    pub err: io::Error,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Couldn't read `{}`, {}", self.path.display(), self.err)
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Loads the source files of a program, with the packages they import.
+pub struct Loader {
+    /// Directories where the imported packages are searched, in order.
+    roots: Vec<PathBuf>,
+    output: LogOutput,
+
+    packages: Vec<Package>,
+    /// The loaded packages, by canonical path.
+    ids: HashMap<PathBuf, PackageId>,
+    /// Files that contain errors, or import a package that does.
+    failed: HashSet<PathBuf>,
+    /// Files being loaded, each one imports the next.
+    stack: Vec<(PathBuf, String)>,
+    logs: LogStream,
+    /// Are the tokens of the files given to `load` kept?
+    keep_tokens: bool,
+}
+
+impl Loader {
+    pub fn new(roots: Vec<PathBuf>, output: LogOutput) -> Loader {
+        Loader {
+            roots,
+            output,
+            packages: Vec::new(),
+            ids: HashMap::new(),
+            failed: HashSet::new(),
+            stack: Vec::new(),
+            logs: LogStream::new(output),
+            keep_tokens: false,
+        }
+    }
+
+    /// Keeps the tokens of the files given to `load`, they are lexed once for
+    /// both the parser and the tokens.
+    pub fn keeping_tokens(mut self) -> Loader {
+        self.keep_tokens = true;
+        self
+    }
+
+    /// Loads the files, and the packages they import. The files don't have to
+    /// be in a source root, their package clause isn't checked.
+    ///
+    /// The files with errors are left out of the program, the other ones are
+    /// still loaded, so they can be checked.
+    pub fn load(mut self, files: &[PathBuf]) -> Result<Program, LoadError> {
+        for file in files {
+            self.load_file(file, None)?;
+        }
+
+        Ok(Program {
+            packages: self.packages,
+            errors: self.logs,
+            failed: self.failed.len(),
+            output: self.output,
+        })
+    }
+
+    /// Loads a file, `expected` is the name of the package when it's imported.
+    ///
+    /// Returns `None` if the file, or a package it imports, contains an error.
+    fn load_file(
+        &mut self,
+        path: &Path,
+        expected: Option<&[String]>,
+    ) -> Result<Option<PackageId>, LoadError> {
+        let canonical = path.canonicalize().map_err(|err| LoadError {
+            path: path.to_owned(),
+            err,
+        })?;
+
+        if self.failed.contains(&canonical) {
+            return Ok(None);
+        }
+        if let Some(&id) = self.ids.get(&canonical) {
+            // the file was loaded as a root, its package clause wasn't checked
+            if let Some(expected) = expected {
+                let package = &self.packages[id];
+                if package.ast.pkg_path.path != expected {
+                    let mut lctx = LogContext::new(&package.source, &package.path, self.output);
+                    lctx.push(PackageMismatch {
+                        expected: expected.join("."),
+                        found: package.name(),
+                        location: package.ast.pkg_path.span.clone(),
+                    });
+                    self.logs.append(lctx.stream());
+                    self.failed.insert(canonical);
+                    return Ok(None);
+                }
+            }
+            return Ok(Some(id));
+        }
+
+        let source = fs::read_to_string(path).map_err(|err| LoadError {
+            path: path.to_owned(),
+            err,
+        })?;
+        let lctx = LogContext::new(&source, path, self.output);
+
+        let mut lexer = Lexer::new(&source, path, lctx);
+        // only the files given to `load` aren't imported
+        if self.keep_tokens && expected.is_none() {
+            lexer = lexer.recording();
+        }
+        let (res, tokens) = Parser::new(lexer).parse_with_tokens();
+        let (ast, mut lctx) = match res {
+            FinalRes::Ok(ast, lctx) => (ast, lctx),
+            FinalRes::Err(logs) => {
+                self.logs.append(logs);
+                self.failed.insert(canonical);
+                return Ok(None);
+            }
+        };
+
+        if let Some(expected) = expected {
+            if ast.pkg_path.path != expected {
+                lctx.push(PackageMismatch {
+                    expected: expected.join("."),
+                    found: ast.pkg_path.path.join("."),
+                    location: ast.pkg_path.span.clone(),
+                });
+            }
+        }
+
+        self.stack
+            .push((canonical.clone(), ast.pkg_path.path.join(".")));
+        let mut imports: Vec<Import> = Vec::new();
+        let mut import_failed = false;
+        for decl in &ast.import_decls {
+            match self.load_import(decl, &mut lctx)? {
+                Some(import) => {
+                    if imports.iter().any(|other| other.name == import.name) {
+                        lctx.push(SimpleLog {
+                            level: LogLevel::Error,
+                            msg: format!("`{}` is imported multiple times", import.name).into(),
+                            cursor_msg: Some("consider using `as` to rename it".into()),
+                            location: decl.span.clone(),
+                        });
+                    }
+                    imports.push(import);
+                }
+                None => import_failed = true,
+            }
+        }
+        self.stack.pop();
+
+        let logs = lctx.stream();
+        if import_failed || logs.failed() {
+            self.logs.append(logs);
+            self.failed.insert(canonical);
+            return Ok(None);
+        }
+
+        let id = self.packages.len();
+        self.packages.push(Package {
+            path: path.to_owned(),
+            source,
+            ast,
+            tokens,
+            imports,
+            logs,
+        });
+        self.ids.insert(canonical, id);
+        Ok(Some(id))
+    }
+
+    /// Finds the file of an imported package and loads it, the errors of the
+    /// import are pushed to the log context of the importing file.
+    ///
+    /// Returns `None` if the import failed, or if the imported package
+    /// contains an error.
+    fn load_import(
+        &mut self,
+        decl: &ImportDecl,
+        lctx: &mut LogContext,
+    ) -> Result<Option<Import>, LoadError> {
+        let path = &decl.path.path;
+
+        // `import a.b.c` is the package `a.b.c`, or the item `c` of `a.b`
+        let (file, package, item) = match self.find_package(path) {
+            Some(file) => (file, &path[..], None),
+            None => {
+                let parent = &path[..path.len() - 1];
+                let found = if parent.is_empty() {
+                    None
+                } else {
+                    self.find_package(parent)
+                };
+                match found {
+                    Some(file) => (file, parent, path.last().cloned()),
+                    None => {
+                        lctx.push(MissingPackage {
+                            name: path.join("."),
+                            expected_file: package_file(path).display().to_string(),
+                            location: decl.span.clone(),
+                        });
+                        return Ok(None);
+                    }
+                }
+            }
+        };
+
+        let canonical = file.canonicalize().map_err(|err| LoadError {
+            path: file.clone(),
+            err,
+        })?;
+        if let Some(pos) = self.stack.iter().position(|(path, _)| *path == canonical) {
+            let mut cycle: Vec<String> = self.stack[pos..]
+                .iter()
+                .map(|(_, name)| name.clone())
+                .collect();
+            cycle.push(self.stack[pos].1.clone());
+            lctx.push(ImportCycle {
+                cycle,
+                location: decl.span.clone(),
+            });
+            return Ok(None);
+        }
+
+        let Some(id) = self.load_file(&file, Some(package))? else {
+            return Ok(None);
+        };
+        let name = decl
+            .alias
+            .clone()
+            .unwrap_or_else(|| path.last().unwrap().clone());
+        Ok(Some(Import {
+            name,
+            package: id,
+            item,
+            span: decl.span.clone(),
+        }))
+    }
+
+    /// Looks for the file of a package in the source roots.
+    fn find_package(&self, path: &[String]) -> Option<PathBuf> {
+        let file = package_file(path);
+        self.roots
+            .iter()
+            .map(|root| root.join(&file))
+            .find(|path| path.is_file())
+    }
+}
+
+/// Path of the file of a package, relative to a source root.
+fn package_file(path: &[String]) -> PathBuf {
+    let mut file: PathBuf = path.iter().collect();
+    file.set_extension("zom");
+    file
+}
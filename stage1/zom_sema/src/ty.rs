@@ -0,0 +1,202 @@
+//! Module containing the types resolved by the semantic analysis.
+//!
+//! Unlike the types of the AST, they don't carry any location, they can be
+//! compared and created by the compiler itself, e.g: the type of a
+//! string literal is `*const u8`.
+use std::fmt;
+
+use zom_parser::types::PrimitiveTy;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Ty {
+    Prim(PrimitiveTy),
+    Pointer {
+        is_const: bool,
+        pointee: Box<Ty>,
+    },
+    /// An array of `len` elements.
+    Array {
+        elem: Box<Ty>,
+        len: u64,
+    },
+    /// A pointer to elements and their number.
+    Slice {
+        is_const: bool,
+        elem: Box<Ty>,
+    },
+    /// A struct, by the qualified name of its package and its name.
+    Struct {
+        package: String,
+        name: String,
+    },
+    /// An enum, by the qualified name of its package and its name.
+    Enum {
+        package: String,
+        name: String,
+    },
+}
+
+impl Ty {
+    pub const VOID: Ty = Ty::Prim(PrimitiveTy::Void);
+    pub const BOOL: Ty = Ty::Prim(PrimitiveTy::Bool);
+
+    /// Type of an integer literal when nothing tells which type it should be.
+    pub const DEFAULT_INT: Ty = Ty::Prim(PrimitiveTy::I32);
+
+    /// Type of a float literal when nothing tells which type it should be.
+    pub const DEFAULT_FLOAT: Ty = Ty::Prim(PrimitiveTy::F64);
+
+    /// Type of an index and of a length, `usize`.
+    pub const USIZE: Ty = Ty::Prim(PrimitiveTy::USize);
+
+    /// Type of a char literal, a Unicode scalar value.
+    pub const CHAR: Ty = Ty::Prim(PrimitiveTy::U32);
+
+    /// Type of a string literal, `*const u8`.
+    pub fn str() -> Ty {
+        Ty::Pointer {
+            is_const: true,
+            pointee: Box::new(Ty::Prim(PrimitiveTy::U8)),
+        }
+    }
+
+    pub fn is_void(&self) -> bool {
+        matches!(self, Ty::Prim(PrimitiveTy::Void))
+    }
+
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Ty::Prim(PrimitiveTy::Bool))
+    }
+
+    pub fn is_int(&self) -> bool {
+        use PrimitiveTy::*;
+        matches!(
+            self,
+            Ty::Prim(U8 | U16 | U32 | U64 | U128 | USize | I8 | I16 | I32 | I64 | I128 | ISize)
+        )
+    }
+
+    pub fn is_float(&self) -> bool {
+        use PrimitiveTy::*;
+        matches!(self, Ty::Prim(F16 | F32 | F64 | F128))
+    }
+
+    pub fn is_pointer(&self) -> bool {
+        matches!(self, Ty::Pointer { .. })
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, Ty::Array { .. })
+    }
+
+    pub fn is_slice(&self) -> bool {
+        matches!(self, Ty::Slice { .. })
+    }
+
+    pub fn is_struct(&self) -> bool {
+        matches!(self, Ty::Struct { .. })
+    }
+
+    pub fn is_enum(&self) -> bool {
+        matches!(self, Ty::Enum { .. })
+    }
+
+    /// Is the type a signed integer?
+    pub fn is_signed(&self) -> bool {
+        use PrimitiveTy::*;
+        matches!(self, Ty::Prim(I8 | I16 | I32 | I64 | I128 | ISize))
+    }
+
+    /// Returns the size in bits of an integer type, `None` if the type isn't
+    /// an integer.
+    ///
+    /// `usize` and `isize` are as wide as a pointer of the target, given by
+    /// `pointer_width`, in bits.
+    pub fn int_bits(&self, pointer_width: u32) -> Option<u32> {
+        use PrimitiveTy::*;
+        match self {
+            Ty::Prim(U8 | I8) => Some(8),
+            Ty::Prim(U16 | I16) => Some(16),
+            Ty::Prim(U32 | I32) => Some(32),
+            Ty::Prim(U64 | I64) => Some(64),
+            Ty::Prim(U128 | I128) => Some(128),
+            Ty::Prim(USize | ISize) => Some(pointer_width),
+            _ => None,
+        }
+    }
+
+    /// Returns the type pointed by the pointer type, `None` if it's not a
+    /// pointer type.
+    pub fn pointee(&self) -> Option<&Ty> {
+        match self {
+            Ty::Pointer { pointee, .. } => Some(pointee),
+            _ => None,
+        }
+    }
+
+    /// Returns the type of the elements of the array or slice type, `None`
+    /// if it's neither.
+    pub fn elem(&self) -> Option<&Ty> {
+        match self {
+            Ty::Array { elem, .. } | Ty::Slice { elem, .. } => Some(elem),
+            _ => None,
+        }
+    }
+
+    /// Can a value of this type be used where a value of type `target` is
+    /// expected?
+    ///
+    /// It is true if the types are equal or if it's a `*T` used as a
+    /// `*const T`, or a `[]T` used as a `[]const T`.
+    pub fn coerces_to(&self, target: &Ty) -> bool {
+        match (self, target) {
+            (
+                Ty::Pointer {
+                    is_const: from_const,
+                    pointee: from,
+                },
+                Ty::Pointer {
+                    is_const: to_const,
+                    pointee: to,
+                },
+            )
+            | (
+                Ty::Slice {
+                    is_const: from_const,
+                    elem: from,
+                },
+                Ty::Slice {
+                    is_const: to_const,
+                    elem: to,
+                },
+            ) => (*to_const || !from_const) && from == to,
+            (from, to) => from == to,
+        }
+    }
+}
+
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ty::Prim(prim) => write!(f, "{prim}"),
+            Ty::Pointer {
+                is_const: true,
+                pointee,
+            } => write!(f, "*const {pointee}"),
+            Ty::Pointer {
+                is_const: false,
+                pointee,
+            } => write!(f, "*{pointee}"),
+            Ty::Array { elem, len } => write!(f, "[{len}]{elem}"),
+            Ty::Slice {
+                is_const: true,
+                elem,
+            } => write!(f, "[]const {elem}"),
+            Ty::Slice {
+                is_const: false,
+                elem,
+            } => write!(f, "[]{elem}"),
+            Ty::Struct { name, .. } | Ty::Enum { name, .. } => write!(f, "{name}"),
+        }
+    }
+}
@@ -0,0 +1,3021 @@
+//! Module responsible for the type checking of a source file.
+//!
+//! Every error of the source file that isn't a syntax error is found here,
+//! the code generation only runs on a source file without errors. The packages
+//! imported by the source file are checked before, their interface is given to
+//! the type checker.
+
+use std::collections::{HashMap, HashSet};
+
+use zom_common::operator::OP_MINUSRARROW;
+use zom_errors::prelude::*;
+use zom_parser::{
+    block::Block,
+    enum_decl::EnumDecl,
+    expr::{
+        ArmBody, BinOperation, Expr, Expression, ExpressionList, FieldInit, MatchArm,
+        UnaryOperation,
+    },
+    impl_decl::{ImplDecl, ImplFn},
+    pattern::{Pat as AstPat, Pattern},
+    source_file::SourceFile,
+    stmt::{ForIter, ForRange, Statement, Stmt},
+    struct_decl::StructDecl,
+    toplvldecl::{Prototype, ReceiverKind, TopLvlDecl},
+    types::{PrimitiveTy, Ty as AstTy, Type as AstType},
+    var_decl::{VarDecl, VarType},
+};
+
+use crate::{
+    err::*,
+    ty::Ty,
+    usefulness::{biased, Ctor, Pat, PatCx},
+};
+
+/// Result of the check of a part of the AST, the error is pushed to the
+/// `LogContext` by the caller.
+pub type CheckResult<T> = Result<T, Box<dyn Log>>;
+
+/// Signature of a function.
+#[derive(Debug, Clone)]
+pub struct FnSig {
+    pub args: Vec<Ty>,
+    pub ret_ty: Ty,
+}
+
+impl FnSig {
+    /// Does the `main` function take the program arguments,
+    /// `(argc: i32, argv: **u8)`?
+    pub fn is_main_with_args(&self) -> bool {
+        match self.args.as_slice() {
+            [argc, argv] => {
+                *argc == Ty::DEFAULT_INT
+                    && argv.pointee().and_then(Ty::pointee) == Some(&Ty::Prim(PrimitiveTy::U8))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A function of an `impl` block, the receiver of a method is the first
+/// argument of its signature.
+#[derive(Debug, Clone)]
+pub struct MethodDef {
+    pub sig: FnSig,
+    pub receiver: Option<ReceiverKind>,
+}
+
+/// How the receiver of a method call is passed to the method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Autoref {
+    /// The value, or the pointer, is passed as is.
+    Value,
+    /// The value is loaded from the pointer.
+    Deref,
+    /// The address of the place is passed.
+    Ref,
+    /// The value is stored in a temporary, its address is passed.
+    RefTemporary,
+}
+
+/// A call of a function of an `impl` block.
+#[derive(Debug, Clone)]
+pub struct MethodCall {
+    pub ty: Ty,
+    pub name: String,
+    /// How the receiver is passed, `None` if the function is called by its
+    /// path, like `Point.new(..)`, with every argument.
+    pub autoref: Option<Autoref>,
+}
+
+/// Returns the symbol of a function or a global of the package, mangled like
+/// the Itanium C++ names: `_ZN`, the length and the name of every part of the
+/// path, then `E`, e.g: `_ZN3std3fmt7printlnE` for `println` of `std.fmt`.
+pub fn item_symbol(package: &str, name: &str) -> String {
+    mangle(package.split('.').chain([name]))
+}
+
+/// Returns the symbol of a function of an `impl` block of the type, mangled
+/// like the items, e.g: `_ZN3geo5Point3lenE` for `len` of `geo.Point`.
+pub fn method_symbol(ty: &Ty, name: &str) -> String {
+    let (Ty::Struct {
+        package,
+        name: ty_name,
+    }
+    | Ty::Enum {
+        package,
+        name: ty_name,
+    }) = ty
+    else {
+        panic!("`{ty}` can't have an `impl` block")
+    };
+    mangle(package.split('.').chain([ty_name.as_str(), name]))
+}
+
+fn mangle<'p>(path: impl Iterator<Item = &'p str>) -> String {
+    let mut symbol = "_ZN".to_owned();
+    for part in path {
+        symbol += &format!("{}{part}", part.len());
+    }
+    symbol + "E"
+}
+
+/// A variable and what can be done with it.
+#[derive(Debug, Clone)]
+pub struct Var {
+    pub ty: Ty,
+    pub mutable: bool,
+}
+
+/// Fields of a struct, in the order of the declaration, it's also their order
+/// in memory.
+#[derive(Debug, Clone)]
+pub struct StructDef {
+    pub fields: Vec<FieldDef>,
+}
+
+impl StructDef {
+    /// Returns the index of the field and the field.
+    pub fn field(&self, name: &str) -> Option<(usize, &FieldDef)> {
+        self.fields
+            .iter()
+            .enumerate()
+            .find(|(_, field)| field.name == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldDef {
+    pub name: String,
+    pub ty: Ty,
+    /// Can other packages use the field?
+    pub public: bool,
+}
+
+/// Variants of an enum, in the order of the declaration.
+#[derive(Debug, Clone)]
+pub struct EnumDef {
+    /// Integer type of the discriminants, the type of the tag.
+    pub repr: Ty,
+    pub variants: Vec<VariantDef>,
+}
+
+impl EnumDef {
+    /// Returns the index of the variant and the variant.
+    pub fn variant(&self, name: &str) -> Option<(usize, &VariantDef)> {
+        self.variants
+            .iter()
+            .enumerate()
+            .find(|(_, variant)| variant.name == name)
+    }
+
+    /// Does a variant carry a payload? If not, the enum is only its
+    /// discriminant, like a C enum.
+    pub fn has_payload(&self) -> bool {
+        self.variants
+            .iter()
+            .any(|variant| !variant.fields.is_empty())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VariantDef {
+    pub name: String,
+    /// The value of the tag of the variant, it fits in the representation of
+    /// the enum.
+    pub discriminant: i128,
+    /// Types of the payload, empty if the variant has none.
+    pub fields: Vec<Ty>,
+}
+
+/// A type declared by a package.
+#[derive(Debug, Clone)]
+pub enum TypeDef {
+    Struct(StructDef),
+    Enum(EnumDef),
+}
+
+/// An item of a package, and whether other packages can use it.
+#[derive(Debug, Clone)]
+pub struct Item<T> {
+    pub def: T,
+    pub public: bool,
+}
+
+/// The functions, the globals and the types of a package, what the packages
+/// importing it can refer to.
+#[derive(Debug, Clone, Default)]
+pub struct Interface {
+    /// Qualified name of the package.
+    pub package: String,
+    pub functions: HashMap<String, Item<FnSig>>,
+    pub globals: HashMap<String, Item<Var>>,
+    /// Types of the package and of the packages it imports, so the types used
+    /// by its items are known by the packages importing it.
+    pub types: HashMap<Ty, Item<TypeDef>>,
+    /// Functions of the `impl` blocks of the types, by type and by name, of
+    /// the package and of the packages it imports, like the types.
+    pub methods: HashMap<Ty, HashMap<String, Item<MethodDef>>>,
+    /// The `extern` functions, they keep their name as symbol.
+    pub externs: HashSet<String>,
+}
+
+impl Interface {
+    /// Returns the symbol of a function of the package, only the `extern`
+    /// functions aren't mangled. The `main` of the root package is named by
+    /// the code generator.
+    pub fn function_symbol(&self, name: &str) -> String {
+        if self.externs.contains(name) {
+            return name.to_owned();
+        }
+        item_symbol(&self.package, name)
+    }
+
+    /// Returns the symbol of a global of the package.
+    pub fn global_symbol(&self, name: &str) -> String {
+        item_symbol(&self.package, name)
+    }
+
+    /// Returns the struct or the enum of the package with this name.
+    pub fn named_ty(&self, name: &str) -> Option<Ty> {
+        let (package, name) = (self.package.clone(), name.to_owned());
+        [
+            Ty::Struct {
+                package: package.clone(),
+                name: name.clone(),
+            },
+            Ty::Enum { package, name },
+        ]
+        .into_iter()
+        .find(|ty| self.types.contains_key(ty))
+    }
+}
+
+/// An import of the source file, with the interface of the imported package.
+#[derive(Debug, Clone)]
+pub struct ResolvedImport {
+    /// Name the import is used with in the file.
+    pub name: String,
+    /// The imported item, `None` if the whole package is imported.
+    pub item: Option<String>,
+    pub interface: Interface,
+    pub span: CodeSpan,
+}
+
+/// What the type checker found out about a source file, the code generation
+/// relies on it.
+#[derive(Debug, Default)]
+pub struct TypeckResults {
+    /// Type of every expression, by its span.
+    expr_tys: HashMap<CodeSpan, Ty>,
+    /// Every type written in the source file, resolved, by its span.
+    tys: HashMap<CodeSpan, Ty>,
+    /// Variant of every `Enum.Variant` expression, by its span.
+    variants: HashMap<CodeSpan, (Ty, usize)>,
+    /// Types of the source file and of the imported packages.
+    pub types: HashMap<Ty, Item<TypeDef>>,
+    /// Import of the package of every `package.item` expression, by its span.
+    package_items: HashMap<CodeSpan, usize>,
+    /// Functions of the `impl` blocks, of the source file and of the imported
+    /// packages.
+    pub methods: HashMap<Ty, HashMap<String, Item<MethodDef>>>,
+    /// Function of an `impl` block called by every call, by the span of the
+    /// called expression, like `p.len` in `p.len()`.
+    method_calls: HashMap<CodeSpan, MethodCall>,
+    /// Imports of the source file.
+    pub imports: Vec<ResolvedImport>,
+    /// Interface of the package of the source file.
+    pub interface: Interface,
+}
+
+impl TypeckResults {
+    /// Returns the type of the expression.
+    ///
+    /// Panic:
+    ///   If the expression wasn't type checked.
+    pub fn expr_ty(&self, expr: &Expression) -> &Ty {
+        &self.expr_tys[&expr.span]
+    }
+
+    /// Returns the resolved type written in the source file.
+    ///
+    /// Panic:
+    ///   If the type wasn't resolved.
+    pub fn ty(&self, ty: &AstType) -> &Ty {
+        &self.tys[&ty.span]
+    }
+
+    /// Returns the fields of the struct type.
+    ///
+    /// Panic:
+    ///   If the type isn't a struct.
+    pub fn struct_def(&self, ty: &Ty) -> &StructDef {
+        match &self.types[ty].def {
+            TypeDef::Struct(def) => def,
+            TypeDef::Enum(_) => panic!("`{ty}` isn't a struct"),
+        }
+    }
+
+    /// Returns the variants of the enum type.
+    ///
+    /// Panic:
+    ///   If the type isn't an enum.
+    pub fn enum_def(&self, ty: &Ty) -> &EnumDef {
+        match &self.types[ty].def {
+            TypeDef::Enum(def) => def,
+            TypeDef::Struct(_) => panic!("`{ty}` isn't an enum"),
+        }
+    }
+
+    /// Returns the enum and the index of the variant if the expression is a
+    /// variant, like `Color.Red`.
+    pub fn variant(&self, expr: &Expression) -> Option<(&Ty, usize)> {
+        self.variants
+            .get(&expr.span)
+            .map(|(ty, index)| (ty, *index))
+    }
+
+    /// Returns the function of an `impl` block if the expression is the called
+    /// expression of a call of one, like `p.len` or `Point.new`.
+    pub fn method_call(&self, fn_op: &Expression) -> Option<&MethodCall> {
+        self.method_calls.get(&fn_op.span)
+    }
+
+    /// Returns the import of the package if the expression is an item of an
+    /// imported package, like `fmt.println`.
+    pub fn package_item(&self, expr: &Expression) -> Option<&ResolvedImport> {
+        self.package_items
+            .get(&expr.span)
+            .map(|&import| &self.imports[import])
+    }
+}
+
+/// A loop or a labeled block, that a `break` or a `continue` can target.
+struct JumpTarget {
+    label: Option<String>,
+    is_loop: bool,
+    /// Is there a `break` targeting it?
+    broken: bool,
+}
+
+pub struct TypeChecker<'a> {
+    lctx: LogContext<'a>,
+    /// Qualified name of the package of the source file.
+    package: String,
+
+    functions: HashMap<String, FnSig>,
+    globals: HashMap<String, Var>,
+    /// Types that can be named without their package, the ones of the source
+    /// file and the imported ones.
+    type_names: HashMap<String, Ty>,
+    /// Local variables, the last scope is the innermost.
+    scopes: Vec<HashMap<String, Var>>,
+    jump_targets: Vec<JumpTarget>,
+    ret_ty: Ty,
+    /// Width of a pointer of the target, in bits.
+    pointer_width: u32,
+
+    results: TypeckResults,
+}
+
+impl<'a> TypeChecker<'a> {
+    pub fn new(lctx: LogContext<'a>) -> Self {
+        TypeChecker {
+            lctx,
+            package: String::new(),
+            functions: HashMap::new(),
+            globals: HashMap::new(),
+            type_names: HashMap::new(),
+            scopes: Vec::new(),
+            jump_targets: Vec::new(),
+            ret_ty: Ty::VOID,
+            pointer_width: usize::BITS,
+            results: TypeckResults::default(),
+        }
+    }
+
+    /// Sets the width of a pointer of the target, in bits, it's the width of
+    /// `usize` and `isize`. By default it's the one of the host.
+    pub fn with_pointer_width(mut self, pointer_width: u32) -> Self {
+        self.pointer_width = pointer_width;
+        self
+    }
+
+    /// Sets the imports of the source file, with the interface of the packages
+    /// they import.
+    pub fn with_imports(mut self, imports: Vec<ResolvedImport>) -> Self {
+        self.results.imports = imports;
+        self
+    }
+
+    /// Type checks the source file. Every function is declared before any body
+    /// is checked, so a function can be called before its definition, and
+    /// every struct and enum is declared before any type is resolved.
+    pub fn check(mut self, source: &SourceFile) -> FinalRes<'a, TypeckResults> {
+        self.package = source.pkg_path.path.join(".");
+
+        self.declare_types(source);
+
+        let mut bodies = Vec::new();
+        for decl in &source.decls {
+            match &decl.decl {
+                TopLvlDecl::Function { proto, body, .. } => {
+                    match self.declare_function(proto, body.is_some()) {
+                        Ok(sig) => bodies.extend(body.as_ref().map(|body| (sig, proto, body))),
+                        Err(err) => self.lctx.push_boxed(err),
+                    }
+                }
+                TopLvlDecl::Impl(impl_decl) => {
+                    let fns = self.declare_impl(decl.public, impl_decl);
+                    bodies.extend(fns.into_iter().map(|(sig, f)| (sig, &f.proto, &f.body)));
+                }
+                _ => {}
+            }
+        }
+
+        for import in self.results.imports.clone() {
+            if let Err(err) = self.declare_imported_item(&import) {
+                self.lctx.push_boxed(err);
+            }
+        }
+
+        for decl in &source.decls {
+            if let TopLvlDecl::GlobalVarDecl(var_decl) = &decl.decl {
+                if let Err(err) = self.check_global(var_decl) {
+                    self.lctx.push_boxed(err);
+                }
+            }
+        }
+
+        for (sig, proto, body) in bodies {
+            self.check_fn_body(sig, proto, body);
+        }
+
+        if self.lctx.failed() {
+            return FinalRes::Err(self.lctx.stream());
+        }
+        self.results.interface = self.interface(source);
+        FinalRes::Ok(self.results, self.lctx)
+    }
+
+    /// Builds the interface of the package, from the items of the source file.
+    fn interface(&self, source: &SourceFile) -> Interface {
+        let mut interface = Interface {
+            package: self.package.clone(),
+            types: self.results.types.clone(),
+            methods: self.results.methods.clone(),
+            ..Default::default()
+        };
+        for decl in &source.decls {
+            match &decl.decl {
+                TopLvlDecl::Function { lib, proto, .. } => {
+                    let def = self.functions[&proto.name].clone();
+                    let item = Item {
+                        def,
+                        public: decl.public,
+                    };
+                    interface.functions.insert(proto.name.clone(), item);
+                    if lib.is_some() {
+                        interface.externs.insert(proto.name.clone());
+                    }
+                }
+                TopLvlDecl::GlobalVarDecl(var_decl) => {
+                    let def = self.globals[&var_decl.name].clone();
+                    let item = Item {
+                        def,
+                        public: decl.public,
+                    };
+                    interface.globals.insert(var_decl.name.clone(), item);
+                }
+                TopLvlDecl::Struct(_) | TopLvlDecl::Enum(_) | TopLvlDecl::Impl(_) => {}
+            }
+        }
+        interface
+    }
+
+    /// Declares the item of an import like `import std.fmt.println`, under the
+    /// name of the import.
+    fn declare_imported_item(&mut self, import: &ResolvedImport) -> CheckResult<()> {
+        let Some(item) = &import.item else {
+            return Ok(());
+        };
+        let interface = &import.interface;
+        if !interface.functions.contains_key(item)
+            && !interface.globals.contains_key(item)
+            && interface.named_ty(item).is_some()
+        {
+            // it's a type, declared with the other types
+            return Ok(());
+        }
+        if self.functions.contains_key(&import.name) || self.globals.contains_key(&import.name) {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("`{}` is already declared in this package", import.name).into(),
+                cursor_msg: Some("consider using `as` to rename the import".into()),
+                location: import.span.clone(),
+            }));
+        }
+
+        // a private item is still declared, so that its uses aren't reported
+        // as undeclared
+        if let Some(function) = interface.functions.get(item) {
+            self.functions
+                .insert(import.name.clone(), function.def.clone());
+            self.imported_function(import, item, import.span.clone())?;
+        } else {
+            if let Some(global) = interface.globals.get(item) {
+                self.globals.insert(import.name.clone(), global.def.clone());
+            }
+            self.imported_global(import, item, import.span.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Declares the structs and the enums of the source file and the imported
+    /// ones, then resolves the types of their fields and of their variants.
+    fn declare_types(&mut self, source: &SourceFile) {
+        for import in &self.results.imports {
+            let types = &import.interface.types;
+            self.results
+                .types
+                .extend(types.iter().map(|(ty, item)| (ty.clone(), item.clone())));
+            let methods = &import.interface.methods;
+            self.results
+                .methods
+                .extend(methods.iter().map(|(ty, fns)| (ty.clone(), fns.clone())));
+        }
+
+        for import in self.results.imports.clone() {
+            let interface = &import.interface;
+            let Some(item) = &import.item else {
+                continue;
+            };
+            let Some(ty) = interface.named_ty(item) else {
+                continue;
+            };
+            if let Err(err) = self.imported_ty(interface, item, import.span.clone()) {
+                self.lctx.push_boxed(err);
+            }
+            // declared even if it's private, like the imported functions
+            self.type_names.insert(import.name.clone(), ty);
+        }
+
+        let mut decls = Vec::new();
+        for decl in &source.decls {
+            let (name, span, ty) = match &decl.decl {
+                TopLvlDecl::Struct(struct_decl) => {
+                    let ty = Ty::Struct {
+                        package: self.package.clone(),
+                        name: struct_decl.name.clone(),
+                    };
+                    (&struct_decl.name, &struct_decl.span, ty)
+                }
+                TopLvlDecl::Enum(enum_decl) => {
+                    let ty = Ty::Enum {
+                        package: self.package.clone(),
+                        name: enum_decl.name.clone(),
+                    };
+                    (&enum_decl.name, &enum_decl.span, ty)
+                }
+                _ => continue,
+            };
+            if self.type_names.contains_key(name) {
+                self.lctx.push(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("type `{name}` is declared multiple times").into(),
+                    cursor_msg: Some("consider renaming the type or the import".into()),
+                    location: span.clone(),
+                });
+                continue;
+            }
+            self.type_names.insert(name.clone(), ty.clone());
+            decls.push((decl, ty));
+        }
+
+        for (decl, ty) in &decls {
+            let def = match &decl.decl {
+                TopLvlDecl::Struct(struct_decl) => TypeDef::Struct(self.struct_def(struct_decl)),
+                TopLvlDecl::Enum(enum_decl) => TypeDef::Enum(self.enum_def(enum_decl)),
+                _ => unreachable!(),
+            };
+            let item = Item {
+                def,
+                public: decl.public,
+            };
+            self.results.types.insert(ty.clone(), item);
+        }
+
+        for (decl, ty) in &decls {
+            let field_tys: Vec<&AstType> = match &decl.decl {
+                TopLvlDecl::Struct(struct_decl) => {
+                    struct_decl.fields.iter().map(|field| &field.ty).collect()
+                }
+                TopLvlDecl::Enum(enum_decl) => enum_decl
+                    .variants
+                    .iter()
+                    .flat_map(|variant| &variant.fields)
+                    .collect(),
+                _ => unreachable!(),
+            };
+            if let Err(err) = self.check_size(ty, &field_tys) {
+                self.lctx.push_boxed(err);
+            }
+        }
+    }
+
+    /// Resolves the fields of a struct of the source file, the errors are
+    /// pushed to the log context and the invalid fields are left out.
+    fn struct_def(&mut self, decl: &StructDecl) -> StructDef {
+        let mut fields: Vec<FieldDef> = Vec::new();
+        for field in &decl.fields {
+            if fields.iter().any(|f| f.name == field.name) {
+                self.lctx.push(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("field `{}` is already declared", field.name).into(),
+                    cursor_msg: None,
+                    location: field.span.clone(),
+                });
+                continue;
+            }
+            let Some(ty) = self.resolve_value_ty_or_log(&field.ty) else {
+                continue;
+            };
+            fields.push(FieldDef {
+                name: field.name.clone(),
+                ty,
+                public: field.public,
+            });
+        }
+        StructDef { fields }
+    }
+
+    /// Resolves the representation and the variants of an enum of the source
+    /// file, the errors are pushed to the log context and the invalid variants
+    /// are left out.
+    ///
+    /// A variant without discriminant has the one of the previous variant plus
+    /// one, the first one has zero.
+    fn enum_def(&mut self, decl: &EnumDecl) -> EnumDef {
+        let repr = match decl.repr.as_ref().map(|repr| self.resolve_ty(repr)) {
+            None => Ty::DEFAULT_INT,
+            Some(Ok(ty)) if ty.is_int() => ty,
+            Some(Ok(ty)) => {
+                self.lctx.push(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("invalid representation `{ty}` for an enum").into(),
+                    cursor_msg: Some("expected an integer type, like `u8` or `i64`".into()),
+                    location: decl.repr.as_ref().unwrap().span.clone(),
+                });
+                Ty::DEFAULT_INT
+            }
+            Some(Err(err)) => {
+                self.lctx.push_boxed(err);
+                Ty::DEFAULT_INT
+            }
+        };
+
+        let mut variants: Vec<VariantDef> = Vec::new();
+        let mut next: i128 = 0;
+        for variant in &decl.variants {
+            let discriminant = match &variant.discriminant {
+                Some(expr) => self.check_discriminant(expr, &repr),
+                None => self
+                    .check_int_lit(
+                        next.unsigned_abs(),
+                        next < 0,
+                        None,
+                        Some(&repr),
+                        variant.span.clone(),
+                    )
+                    .map(|_| next),
+            };
+            let discriminant = match discriminant {
+                Ok(discriminant) => discriminant,
+                Err(err) => {
+                    self.lctx.push_boxed(err);
+                    continue;
+                }
+            };
+            next = discriminant.wrapping_add(1);
+
+            if variants.iter().any(|v| v.name == variant.name) {
+                self.lctx.push(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("variant `{}` is already declared", variant.name).into(),
+                    cursor_msg: None,
+                    location: variant.span.clone(),
+                });
+                continue;
+            }
+            if let Some(other) = variants.iter().find(|v| v.discriminant == discriminant) {
+                self.lctx.push(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("discriminant `{discriminant}` is used more than once").into(),
+                    cursor_msg: Some(format!("already used by `{}`", other.name).into()),
+                    location: variant.span.clone(),
+                });
+                continue;
+            }
+
+            let fields: Option<Vec<Ty>> = variant
+                .fields
+                .iter()
+                .map(|ty| self.resolve_value_ty_or_log(ty))
+                .collect();
+            let Some(fields) = fields else {
+                continue;
+            };
+            variants.push(VariantDef {
+                name: variant.name.clone(),
+                discriminant,
+                fields,
+            });
+        }
+        EnumDef { repr, variants }
+    }
+
+    /// Checks the explicit discriminant of a variant, an integer literal, maybe
+    /// negated, that fits in the representation of the enum.
+    fn check_discriminant(&self, expr: &Expression, repr: &Ty) -> CheckResult<i128> {
+        let (value, negative) = match &expr.expr {
+            Expr::IntLitExpr {
+                value,
+                suffix: None,
+                ..
+            } => (*value, false),
+            Expr::UnaryExpr {
+                op: UnaryOperation::Negation,
+                expr: inner,
+            } if matches!(inner.expr, Expr::IntLitExpr { suffix: None, .. }) => {
+                let Expr::IntLitExpr { value, .. } = inner.expr else {
+                    unreachable!()
+                };
+                (value, true)
+            }
+            _ => {
+                return Err(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: "discriminant of a variant must be an integer literal".into(),
+                    cursor_msg: Some(format!("expected a `{repr}` literal").into()),
+                    location: expr.span.clone(),
+                }))
+            }
+        };
+        self.check_int_lit(value, negative, None, Some(repr), expr.span.clone())?;
+
+        // a `u128` bigger than `i128::MAX` wraps, it keeps the bits of the tag
+        let discriminant = value as i128;
+        Ok(if negative {
+            discriminant.wrapping_neg()
+        } else {
+            discriminant
+        })
+    }
+
+    /// Checks the length of an array type, an integer literal that fits in a
+    /// `usize`.
+    fn check_array_len(&self, len: &Expression) -> CheckResult<u64> {
+        let Expr::IntLitExpr { value, suffix, .. } = &len.expr else {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: "length of an array must be an integer literal".into(),
+                cursor_msg: Some(format!("expected a `{}` literal", Ty::USIZE).into()),
+                location: len.span.clone(),
+            }));
+        };
+        let ty = self.check_int_lit(*value, false, *suffix, Some(&Ty::USIZE), len.span.clone())?;
+        self.check_ty(&ty, &Ty::USIZE, len.span.clone())?;
+        Ok(*value as u64)
+    }
+
+    /// Resolves the type of a field or of a payload, the error is pushed to the
+    /// log context.
+    fn resolve_value_ty_or_log(&mut self, ty: &AstType) -> Option<Ty> {
+        let resolved = self.resolve_ty(ty).and_then(|resolved| {
+            self.check_value_ty(&resolved, ty.span.clone())?;
+            Ok(resolved)
+        });
+        match resolved {
+            Ok(resolved) => Some(resolved),
+            Err(err) => {
+                self.lctx.push_boxed(err);
+                None
+            }
+        }
+    }
+
+    /// Returns an error if the type contains itself, directly or in the fields
+    /// of another type, its size would be infinite.
+    fn check_size(&self, ty: &Ty, field_tys: &[&AstType]) -> CheckResult<()> {
+        for field_ty in field_tys {
+            let Some(resolved) = self.results.tys.get(&field_ty.span) else {
+                continue;
+            };
+            if self.contains_ty(resolved, ty, &mut HashSet::new()) {
+                return Err(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("recursive type `{ty}` has an infinite size").into(),
+                    cursor_msg: Some(format!("consider a pointer, like `*{resolved}`").into()),
+                    location: field_ty.span.clone(),
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Is the type `target` the type `ty`, or in the fields or the payloads of
+    /// `ty`, or its elements if it's an array?
+    fn contains_ty(&self, ty: &Ty, target: &Ty, visited: &mut HashSet<Ty>) -> bool {
+        if ty == target {
+            return true;
+        }
+        if let Ty::Array { elem, .. } = ty {
+            return self.contains_ty(elem, target, visited);
+        }
+        if !visited.insert(ty.clone()) {
+            return false;
+        }
+        match self.results.types.get(ty).map(|item| &item.def) {
+            Some(TypeDef::Struct(def)) => def
+                .fields
+                .iter()
+                .any(|field| self.contains_ty(&field.ty, target, visited)),
+            Some(TypeDef::Enum(def)) => def
+                .variants
+                .iter()
+                .flat_map(|variant| &variant.fields)
+                .any(|field_ty| self.contains_ty(field_ty, target, visited)),
+            None => false,
+        }
+    }
+
+    /// Resolves a type written in the source file, it's recorded for the code
+    /// generation.
+    fn resolve_ty(&mut self, ty: &AstType) -> CheckResult<Ty> {
+        let resolved = match &ty.ty {
+            AstTy::PrimTy(prim) => Ty::Prim(*prim),
+            AstTy::PointerTy {
+                is_const,
+                pointed_ty,
+            } => Ty::Pointer {
+                is_const: *is_const,
+                pointee: Box::new(self.resolve_ty(pointed_ty)?),
+            },
+            AstTy::ArrayTy { len, elem_ty } => {
+                let elem = self.resolve_ty(elem_ty)?;
+                self.check_value_ty(&elem, elem_ty.span.clone())?;
+                Ty::Array {
+                    elem: Box::new(elem),
+                    len: self.check_array_len(len)?,
+                }
+            }
+            AstTy::SliceTy { is_const, elem_ty } => {
+                let elem = self.resolve_ty(elem_ty)?;
+                self.check_value_ty(&elem, elem_ty.span.clone())?;
+                Ty::Slice {
+                    is_const: *is_const,
+                    elem: Box::new(elem),
+                }
+            }
+            AstTy::NamedTy {
+                package: None,
+                name,
+            } => self
+                .type_names
+                .get(name)
+                .cloned()
+                .ok_or_else(|| -> Box<dyn Log> {
+                    Box::new(Undeclared {
+                        kind: "type",
+                        name: name.clone(),
+                        location: ty.span.clone(),
+                    })
+                })?,
+            AstTy::NamedTy {
+                package: Some(package),
+                name,
+            } => {
+                let Some(import) = self
+                    .results
+                    .imports
+                    .iter()
+                    .find(|import| import.item.is_none() && import.name == *package)
+                else {
+                    return Err(Box::new(Undeclared {
+                        kind: "package",
+                        name: package.clone(),
+                        location: ty.span.clone(),
+                    }));
+                };
+                self.imported_ty(&import.interface, name, ty.span.clone())?
+            }
+        };
+        self.results.tys.insert(ty.span.clone(), resolved.clone());
+        Ok(resolved)
+    }
+
+    /// Resolves the types of the signature of a function.
+    fn fn_sig(&mut self, proto: &Prototype) -> CheckResult<FnSig> {
+        let mut args = Vec::with_capacity(proto.args.len());
+        for arg in &proto.args {
+            args.push(self.resolve_ty(&arg.ty)?);
+        }
+        Ok(FnSig {
+            args,
+            ret_ty: self.resolve_ty(&proto.ret_ty)?,
+        })
+    }
+
+    /// Returns the struct or the enum of an imported package.
+    fn imported_ty(&self, interface: &Interface, name: &str, span: CodeSpan) -> CheckResult<Ty> {
+        let Some(ty) = interface.named_ty(name) else {
+            return Err(Box::new(Undeclared {
+                kind: "type",
+                name: format!("{}.{}", interface.package, name),
+                location: span,
+            }));
+        };
+        if !interface.types[&ty].public {
+            return Err(Box::new(PrivateItem {
+                kind: if ty.is_struct() { "struct" } else { "enum" },
+                name: name.to_owned(),
+                package: interface.package.clone(),
+                location: span,
+            }));
+        }
+        Ok(ty)
+    }
+
+    /// Returns the type of the field of a value of type `ty`, the span is the
+    /// one of the name of the field.
+    fn field_ty(&self, ty: &Ty, name: &str, span: CodeSpan) -> CheckResult<Ty> {
+        let field = match self.results.types.get(ty).map(|item| &item.def) {
+            Some(TypeDef::Struct(def)) => def.field(name),
+            _ => None,
+        };
+        let Some((_, field)) = field else {
+            return Err(Box::new(NoField {
+                ty: ty.clone(),
+                name: name.to_owned(),
+                location: span,
+            }));
+        };
+        match ty {
+            Ty::Struct { package, .. } if !field.public && *package != self.package => {
+                Err(Box::new(PrivateItem {
+                    kind: "field",
+                    name: name.to_owned(),
+                    package: package.clone(),
+                    location: span,
+                }))
+            }
+            _ => Ok(field.ty.clone()),
+        }
+    }
+
+    /// Returns the import, and the name of the item, if the expression is an
+    /// item of an imported package, like `fmt.println`. A variable hides an
+    /// imported package with the same name.
+    fn package_item<'e>(&self, expr: &'e Expression) -> Option<(usize, &'e str)> {
+        let Expr::MemberAccessExpr {
+            expr: package,
+            member_name,
+        } = &expr.expr
+        else {
+            return None;
+        };
+        let Expr::IdentifierExpr(name) = &package.expr else {
+            return None;
+        };
+        if self.get_variable(name, package.span.clone()).is_ok() {
+            return None;
+        }
+
+        let import = self
+            .results
+            .imports
+            .iter()
+            .position(|import| import.item.is_none() && import.name == *name)?;
+        Some((import, member_name))
+    }
+
+    /// Returns the enum and the index of the variant if the expression is a
+    /// variant, like `Color.Red` or `geo.Color.Red`. A variable hides an enum
+    /// with the same name.
+    fn enum_variant(&mut self, expr: &Expression) -> CheckResult<Option<(Ty, usize)>> {
+        let Expr::MemberAccessExpr {
+            expr: base,
+            member_name,
+        } = &expr.expr
+        else {
+            return Ok(None);
+        };
+        let Some(ty @ Ty::Enum { .. }) = self.type_path(base)? else {
+            return Ok(None);
+        };
+
+        let Some((index, _)) = self.results.enum_def(&ty).variant(member_name) else {
+            return Err(Box::new(NoVariant {
+                ty,
+                name: member_name.clone(),
+                location: member_span(expr, member_name),
+            }));
+        };
+        self.results
+            .variants
+            .insert(expr.span.clone(), (ty.clone(), index));
+        Ok(Some((ty, index)))
+    }
+
+    /// Returns the struct or the enum if the expression names one, like `Point`
+    /// or `geo.Point`. A variable hides a type with the same name.
+    fn type_path(&self, expr: &Expression) -> CheckResult<Option<Ty>> {
+        match &expr.expr {
+            Expr::IdentifierExpr(name) if self.get_variable(name, expr.span.clone()).is_err() => {
+                Ok(self.type_names.get(name).cloned())
+            }
+            Expr::MemberAccessExpr {
+                member_name: name, ..
+            } => match self.package_item(expr) {
+                Some((import, _)) => {
+                    let interface = &self.results.imports[import].interface;
+                    match interface.named_ty(name) {
+                        Some(_) => Ok(Some(self.imported_ty(
+                            interface,
+                            name,
+                            expr.span.clone(),
+                        )?)),
+                        None => Ok(None),
+                    }
+                }
+                None => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the type and the name of the function if the expression is a
+    /// function of an `impl` block named by its path, like `Point.new`.
+    fn assoc_fn(&self, expr: &Expression) -> CheckResult<Option<(Ty, MethodDef)>> {
+        let Expr::MemberAccessExpr {
+            expr: base,
+            member_name,
+        } = &expr.expr
+        else {
+            return Ok(None);
+        };
+        let Some(ty) = self.type_path(base)? else {
+            return Ok(None);
+        };
+        // a variant can't have the name of a function
+        if ty.is_enum() && self.results.enum_def(&ty).variant(member_name).is_some() {
+            return Ok(None);
+        }
+        let def = self.method(&ty, member_name, member_span(expr, member_name))?;
+        Ok(Some((ty, def)))
+    }
+
+    /// Returns the function of an `impl` block of the type, the span is the
+    /// one of its name.
+    fn method(&self, ty: &Ty, name: &str, span: CodeSpan) -> CheckResult<MethodDef> {
+        let Some(item) = self.results.methods.get(ty).and_then(|fns| fns.get(name)) else {
+            return Err(Box::new(NoMethod {
+                ty: ty.clone(),
+                name: name.to_owned(),
+                location: span,
+            }));
+        };
+        match ty {
+            Ty::Struct { package, .. } | Ty::Enum { package, .. }
+                if !item.public && *package != self.package =>
+            {
+                Err(Box::new(PrivateItem {
+                    kind: "function",
+                    name: format!("{ty}.{name}"),
+                    package: package.clone(),
+                    location: span,
+                }))
+            }
+            _ => Ok(item.def.clone()),
+        }
+    }
+
+    /// Returns the global if the expression is a global of an imported package,
+    /// like `math.pi`.
+    fn package_global(&mut self, expr: &Expression) -> CheckResult<Option<Var>> {
+        let Some((import, item)) = self.package_item(expr) else {
+            return Ok(None);
+        };
+        let var = self.imported_global(&self.results.imports[import], item, expr.span.clone())?;
+        self.results.package_items.insert(expr.span.clone(), import);
+        Ok(Some(var))
+    }
+
+    /// Returns the signature of a function of an imported package.
+    fn imported_function(
+        &self,
+        import: &ResolvedImport,
+        name: &str,
+        span: CodeSpan,
+    ) -> CheckResult<FnSig> {
+        let interface = &import.interface;
+        let Some(item) = interface.functions.get(name) else {
+            return Err(Box::new(Undeclared {
+                kind: "function",
+                name: format!("{}.{}", interface.package, name),
+                location: span,
+            }));
+        };
+        if !item.public {
+            return Err(Box::new(PrivateItem {
+                kind: "function",
+                name: name.to_owned(),
+                package: interface.package.clone(),
+                location: span,
+            }));
+        }
+        Ok(item.def.clone())
+    }
+
+    /// Returns a global of an imported package.
+    fn imported_global(
+        &self,
+        import: &ResolvedImport,
+        name: &str,
+        span: CodeSpan,
+    ) -> CheckResult<Var> {
+        let interface = &import.interface;
+        let Some(item) = interface.globals.get(name) else {
+            return Err(Box::new(Undeclared {
+                kind: "item",
+                name: format!("{}.{}", interface.package, name),
+                location: span,
+            }));
+        };
+        if !item.public {
+            return Err(Box::new(PrivateItem {
+                kind: "global",
+                name: name.to_owned(),
+                package: interface.package.clone(),
+                location: span,
+            }));
+        }
+        Ok(item.def.clone())
+    }
+
+    /// Records the type of the expression and returns it.
+    fn record(&mut self, expr: &Expression, ty: Ty) -> Ty {
+        self.results.expr_tys.insert(expr.span.clone(), ty.clone());
+        ty
+    }
+
+    /// Returns an error if a value of type `found` cannot be used where a value
+    /// of type `expected` is.
+    fn check_ty(&self, found: &Ty, expected: &Ty, span: CodeSpan) -> CheckResult<()> {
+        if found.coerces_to(expected) {
+            Ok(())
+        } else {
+            Err(Box::new(MismatchedTypes {
+                expected: expected.clone(),
+                found: found.clone(),
+                location: span,
+            }))
+        }
+    }
+
+    /// Returns an error if the type is `void`, because it's the type of a value.
+    fn check_value_ty(&self, ty: &Ty, span: CodeSpan) -> CheckResult<()> {
+        if ty.is_void() {
+            Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: "`void` cannot be the type of a value".into(),
+                cursor_msg: None,
+                location: span,
+            }))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Declares a new local variable in the innermost scope.
+    fn declare_local(
+        &mut self,
+        name: &str,
+        ty: Ty,
+        mutable: bool,
+        span: CodeSpan,
+    ) -> CheckResult<()> {
+        if self.scopes.last().unwrap().contains_key(name) {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("`{name}` is already declared in this scope").into(),
+                cursor_msg: None,
+                location: span,
+            }));
+        }
+        self.check_value_ty(&ty, span)?;
+
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_owned(), Var { ty, mutable });
+        Ok(())
+    }
+
+    /// Looks for a local variable and then for a global one.
+    fn get_variable(&self, name: &str, span: CodeSpan) -> CheckResult<Var> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .or_else(|| self.globals.get(name))
+            .cloned()
+            .ok_or_else(|| -> Box<dyn Log> {
+                Box::new(Undeclared {
+                    kind: "variable",
+                    name: name.to_owned(),
+                    location: span,
+                })
+            })
+    }
+
+    /// Declares the function with its signature.
+    fn declare_function(&mut self, proto: &Prototype, has_body: bool) -> CheckResult<FnSig> {
+        if let Some(receiver) = &proto.receiver {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: "`self` receiver outside of an `impl` block".into(),
+                cursor_msg: Some("only the functions of an `impl` block can be methods".into()),
+                location: receiver.span.clone(),
+            }));
+        }
+        if self.functions.contains_key(&proto.name) {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("function `{}` is defined multiple times", proto.name).into(),
+                cursor_msg: None,
+                location: proto.span.clone(),
+            }));
+        }
+
+        let sig = self.fn_sig(proto)?;
+        for (arg, ty) in proto.args.iter().zip(&sig.args) {
+            self.check_value_ty(ty, arg.ty.span.clone())?;
+        }
+
+        // an invalid `main` is still declared, so its body is checked
+        if proto.name == "main"
+            && has_body
+            && !((sig.args.is_empty() || sig.is_main_with_args())
+                && (sig.ret_ty.is_void() || sig.ret_ty.is_int()))
+        {
+            self.lctx.push(SimpleLog {
+                level: LogLevel::Error,
+                msg: "invalid signature for the `main` function".into(),
+                cursor_msg: Some(
+                    "expected `fn main()` or `fn main(argc: i32, argv: **u8)` returning `void` or an integer"
+                        .into(),
+                ),
+                location: proto.span.clone(),
+            });
+        }
+
+        self.functions.insert(proto.name.clone(), sig.clone());
+        Ok(sig)
+    }
+
+    /// Declares the functions of an `impl` block, the valid ones are returned
+    /// with their signature, so their body can be checked.
+    fn declare_impl<'d>(&mut self, public: bool, decl: &'d ImplDecl) -> Vec<(FnSig, &'d ImplFn)> {
+        if public {
+            self.lctx.push(SimpleLog {
+                level: LogLevel::Error,
+                msg: "an `impl` block can't be `pub`".into(),
+                cursor_msg: Some("consider making its functions `pub` instead".into()),
+                location: decl.span.clone(),
+            });
+        }
+        let ty = match self.resolve_ty(&decl.ty) {
+            Ok(ty) => ty,
+            Err(err) => {
+                self.lctx.push_boxed(err);
+                return Vec::new();
+            }
+        };
+        match &ty {
+            Ty::Struct { package, .. } | Ty::Enum { package, .. } if *package == self.package => {}
+            _ => {
+                let reason = if ty.is_struct() || ty.is_enum() {
+                    "it's declared in another package"
+                } else {
+                    "only structs and enums can have one"
+                };
+                self.lctx.push(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("cannot define an `impl` block for `{ty}`").into(),
+                    cursor_msg: Some(reason.into()),
+                    location: decl.ty.span.clone(),
+                });
+                return Vec::new();
+            }
+        }
+
+        let mut fns = Vec::new();
+        for f in &decl.fns {
+            match self.declare_method(&ty, f) {
+                Ok(sig) => fns.push((sig, f)),
+                Err(err) => self.lctx.push_boxed(err),
+            }
+        }
+        fns
+    }
+
+    /// Declares a function of an `impl` block of the type, returns its
+    /// signature, with the receiver as first argument.
+    fn declare_method(&mut self, ty: &Ty, f: &ImplFn) -> CheckResult<FnSig> {
+        let proto = &f.proto;
+        let defined = self.results.methods.get(ty);
+        if defined.is_some_and(|fns| fns.contains_key(&proto.name)) {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("function `{ty}.{}` is defined multiple times", proto.name).into(),
+                cursor_msg: None,
+                location: proto.span.clone(),
+            }));
+        }
+        if ty.is_enum() && self.results.enum_def(ty).variant(&proto.name).is_some() {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("`{ty}.{}` is already a variant", proto.name).into(),
+                cursor_msg: Some("consider renaming the function".into()),
+                location: proto.span.clone(),
+            }));
+        }
+
+        let mut sig = self.fn_sig(proto)?;
+        for (arg, ty) in proto.args.iter().zip(&sig.args) {
+            self.check_value_ty(ty, arg.ty.span.clone())?;
+        }
+        let receiver = proto.receiver.as_ref().map(|receiver| receiver.kind);
+        if let Some(kind) = receiver {
+            let self_ty = match kind {
+                ReceiverKind::Value => ty.clone(),
+                ReceiverKind::Pointer | ReceiverKind::ConstPointer => Ty::Pointer {
+                    is_const: kind == ReceiverKind::ConstPointer,
+                    pointee: Box::new(ty.clone()),
+                },
+            };
+            sig.args.insert(0, self_ty);
+        }
+
+        let item = Item {
+            def: MethodDef {
+                sig: sig.clone(),
+                receiver,
+            },
+            public: f.public,
+        };
+        self.results
+            .methods
+            .entry(ty.clone())
+            .or_default()
+            .insert(proto.name.clone(), item);
+        Ok(sig)
+    }
+
+    /// Checks the body of an already declared function, of signature `sig`.
+    fn check_fn_body(&mut self, sig: FnSig, proto: &Prototype, body: &Block) {
+        self.ret_ty = sig.ret_ty.clone();
+
+        self.scopes.push(HashMap::new());
+        let receiver = proto.receiver.as_ref().map(|receiver| &receiver.span);
+        let spans = receiver
+            .into_iter()
+            .chain(proto.args.iter().map(|arg| &arg.span));
+        for ((name, span), ty) in proto.arg_names().zip(spans).zip(sig.args) {
+            if let Err(err) = self.declare_local(name, ty, true, span.clone()) {
+                self.lctx.push_boxed(err);
+            }
+        }
+
+        let falls_through = self.check_block(body);
+        self.scopes.pop();
+
+        if falls_through && !sig.ret_ty.is_void() {
+            self.lctx.push(MissingReturn {
+                name: proto.name.clone(),
+                ret_ty: sig.ret_ty,
+                location: proto.ret_ty.span.clone(),
+            });
+        }
+    }
+
+    /// Checks a global variable, its initializer must be a constant.
+    fn check_global(&mut self, decl: &VarDecl) -> CheckResult<()> {
+        if self.globals.contains_key(&decl.name) {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("global `{}` is defined multiple times", decl.name).into(),
+                cursor_msg: None,
+                location: decl.span.clone(),
+            }));
+        }
+        let declared = decl.ty.as_ref().map(|ty| self.resolve_ty(ty)).transpose()?;
+
+        let value_ty = match &decl.expr {
+            Some(expr) if !is_literal(expr) => {
+                return Err(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: "initializer of a global must be a constant".into(),
+                    cursor_msg: Some("only literals are supported".into()),
+                    location: expr.span.clone(),
+                }));
+            }
+            Some(expr) => {
+                let ty = self.check_expr(expr, declared.as_ref())?;
+                if let Some(declared) = &declared {
+                    self.check_ty(&ty, declared, expr.span.clone())?;
+                }
+                Some(ty)
+            }
+            None => None,
+        };
+
+        let ty = self.var_ty(decl, declared, value_ty)?;
+        self.check_value_ty(&ty, decl.span.clone())?;
+
+        self.globals.insert(
+            decl.name.clone(),
+            Var {
+                ty,
+                mutable: matches!(decl.var_type, VarType::VariableVar),
+            },
+        );
+        Ok(())
+    }
+
+    /// Determines the type of a variable, given the declared type and the
+    /// type of the initializer.
+    fn var_ty(
+        &self,
+        decl: &VarDecl,
+        declared: Option<Ty>,
+        value_ty: Option<Ty>,
+    ) -> CheckResult<Ty> {
+        if value_ty.is_none() && matches!(decl.var_type, VarType::ConstVar) {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("constant `{}` must be initialized", decl.name).into(),
+                cursor_msg: None,
+                location: decl.span.clone(),
+            }));
+        }
+        match (declared, value_ty) {
+            (Some(ty), _) | (None, Some(ty)) => Ok(ty),
+            (None, None) => Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: "type annotations needed".into(),
+                cursor_msg: Some(format!("consider giving `{}` a type", decl.name).into()),
+                location: decl.span.clone(),
+            })),
+        }
+    }
+
+    /// Checks every statement of the block in a new scope, errors are pushed
+    /// to the log context so every statement is checked.
+    ///
+    /// Returns true if the end of the block can be reached.
+    fn check_block(&mut self, block: &Block) -> bool {
+        self.scopes.push(HashMap::new());
+        let mut reachable = true;
+        for stmt in &block.stmts {
+            // the statements after a return, break or continue are unreachable
+            // but are still checked.
+            reachable &= self.check_stmt_or_log(stmt);
+        }
+        self.scopes.pop();
+        reachable
+    }
+
+    /// Checks a statement nested in another one, like the body of an if
+    /// statement, in its own scope.
+    fn check_nested_stmt(&mut self, stmt: &Statement) -> bool {
+        self.scopes.push(HashMap::new());
+        let falls_through = self.check_stmt_or_log(stmt);
+        self.scopes.pop();
+        falls_through
+    }
+
+    /// Checks the statement and pushes the error, if any, to the log context.
+    ///
+    /// A statement with an error is considered to never complete, so a failed
+    /// `return` doesn't also report that the function may end without
+    /// returning a value.
+    fn check_stmt_or_log(&mut self, stmt: &Statement) -> bool {
+        match self.check_stmt(stmt) {
+            Ok(falls_through) => falls_through,
+            Err(err) => {
+                self.lctx.push_boxed(err);
+                false
+            }
+        }
+    }
+
+    /// Checks a statement, returns true if the execution can continue after it.
+    fn check_stmt(&mut self, stmt: &Statement) -> CheckResult<bool> {
+        let span = stmt.span.clone();
+        match &stmt.stmt {
+            Stmt::ExprStmt(expr) => {
+                match &expr.expr {
+                    // a call statement may call a function returning void.
+                    Expr::CallExpr { fn_op, args } => {
+                        let ty = self.check_call(fn_op, args, expr.span.clone())?;
+                        self.record(expr, ty);
+                    }
+                    // so does a match statement, and it may never complete
+                    Expr::MatchExpr { scrutinee, arms } => {
+                        let (ty, falls_through) = self.check_match(scrutinee, arms, None)?;
+                        self.record(expr, ty);
+                        return Ok(falls_through);
+                    }
+                    _ => {
+                        self.check_expr(expr, None)?;
+                    }
+                }
+                Ok(true)
+            }
+            Stmt::IfElseStmt {
+                predicate,
+                stmt_true,
+                stmt_false,
+            } => {
+                self.check_condition(predicate)?;
+                let true_falls = self.check_nested_stmt(stmt_true);
+                let false_falls = match stmt_false {
+                    Some(stmt_false) => self.check_nested_stmt(stmt_false),
+                    None => true,
+                };
+                Ok(true_falls || false_falls)
+            }
+            Stmt::BlockStmt { label: None, block } => Ok(self.check_block(block)),
+            Stmt::BlockStmt {
+                label: Some(label),
+                block,
+            } => {
+                self.jump_targets.push(JumpTarget {
+                    label: Some(label.clone()),
+                    is_loop: false,
+                    broken: false,
+                });
+                let falls_through = self.check_block(block);
+                let target = self.jump_targets.pop().unwrap();
+                Ok(falls_through || target.broken)
+            }
+            Stmt::ReturnStmt(expr) => {
+                self.check_return_stmt(expr.as_ref(), span)?;
+                Ok(false)
+            }
+            Stmt::WhileStmt {
+                label,
+                ctrling_expr,
+                loop_body,
+            } => {
+                self.check_condition(ctrling_expr)?;
+                self.jump_targets.push(JumpTarget {
+                    label: label.clone(),
+                    is_loop: true,
+                    broken: false,
+                });
+                self.check_block(loop_body);
+                let target = self.jump_targets.pop().unwrap();
+
+                // `while (true)` only ends with a break
+                Ok(!is_true_lit(ctrling_expr) || target.broken)
+            }
+            Stmt::ForStmt {
+                label,
+                index,
+                var,
+                iter,
+                loop_body,
+            } => {
+                let ty = match iter {
+                    ForIter::Range(range) => {
+                        if index.is_some() {
+                            return Err(Box::new(SimpleLog {
+                                level: LogLevel::Error,
+                                msg: "`for` loop over a range with an index".into(),
+                                cursor_msg: Some(
+                                    "only the elements of arrays and slices have an index".into(),
+                                ),
+                                location: range.start.span.start..range.end.span.end,
+                            }));
+                        }
+                        self.check_for_range(range)?
+                    }
+                    ForIter::Elems(expr) => self.check_for_elems(expr)?,
+                };
+                if index.as_ref() == Some(var) {
+                    return Err(Box::new(SimpleLog {
+                        level: LogLevel::Error,
+                        msg: format!("`{var}` is bound more than once in the `for` loop").into(),
+                        cursor_msg: None,
+                        location: span,
+                    }));
+                }
+                self.jump_targets.push(JumpTarget {
+                    label: label.clone(),
+                    is_loop: true,
+                    broken: false,
+                });
+                // the variables are constants, in a scope around the body
+                let mut scope = HashMap::from([(var.clone(), Var { ty, mutable: false })]);
+                if let Some(index) = index {
+                    let ty = Ty::USIZE;
+                    scope.insert(index.clone(), Var { ty, mutable: false });
+                }
+                self.scopes.push(scope);
+                self.check_block(loop_body);
+                self.scopes.pop();
+                self.jump_targets.pop();
+                Ok(true)
+            }
+            Stmt::BreakStmt { label, expr } => {
+                if let Some(expr) = expr {
+                    return Err(Box::new(Unsupported {
+                        what: "`break` with a value".to_owned(),
+                        location: expr.span.clone(),
+                    }));
+                }
+                self.check_jump(label.as_deref(), false, span)?;
+                Ok(false)
+            }
+            Stmt::ContinueStmt { label } => {
+                self.check_jump(label.as_deref(), true, span)?;
+                Ok(false)
+            }
+            Stmt::AssignementStmt { lhs, rhs } => {
+                self.check_assignement_stmt(lhs, rhs, span)?;
+                Ok(true)
+            }
+            Stmt::CompoundAssignStmt { place, op, value } => {
+                self.check_compound_assign_stmt(place, op, value, span)?;
+                Ok(true)
+            }
+            Stmt::ShortVarDeclStmt { names, exprs } => {
+                self.check_short_var_decl(names, exprs, span)?;
+                Ok(true)
+            }
+            Stmt::VariableDeclStmt(decl) => {
+                self.check_var_decl_stmt(decl)?;
+                Ok(true)
+            }
+        }
+    }
+
+    fn check_return_stmt(&mut self, expr: Option<&Expression>, span: CodeSpan) -> CheckResult<()> {
+        let ret_ty = self.ret_ty.clone();
+        match expr {
+            Some(expr) => {
+                let ty = self.check_expr(expr, Some(&ret_ty))?;
+                self.check_ty(&ty, &ret_ty, expr.span.clone())
+            }
+            None if ret_ty.is_void() => Ok(()),
+            None => Err(Box::new(MismatchedTypes {
+                expected: ret_ty,
+                found: Ty::VOID,
+                location: span,
+            })),
+        }
+    }
+
+    /// Checks that a `break` or a `continue` has a target.
+    ///
+    /// Without a label, the innermost loop is the target. With a label, it's
+    /// the loop or the block with this label.
+    fn check_jump(
+        &mut self,
+        label: Option<&str>,
+        is_continue: bool,
+        span: CodeSpan,
+    ) -> CheckResult<()> {
+        let keyword = if is_continue { "continue" } else { "break" };
+        let target = match label {
+            Some(label) => self
+                .jump_targets
+                .iter_mut()
+                .rev()
+                .find(|target| target.label.as_deref() == Some(label))
+                .ok_or_else(|| -> Box<dyn Log> {
+                    Box::new(Undeclared {
+                        kind: "label",
+                        name: label.to_owned(),
+                        location: span.clone(),
+                    })
+                })?,
+            None => self
+                .jump_targets
+                .iter_mut()
+                .rev()
+                .find(|target| target.is_loop)
+                .ok_or_else(|| -> Box<dyn Log> {
+                    Box::new(SimpleLog {
+                        level: LogLevel::Error,
+                        msg: format!("`{keyword}` outside of a loop").into(),
+                        cursor_msg: None,
+                        location: span.clone(),
+                    })
+                })?,
+        };
+
+        if !is_continue {
+            target.broken = true;
+        } else if !target.is_loop {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: "`continue` can only target a loop, not a labeled block".into(),
+                cursor_msg: None,
+                location: span,
+            }));
+        }
+        Ok(())
+    }
+
+    fn check_assignement_stmt(
+        &mut self,
+        lhs: &ExpressionList,
+        rhs: &ExpressionList,
+        span: CodeSpan,
+    ) -> CheckResult<()> {
+        if lhs.0.len() != rhs.0.len() {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!(
+                    "assignment mismatch: {} place(s) but {} value(s)",
+                    lhs.0.len(),
+                    rhs.0.len()
+                )
+                .into(),
+                cursor_msg: None,
+                location: span,
+            }));
+        }
+
+        for (place_expr, expr) in lhs.0.iter().zip(&rhs.0) {
+            let place = self.check_place(place_expr)?;
+            if !place.mutable {
+                return Err(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: "cannot assign to a constant".into(),
+                    cursor_msg: Some("cannot be assigned".into()),
+                    location: place_expr.span.clone(),
+                }));
+            }
+            let ty = self.check_expr(expr, Some(&place.ty))?;
+            self.check_ty(&ty, &place.ty, expr.span.clone())?;
+        }
+        Ok(())
+    }
+
+    fn check_compound_assign_stmt(
+        &mut self,
+        place_expr: &Expression,
+        op: &BinOperation,
+        value: &Expression,
+        span: CodeSpan,
+    ) -> CheckResult<()> {
+        let place = self.check_place(place_expr)?;
+        if !place.mutable {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: "cannot assign to a constant".into(),
+                cursor_msg: Some("cannot be assigned".into()),
+                location: place_expr.span.clone(),
+            }));
+        }
+        let ty = self.check_expr(value, Some(&place.ty))?;
+        self.check_ty(&ty, &place.ty, value.span.clone())?;
+
+        if !is_valid_operand(op, &place.ty) {
+            return Err(Box::new(InvalidOperand {
+                op: format!("{op}="),
+                ty: place.ty,
+                location: span,
+            }));
+        }
+        Ok(())
+    }
+
+    fn check_short_var_decl(
+        &mut self,
+        names: &[String],
+        exprs: &[Expression],
+        span: CodeSpan,
+    ) -> CheckResult<()> {
+        if names.len() != exprs.len() {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!(
+                    "assignment mismatch: {} variable(s) but {} value(s)",
+                    names.len(),
+                    exprs.len()
+                )
+                .into(),
+                cursor_msg: None,
+                location: span,
+            }));
+        }
+
+        // every value is checked before the variables are declared, like they
+        // are computed before any store
+        let mut tys = Vec::with_capacity(exprs.len());
+        for expr in exprs {
+            tys.push(self.check_expr(expr, None)?);
+        }
+
+        for (name, ty) in names.iter().zip(tys) {
+            self.declare_local(name, ty, true, span.clone())?;
+        }
+        Ok(())
+    }
+
+    fn check_var_decl_stmt(&mut self, decl: &VarDecl) -> CheckResult<()> {
+        let declared = decl.ty.as_ref().map(|ty| self.resolve_ty(ty)).transpose()?;
+
+        let value_ty = match &decl.expr {
+            Some(expr) => {
+                let ty = self.check_expr(expr, declared.as_ref())?;
+                if let Some(declared) = &declared {
+                    self.check_ty(&ty, declared, expr.span.clone())?;
+                }
+                Some(ty)
+            }
+            None => None,
+        };
+
+        let ty = self.var_ty(decl, declared, value_ty)?;
+        let mutable = matches!(decl.var_type, VarType::VariableVar);
+        self.declare_local(&decl.name, ty, mutable, decl.span.clone())
+    }
+
+    /// Checks the range of a `for` loop and its step, returns the type of the
+    /// values of the range.
+    fn check_for_range(&mut self, range: &ForRange) -> CheckResult<Ty> {
+        let ForRange {
+            start, end, step, ..
+        } = range;
+        // an integer literal takes the type of the other bound
+        let ty = if is_untyped_lit(start) && !is_untyped_lit(end) {
+            let ty = self.check_expr(end, None)?;
+            let start_ty = self.check_expr(start, Some(&ty))?;
+            self.check_ty(&start_ty, &ty, start.span.clone())?;
+            ty
+        } else {
+            let ty = self.check_expr(start, None)?;
+            let end_ty = self.check_expr(end, Some(&ty))?;
+            self.check_ty(&end_ty, &ty, end.span.clone())?;
+            ty
+        };
+        if !ty.is_int() {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("`for` loop over a range of `{ty}`").into(),
+                cursor_msg: Some("only ranges of integers can be iterated".into()),
+                location: start.span.start..end.span.end,
+            }));
+        }
+
+        if let Some(step) = step {
+            let step_ty = self.check_expr(step, Some(&ty))?;
+            self.check_ty(&step_ty, &ty, step.span.clone())?;
+
+            let positive = match &step.expr {
+                Expr::IntLitExpr { value, .. } => *value != 0,
+                Expr::UnaryExpr {
+                    op: UnaryOperation::Negation,
+                    expr,
+                } => !matches!(expr.expr, Expr::IntLitExpr { .. }),
+                _ => true,
+            };
+            if !positive {
+                return Err(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: "the step of a range must be positive".into(),
+                    cursor_msg: None,
+                    location: step.span.clone(),
+                }));
+            }
+        }
+        Ok(ty)
+    }
+
+    /// Checks the array or the slice iterated by a `for` loop, returns the
+    /// type of its elements.
+    fn check_for_elems(&mut self, expr: &Expression) -> CheckResult<Ty> {
+        let ty = self.check_expr(expr, None)?;
+        match ty.elem() {
+            Some(elem) => Ok(elem.clone()),
+            None => Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("`for` loop over a value of `{ty}`").into(),
+                cursor_msg: Some(
+                    "only ranges of integers, arrays and slices can be iterated".into(),
+                ),
+                location: expr.span.clone(),
+            })),
+        }
+    }
+
+    /// Checks an expression that must be a `bool`, used as a condition.
+    fn check_condition(&mut self, expr: &Expression) -> CheckResult<()> {
+        let ty = self.check_expr(expr, Some(&Ty::BOOL))?;
+        self.check_ty(&ty, &Ty::BOOL, expr.span.clone())
+    }
+
+    /// Checks an expression that refers to a location in memory.
+    fn check_place(&mut self, expr: &Expression) -> CheckResult<Var> {
+        if self.enum_variant(expr)?.is_some() {
+            return Err(invalid_place(expr));
+        }
+        let place = match &expr.expr {
+            Expr::IdentifierExpr(name) => self.get_variable(name, expr.span.clone())?,
+            Expr::MemberAccessExpr { .. } if self.package_item(expr).is_some() => {
+                self.package_global(expr)?.unwrap()
+            }
+            Expr::MemberAccessExpr {
+                expr: inner,
+                member_name,
+            } => {
+                let base = self.check_place(inner)?;
+                Var {
+                    ty: self.field_ty(&base.ty, member_name, member_span(expr, member_name))?,
+                    mutable: base.mutable,
+                }
+            }
+            Expr::PointerMemberAccessExpr {
+                expr: inner,
+                member_name,
+            } => match self.check_expr(inner, None)? {
+                Ty::Pointer { is_const, pointee } => Var {
+                    ty: self.field_ty(&pointee, member_name, member_span(expr, member_name))?,
+                    mutable: !is_const,
+                },
+                ty => {
+                    return Err(Box::new(InvalidOperand {
+                        op: OP_MINUSRARROW.to_owned(),
+                        ty,
+                        location: expr.span.clone(),
+                    }))
+                }
+            },
+            Expr::ParenthesizedExpr(inner) => self.check_place(inner)?,
+            Expr::IndexExpr { expr: inner, index } => self.check_index(inner, index)?,
+            Expr::UnaryExpr {
+                op: UnaryOperation::Dereference,
+                expr: inner,
+            } => match self.check_expr(inner, None)? {
+                Ty::Pointer { is_const, pointee } => Var {
+                    ty: *pointee,
+                    mutable: !is_const,
+                },
+                ty => {
+                    return Err(Box::new(InvalidOperand {
+                        op: UnaryOperation::Dereference.to_string(),
+                        ty,
+                        location: expr.span.clone(),
+                    }))
+                }
+            },
+            _ => return Err(invalid_place(expr)),
+        };
+        self.record(expr, place.ty.clone());
+        Ok(place)
+    }
+
+    /// Checks an expression and returns its type, the expected type is used to
+    /// know the type of literals, but the returned type must still be checked.
+    fn check_expr(&mut self, expr: &Expression, expected: Option<&Ty>) -> CheckResult<Ty> {
+        let span = expr.span.clone();
+        let ty = match &expr.expr {
+            Expr::IntLitExpr { value, suffix, .. } => {
+                self.check_int_lit(*value, false, *suffix, expected, span)?
+            }
+            Expr::FloatLitExpr(float) => self.check_float_lit(*float, expected, span)?,
+            Expr::CharLitExpr(c) => {
+                let ty = expected.filter(|ty| ty.is_int()).unwrap_or(&Ty::CHAR);
+                self.check_int_lit(*c as u128, false, None, Some(ty), span)?
+            }
+            Expr::BoolLitExpr(_) => Ty::BOOL,
+            Expr::StrLitExpr(_) => Ty::str(),
+            Expr::IdentifierExpr(name) => self.get_variable(name, span)?.ty,
+            Expr::ParenthesizedExpr(inner) => self.check_expr(inner, expected)?,
+            Expr::BinaryExpr { lhs, op, rhs } => self.check_binary(lhs, op, rhs, span, expected)?,
+            Expr::UnaryExpr { op, expr: inner } => {
+                self.check_unary(expr, op, inner, span, expected)?
+            }
+            Expr::CallExpr { fn_op, args } => {
+                let ty = self.check_call(fn_op, args, span.clone())?;
+                if ty.is_void() {
+                    return Err(Box::new(MismatchedTypes {
+                        expected: expected.cloned().unwrap_or(Ty::DEFAULT_INT),
+                        found: Ty::VOID,
+                        location: span,
+                    }));
+                }
+                ty
+            }
+            Expr::IfElseExpr {
+                true_expr,
+                predicate,
+                false_expr,
+            } => self.check_if_else_expr(true_expr, predicate, false_expr, expected)?,
+            Expr::MemberAccessExpr {
+                expr: inner,
+                member_name,
+            } => match self.enum_variant(expr)? {
+                Some((ty, index)) => {
+                    if !self.results.enum_def(&ty).variants[index].fields.is_empty() {
+                        return Err(Box::new(SimpleLog {
+                            level: LogLevel::Error,
+                            msg: format!("variant `{ty}.{member_name}` carries a payload").into(),
+                            cursor_msg: Some(
+                                format!("expected `{ty}.{member_name}(..)` with the payload")
+                                    .into(),
+                            ),
+                            location: span,
+                        }));
+                    }
+                    ty
+                }
+                None => match self.package_global(expr)? {
+                    Some(var) => var.ty,
+                    None => match self.check_expr(inner, None)? {
+                        // the number of elements
+                        Ty::Array { .. } | Ty::Slice { .. } if member_name == "len" => Ty::USIZE,
+                        ty => self.field_ty(&ty, member_name, member_span(expr, member_name))?,
+                    },
+                },
+            },
+            Expr::PointerMemberAccessExpr { .. } => self.check_place(expr)?.ty,
+            Expr::IndexExpr { expr: inner, index } => self.check_index(inner, index)?.ty,
+            Expr::SliceExpr {
+                expr: inner,
+                start,
+                end,
+            } => self.check_slice(inner, start.as_deref(), end.as_deref())?,
+            Expr::ArrayLitExpr(elems) => self.check_array_lit(elems, expected, span)?,
+            Expr::StructLitExpr { ty, fields } => self.check_struct_lit(ty, fields)?,
+            Expr::MatchExpr { scrutinee, arms } => {
+                let (ty, _) = self.check_match(scrutinee, arms, expected)?;
+                if ty.is_void() {
+                    return Err(Box::new(MismatchedTypes {
+                        expected: expected.cloned().unwrap_or(Ty::DEFAULT_INT),
+                        found: Ty::VOID,
+                        location: scrutinee.span.clone(),
+                    }));
+                }
+                ty
+            }
+        };
+        Ok(self.record(expr, ty))
+    }
+
+    /// Checks the array or the slice that is indexed or sliced, it's
+    /// a place, unless it's an array computed by the expression.
+    fn check_indexed(&mut self, expr: &Expression) -> CheckResult<Var> {
+        let base = if is_place_expr(expr) {
+            self.check_place(expr)?
+        } else {
+            Var {
+                ty: self.check_expr(expr, None)?,
+                mutable: false,
+            }
+        };
+        if base.ty.elem().is_none() {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("cannot index into a value of type `{}`", base.ty).into(),
+                cursor_msg: Some("only arrays and slices can be indexed".into()),
+                location: expr.span.clone(),
+            }));
+        }
+        Ok(base)
+    }
+
+    /// Checks an expression that must be a `usize`, an index or a bound of a
+    /// slice.
+    fn check_usize(&mut self, expr: &Expression) -> CheckResult<()> {
+        let ty = self.check_expr(expr, Some(&Ty::USIZE))?;
+        self.check_ty(&ty, &Ty::USIZE, expr.span.clone())
+    }
+
+    /// Checks an element of an array or of a slice, it can be assigned if the
+    /// array can be or if the slice isn't `const`.
+    fn check_index(&mut self, expr: &Expression, index: &Expression) -> CheckResult<Var> {
+        let base = self.check_indexed(expr)?;
+        self.check_usize(index)?;
+        Ok(match base.ty {
+            Ty::Slice { is_const, elem } => Var {
+                ty: *elem,
+                mutable: !is_const,
+            },
+            Ty::Array { elem, .. } => Var {
+                ty: *elem,
+                mutable: base.mutable,
+            },
+            _ => unreachable!("`check_indexed` only returns arrays and slices"),
+        })
+    }
+
+    /// Checks the slice of an array or of a slice, it's `const` if the
+    /// elements can't be assigned.
+    fn check_slice(
+        &mut self,
+        expr: &Expression,
+        start: Option<&Expression>,
+        end: Option<&Expression>,
+    ) -> CheckResult<Ty> {
+        let base = self.check_indexed(expr)?;
+        if base.ty.is_array() && !is_place_expr(expr) {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: "cannot slice a temporary array".into(),
+                cursor_msg: Some("consider storing the array in a variable".into()),
+                location: expr.span.clone(),
+            }));
+        }
+        for bound in start.into_iter().chain(end) {
+            self.check_usize(bound)?;
+        }
+        Ok(match base.ty {
+            Ty::Array { elem, .. } => Ty::Slice {
+                is_const: !base.mutable,
+                elem,
+            },
+            slice => slice,
+        })
+    }
+
+    /// Checks an array literal, the elements have the type of the first one,
+    /// or the one of the elements of the expected array type.
+    fn check_array_lit(
+        &mut self,
+        elems: &[Expression],
+        expected: Option<&Ty>,
+        span: CodeSpan,
+    ) -> CheckResult<Ty> {
+        let mut elem_ty = match expected {
+            Some(Ty::Array { elem, .. }) => Some((**elem).clone()),
+            _ => None,
+        };
+        for elem in elems {
+            let ty = self.check_expr(elem, elem_ty.as_ref())?;
+            match &elem_ty {
+                Some(elem_ty) => self.check_ty(&ty, elem_ty, elem.span.clone())?,
+                None => elem_ty = Some(ty),
+            }
+        }
+        let Some(elem) = elem_ty else {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: "type annotations needed".into(),
+                cursor_msg: Some("cannot infer the type of the elements of an empty array".into()),
+                location: span,
+            }));
+        };
+        Ok(Ty::Array {
+            elem: Box::new(elem),
+            len: elems.len() as u64,
+        })
+    }
+
+    /// Checks an integer literal, its type is the expected type if it's an
+    /// integer type or `i32`.
+    fn check_int_lit(
+        &self,
+        value: u128,
+        negative: bool,
+        suffix: Option<PrimitiveTy>,
+        expected: Option<&Ty>,
+        span: CodeSpan,
+    ) -> CheckResult<Ty> {
+        // the suffix gives the type, even if another one is expected
+        let ty = match suffix {
+            Some(suffix) => Ty::Prim(suffix),
+            None => expected
+                .filter(|ty| ty.is_int())
+                .cloned()
+                .unwrap_or(Ty::DEFAULT_INT),
+        };
+        let bits = ty.int_bits(self.pointer_width).unwrap();
+
+        let max = if ty.is_signed() {
+            (u128::MAX >> (129 - bits)) + negative as u128
+        } else if negative {
+            return Err(Box::new(InvalidOperand {
+                op: UnaryOperation::Negation.to_string(),
+                ty,
+                location: span,
+            }));
+        } else {
+            u128::MAX >> (128 - bits)
+        };
+        if value > max {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("literal out of range for `{ty}`").into(),
+                cursor_msg: Some(
+                    format!(
+                        "`{}{}` doesn't fit in `{}`",
+                        if negative { "-" } else { "" },
+                        value,
+                        ty
+                    )
+                    .into(),
+                ),
+                location: span,
+            }));
+        }
+        Ok(ty)
+    }
+
+    /// Returns the type of the float literal, the expected type if it's a
+    /// float type, an error if the value is too big for it.
+    fn check_float_lit(
+        &self,
+        value: f64,
+        expected: Option<&Ty>,
+        span: CodeSpan,
+    ) -> CheckResult<Ty> {
+        let ty = expected
+            .filter(|ty| ty.is_float())
+            .cloned()
+            .unwrap_or(Ty::DEFAULT_FLOAT);
+
+        let max = match ty {
+            Ty::Prim(PrimitiveTy::F16) => 65504.0,
+            Ty::Prim(PrimitiveTy::F32) => f32::MAX as f64,
+            _ => f64::MAX,
+        };
+        if value > max {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("literal out of range for `{ty}`").into(),
+                cursor_msg: Some(format!("`{value:?}` doesn't fit in `{ty}`").into()),
+                location: span,
+            }));
+        }
+        Ok(ty)
+    }
+
+    fn check_binary(
+        &mut self,
+        lhs: &Expression,
+        op: &BinOperation,
+        rhs: &Expression,
+        span: CodeSpan,
+        expected: Option<&Ty>,
+    ) -> CheckResult<Ty> {
+        // the operands of a comparison don't have the type of the result
+        let expected = if op.is_comparison() { None } else { expected };
+
+        // an integer literal takes the type of the other operand
+        let (lhs_ty, rhs_ty) = if is_untyped_lit(lhs) && !is_untyped_lit(rhs) {
+            let rhs_ty = self.check_expr(rhs, expected)?;
+            let lhs_ty = self.check_expr(lhs, Some(&rhs_ty))?;
+            (lhs_ty, rhs_ty)
+        } else {
+            let lhs_ty = self.check_expr(lhs, expected)?;
+            let rhs_ty = self.check_expr(rhs, Some(&lhs_ty))?;
+            (lhs_ty, rhs_ty)
+        };
+
+        if lhs_ty != rhs_ty {
+            return Err(Box::new(MismatchedTypes {
+                expected: lhs_ty,
+                found: rhs_ty,
+                location: rhs.span.clone(),
+            }));
+        }
+        let ty = lhs_ty;
+
+        // a value of an enum with payloads isn't only its discriminant
+        if !is_valid_operand(op, &ty) || ty.is_enum() && self.results.enum_def(&ty).has_payload() {
+            return Err(Box::new(InvalidOperand {
+                op: op.to_string(),
+                ty,
+                location: span,
+            }));
+        }
+
+        Ok(if op.is_comparison() { Ty::BOOL } else { ty })
+    }
+
+    fn check_unary(
+        &mut self,
+        expr: &Expression,
+        op: &UnaryOperation,
+        inner: &Expression,
+        span: CodeSpan,
+        expected: Option<&Ty>,
+    ) -> CheckResult<Ty> {
+        let invalid = |ty: Ty| -> CheckResult<Ty> {
+            Err(Box::new(InvalidOperand {
+                op: op.to_string(),
+                ty,
+                location: span.clone(),
+            }))
+        };
+
+        match op {
+            UnaryOperation::Negation => {
+                if let Expr::IntLitExpr { value, suffix, .. } = inner.expr {
+                    let ty = self.check_int_lit(value, true, suffix, expected, span.clone())?;
+                    return Ok(self.record(inner, ty));
+                }
+                let ty = self.check_expr(inner, expected)?;
+                if ty.is_signed() || ty.is_float() {
+                    Ok(ty)
+                } else {
+                    invalid(ty)
+                }
+            }
+            UnaryOperation::Not => {
+                let ty = self.check_expr(inner, expected)?;
+                if ty.is_int() || ty.is_bool() {
+                    Ok(ty)
+                } else {
+                    invalid(ty)
+                }
+            }
+            UnaryOperation::BitwiseNot => {
+                let ty = self.check_expr(inner, expected)?;
+                if ty.is_int() {
+                    Ok(ty)
+                } else {
+                    invalid(ty)
+                }
+            }
+            UnaryOperation::AddressOf => {
+                let place = self.check_place(inner)?;
+                Ok(Ty::Pointer {
+                    is_const: !place.mutable,
+                    pointee: Box::new(place.ty),
+                })
+            }
+            UnaryOperation::Dereference => {
+                let place = self.check_place(expr)?;
+                self.check_value_ty(&place.ty, span.clone())?;
+                Ok(place.ty)
+            }
+        }
+    }
+
+    /// Checks a call and returns the return type of the function, it may be
+    /// `void`.
+    fn check_call(
+        &mut self,
+        fn_op: &Expression,
+        args: &[Expression],
+        span: CodeSpan,
+    ) -> CheckResult<Ty> {
+        let (name, sig) = if let Some((ty, def)) = self.assoc_fn(fn_op)? {
+            let Expr::MemberAccessExpr { member_name, .. } = &fn_op.expr else {
+                unreachable!("a function of an `impl` block is named by a member access")
+            };
+            let call = MethodCall {
+                ty: ty.clone(),
+                name: member_name.clone(),
+                autoref: None,
+            };
+            self.results.method_calls.insert(fn_op.span.clone(), call);
+            (format!("{ty}.{member_name}"), def.sig)
+        } else if let Some((ty, index)) = self.enum_variant(fn_op)? {
+            let variant = &self.results.enum_def(&ty).variants[index];
+            let name = format!("{ty}.{}", variant.name);
+            if variant.fields.is_empty() {
+                return Err(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("variant `{name}` doesn't carry a payload").into(),
+                    cursor_msg: Some(format!("expected `{name}`, without parentheses").into()),
+                    location: span,
+                }));
+            }
+            let sig = FnSig {
+                args: variant.fields.clone(),
+                ret_ty: ty,
+            };
+            (name, sig)
+        } else if let Some((import, item)) = self.package_item(fn_op) {
+            let resolved = &self.results.imports[import];
+            let sig = self.imported_function(resolved, item, fn_op.span.clone())?;
+            self.results
+                .package_items
+                .insert(fn_op.span.clone(), import);
+            (format!("{}.{}", resolved.name, item), sig)
+        } else if let Expr::IdentifierExpr(name) = &fn_op.expr {
+            let sig = self
+                .functions
+                .get(name)
+                .cloned()
+                .ok_or_else(|| -> Box<dyn Log> {
+                    Box::new(Undeclared {
+                        kind: "function",
+                        name: name.clone(),
+                        location: fn_op.span.clone(),
+                    })
+                })?;
+            (name.clone(), sig)
+        } else if let Expr::MemberAccessExpr { .. } | Expr::PointerMemberAccessExpr { .. } =
+            &fn_op.expr
+        {
+            self.check_method_receiver(fn_op)?
+        } else {
+            return Err(Box::new(Unsupported {
+                what: "calling something else than a function by its name".to_owned(),
+                location: fn_op.span.clone(),
+            }));
+        };
+
+        if args.len() != sig.args.len() {
+            return Err(Box::new(WrongArgCount {
+                name,
+                expected: sig.args.len(),
+                found: args.len(),
+                location: span,
+            }));
+        }
+
+        for (arg, ty) in args.iter().zip(&sig.args) {
+            let arg_ty = self.check_expr(arg, Some(ty))?;
+            self.check_ty(&arg_ty, ty, arg.span.clone())?;
+        }
+
+        Ok(sig.ret_ty)
+    }
+
+    /// Checks the receiver of a method call, like `p` in `p.len()` or in
+    /// `p->len()`, returns the name of the method and its signature without
+    /// the receiver.
+    fn check_method_receiver(&mut self, fn_op: &Expression) -> CheckResult<(String, FnSig)> {
+        let (base, member_name, through_pointer) = match &fn_op.expr {
+            Expr::MemberAccessExpr { expr, member_name } => (expr, member_name, false),
+            Expr::PointerMemberAccessExpr { expr, member_name } => (expr, member_name, true),
+            _ => unreachable!("a method is called through a member access"),
+        };
+        let span = member_span(fn_op, member_name);
+
+        let base_ty = self.check_expr(base, None)?;
+        let ty = match &base_ty {
+            Ty::Pointer { pointee, .. } if through_pointer => (**pointee).clone(),
+            _ if through_pointer => {
+                return Err(Box::new(InvalidOperand {
+                    op: OP_MINUSRARROW.to_owned(),
+                    ty: base_ty,
+                    location: fn_op.span.clone(),
+                }))
+            }
+            _ => base_ty.clone(),
+        };
+        let def = self.method(&ty, member_name, span.clone())?;
+        let name = format!("{ty}.{member_name}");
+        let Some(receiver) = def.receiver else {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("`{name}` isn't a method").into(),
+                cursor_msg: Some(
+                    format!("it doesn't take `self`, consider calling it as `{name}(..)`").into(),
+                ),
+                location: span,
+            }));
+        };
+        let modified = |on: &str| -> Box<dyn Log> {
+            Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("cannot call `{name}`, taking `*self`, on {on}").into(),
+                cursor_msg: Some(format!("`{name}` may modify it").into()),
+                location: base.span.clone(),
+            })
+        };
+
+        // the receiver is referenced or dereferenced as the method takes it
+        let autoref = match (receiver, through_pointer) {
+            (ReceiverKind::Value, false) => Autoref::Value,
+            (ReceiverKind::Value, true) => Autoref::Deref,
+            (ReceiverKind::Pointer, true) if base_ty.coerces_to(&def.sig.args[0]) => Autoref::Value,
+            (ReceiverKind::Pointer, true) => return Err(modified(&format!("a `{base_ty}`"))),
+            (ReceiverKind::ConstPointer, true) => Autoref::Value,
+            (kind, false) if is_place_expr(base) && self.enum_variant(base)?.is_none() => {
+                let place = self.check_place(base)?;
+                if kind == ReceiverKind::Pointer && !place.mutable {
+                    return Err(modified("a constant"));
+                }
+                Autoref::Ref
+            }
+            (_, false) => Autoref::RefTemporary,
+        };
+        let call = MethodCall {
+            ty,
+            name: member_name.clone(),
+            autoref: Some(autoref),
+        };
+        self.results.method_calls.insert(fn_op.span.clone(), call);
+
+        let mut sig = def.sig;
+        sig.args.remove(0);
+        Ok((name, sig))
+    }
+
+    /// Checks a struct literal, every field must be initialized once.
+    fn check_struct_lit(&mut self, ty: &AstType, fields: &[FieldInit]) -> CheckResult<Ty> {
+        let struct_ty = self.resolve_ty(ty)?;
+        let Some(TypeDef::Struct(def)) = self.results.types.get(&struct_ty).map(|item| &item.def)
+        else {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!("`{struct_ty}` is not a struct").into(),
+                cursor_msg: Some("only structs have literals with fields".into()),
+                location: ty.span.clone(),
+            }));
+        };
+        let def = def.clone();
+
+        let mut initialized = HashSet::new();
+        for init in fields {
+            let field_ty = self.field_ty(&struct_ty, &init.name, init.span.clone())?;
+            if !initialized.insert(init.name.as_str()) {
+                return Err(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("field `{}` is initialized more than once", init.name).into(),
+                    cursor_msg: None,
+                    location: init.span.clone(),
+                }));
+            }
+            let value_ty = self.check_expr(&init.expr, Some(&field_ty))?;
+            self.check_ty(&value_ty, &field_ty, init.expr.span.clone())?;
+        }
+
+        let missing: Vec<_> = def
+            .fields
+            .iter()
+            .filter(|field| !initialized.contains(field.name.as_str()))
+            .map(|field| format!("`{}`", field.name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: format!(
+                    "missing field(s) {} in the literal of `{}`",
+                    missing.join(", "),
+                    struct_ty
+                )
+                .into(),
+                cursor_msg: Some("every field must be initialized".into()),
+                location: ty.span.clone(),
+            }));
+        }
+        Ok(struct_ty)
+    }
+
+    /// Checks a match, returns its type and whether the execution can
+    /// continue after it.
+    ///
+    /// The arms are checked in order, the patterns must cover every value of
+    /// the scrutinee and an arm whose pattern only matches values of the
+    /// previous arms is reported. The type of the match is the one of the arms,
+    /// a block arm is `void` and an arm that never completes has no type.
+    fn check_match(
+        &mut self,
+        scrutinee: &Expression,
+        arms: &[MatchArm],
+        expected: Option<&Ty>,
+    ) -> CheckResult<(Ty, bool)> {
+        let scrutinee_ty = self.check_expr(scrutinee, None)?;
+
+        let mut rows = Vec::new();
+        let mut arm_scopes = Vec::new();
+        for arm in arms {
+            self.scopes.push(HashMap::new());
+            let pat = self.check_pattern(&arm.pattern, &scrutinee_ty);
+            let guard = match (&pat, &arm.guard) {
+                (Ok(_), Some(guard)) => self.check_condition(guard),
+                _ => Ok(()),
+            };
+            arm_scopes.push(self.scopes.pop().unwrap());
+            let (pat, ()) = (pat?, guard?);
+
+            let cx = self.pat_cx();
+            let row = [pat];
+            if cx
+                .usefulness(&rows, &row, std::slice::from_ref(&scrutinee_ty))
+                .is_empty()
+            {
+                self.lctx.push(SimpleLog {
+                    level: LogLevel::Warning,
+                    msg: "unreachable pattern".into(),
+                    cursor_msg: Some(
+                        "every value it matches is matched by the previous arms".into(),
+                    ),
+                    location: arm.pattern.span.clone(),
+                });
+            }
+            // a guarded arm may not match the values of its pattern
+            if arm.guard.is_none() {
+                rows.push(row.to_vec());
+            }
+        }
+
+        let cx = self.pat_cx();
+        let missing: Vec<String> = cx
+            .usefulness(&rows, &[Pat::Wild], std::slice::from_ref(&scrutinee_ty))
+            .iter()
+            .map(|witness| cx.fmt_pat(&witness[0], &scrutinee_ty))
+            .collect();
+        if !missing.is_empty() {
+            return Err(Box::new(NonExhaustive {
+                ty: scrutinee_ty,
+                missing,
+                location: scrutinee.span.clone(),
+            }));
+        }
+
+        // the arms with a typed expression are checked first, an integer
+        // literal takes their type
+        let mut order: Vec<usize> = (0..arms.len()).collect();
+        order
+            .sort_by_key(|&i| matches!(&arms[i].body, ArmBody::Expr(expr) if is_untyped_lit(expr)));
+
+        let mut ty: Option<Ty> = None;
+        let mut falls_through = false;
+        for i in order {
+            let arm = &arms[i];
+            self.scopes.push(std::mem::take(&mut arm_scopes[i]));
+            let arm_ty = self.check_arm_body(&arm.body, ty.as_ref().or(expected));
+            self.scopes.pop();
+
+            let (arm_ty, location) = match (arm_ty?, &arm.body) {
+                (Some(arm_ty), ArmBody::Expr(expr)) => (arm_ty, expr.span.clone()),
+                (Some(arm_ty), ArmBody::Block(_)) => (arm_ty, arm.pattern.span.clone()),
+                (None, _) => continue,
+            };
+            falls_through = true;
+            match &ty {
+                Some(ty) if *ty != arm_ty => {
+                    return Err(Box::new(MismatchedTypes {
+                        expected: ty.clone(),
+                        found: arm_ty,
+                        location,
+                    }));
+                }
+                Some(_) => {}
+                None => ty = Some(arm_ty),
+            }
+        }
+        Ok((ty.unwrap_or(Ty::VOID), falls_through))
+    }
+
+    /// Checks the body of an arm, in the scope of the bindings of its pattern,
+    /// returns its type or `None` if it never completes.
+    fn check_arm_body(&mut self, body: &ArmBody, expected: Option<&Ty>) -> CheckResult<Option<Ty>> {
+        match body {
+            // like a call statement, it may call a function returning void
+            ArmBody::Expr(
+                expr @ Expression {
+                    expr: Expr::CallExpr { fn_op, args },
+                    ..
+                },
+            ) => {
+                let ty = self.check_call(fn_op, args, expr.span.clone())?;
+                Ok(Some(self.record(expr, ty)))
+            }
+            ArmBody::Expr(expr) => Ok(Some(self.check_expr(expr, expected)?)),
+            ArmBody::Block(block) => Ok(self.check_block(block).then_some(Ty::VOID)),
+        }
+    }
+
+    fn pat_cx(&self) -> PatCx<'_> {
+        PatCx {
+            types: &self.results.types,
+            pointer_width: self.pointer_width,
+        }
+    }
+
+    /// Checks that the pattern matches values of type `ty`, its bindings are
+    /// declared as constants in the innermost scope. Returns what it matches.
+    fn check_pattern(&mut self, pattern: &Pattern, ty: &Ty) -> CheckResult<Pat> {
+        let span = pattern.span.clone();
+        match &pattern.pat {
+            AstPat::Wildcard => Ok(Pat::Wild),
+            AstPat::Binding(name) => {
+                self.declare_local(name, ty.clone(), false, span)?;
+                Ok(Pat::Wild)
+            }
+            AstPat::Literal(lit) => {
+                let value = self.check_pattern_lit(lit, ty)?;
+                Ok(Pat::Ctor(Ctor::Range(value, value), Vec::new()))
+            }
+            AstPat::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                if !ty.is_int() {
+                    return Err(Box::new(SimpleLog {
+                        level: LogLevel::Error,
+                        msg: format!("range pattern on a value of type `{ty}`").into(),
+                        cursor_msg: Some("only integers and chars can be matched by ranges".into()),
+                        location: span,
+                    }));
+                }
+                let lo = self.check_pattern_lit(start, ty)?;
+                let hi = self.check_pattern_lit(end, ty)?;
+                let hi = if *inclusive {
+                    Some(hi)
+                } else {
+                    hi.checked_sub(1)
+                };
+                match hi {
+                    Some(hi) if lo <= hi => Ok(Pat::Ctor(Ctor::Range(lo, hi), Vec::new())),
+                    _ => Err(Box::new(SimpleLog {
+                        level: LogLevel::Error,
+                        msg: "empty range pattern".into(),
+                        cursor_msg: Some("the start must be lower than the end".into()),
+                        location: span,
+                    })),
+                }
+            }
+            AstPat::Variant { path, fields } => {
+                self.check_variant_pattern(path, fields.as_deref(), ty, span)
+            }
+            AstPat::Struct {
+                ty: struct_ty,
+                fields,
+                rest,
+            } => {
+                let found = self.resolve_ty(struct_ty)?;
+                self.check_ty(&found, ty, struct_ty.span.clone())?;
+                let Some(TypeDef::Struct(def)) =
+                    self.results.types.get(&found).map(|item| &item.def)
+                else {
+                    return Err(Box::new(SimpleLog {
+                        level: LogLevel::Error,
+                        msg: format!("`{found}` is not a struct").into(),
+                        cursor_msg: Some("only structs have patterns with fields".into()),
+                        location: struct_ty.span.clone(),
+                    }));
+                };
+                let def = def.clone();
+
+                let mut subpats = vec![Pat::Wild; def.fields.len()];
+                let mut matched = HashSet::new();
+                for field in fields {
+                    let field_ty = self.field_ty(&found, &field.name, field.span.clone())?;
+                    if !matched.insert(field.name.as_str()) {
+                        return Err(Box::new(SimpleLog {
+                            level: LogLevel::Error,
+                            msg: format!("field `{}` is matched more than once", field.name).into(),
+                            cursor_msg: None,
+                            location: field.span.clone(),
+                        }));
+                    }
+                    let (index, _) = def.field(&field.name).unwrap();
+                    subpats[index] = self.check_pattern(&field.pattern, &field_ty)?;
+                }
+
+                let missing: Vec<_> = def
+                    .fields
+                    .iter()
+                    .filter(|field| !matched.contains(field.name.as_str()))
+                    .map(|field| format!("`{}`", field.name))
+                    .collect();
+                if !rest && !missing.is_empty() {
+                    return Err(Box::new(SimpleLog {
+                        level: LogLevel::Error,
+                        msg: format!(
+                            "pattern of `{}` doesn't mention field(s) {}",
+                            found,
+                            missing.join(", ")
+                        )
+                        .into(),
+                        cursor_msg: Some(
+                            "match every field, or ignore the others with `..`".into(),
+                        ),
+                        location: struct_ty.span.clone(),
+                    }));
+                }
+                Ok(Pat::Ctor(Ctor::Struct, subpats))
+            }
+        }
+    }
+
+    /// Checks a variant pattern, like `Shape.Circle(r)`, its payload must be
+    /// matched if it has one.
+    fn check_variant_pattern(
+        &mut self,
+        path: &Expression,
+        fields: Option<&[Pattern]>,
+        ty: &Ty,
+        span: CodeSpan,
+    ) -> CheckResult<Pat> {
+        let Some((enum_ty, index)) = self.enum_variant(path)? else {
+            return Err(Box::new(SimpleLog {
+                level: LogLevel::Error,
+                msg: "expected a variant".into(),
+                cursor_msg: Some(
+                    "only the variants of an enum, like `Color.Red`, are patterns with a path"
+                        .into(),
+                ),
+                location: path.span.clone(),
+            }));
+        };
+        self.check_ty(&enum_ty, ty, path.span.clone())?;
+
+        let variant = self.results.enum_def(&enum_ty).variants[index].clone();
+        let name = format!("{enum_ty}.{}", variant.name);
+        let fields = match fields {
+            None if variant.fields.is_empty() => &[][..],
+            Some(fields) if fields.len() == variant.fields.len() => fields,
+            None => {
+                return Err(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("variant `{name}` carries a payload").into(),
+                    cursor_msg: Some(
+                        format!("expected `{name}(..)` with patterns of the payload").into(),
+                    ),
+                    location: span,
+                }))
+            }
+            Some(_) if variant.fields.is_empty() => {
+                return Err(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!("variant `{name}` doesn't carry a payload").into(),
+                    cursor_msg: Some(format!("expected `{name}`, without parentheses").into()),
+                    location: span,
+                }))
+            }
+            Some(fields) => {
+                return Err(Box::new(SimpleLog {
+                    level: LogLevel::Error,
+                    msg: format!(
+                        "the payload of variant `{}` has {} field(s) but the pattern has {}",
+                        name,
+                        variant.fields.len(),
+                        fields.len()
+                    )
+                    .into(),
+                    cursor_msg: None,
+                    location: span,
+                }))
+            }
+        };
+
+        let mut subpats = Vec::with_capacity(fields.len());
+        for (field, field_ty) in fields.iter().zip(&variant.fields) {
+            subpats.push(self.check_pattern(field, field_ty)?);
+        }
+        Ok(Pat::Ctor(Ctor::Variant(index), subpats))
+    }
+
+    /// Checks the literal of a pattern, it must have the type `ty`, and returns
+    /// its biased value.
+    fn check_pattern_lit(&mut self, lit: &Expression, ty: &Ty) -> CheckResult<u128> {
+        let lit_ty = self.check_expr(lit, Some(ty))?;
+        self.check_ty(&lit_ty, ty, lit.span.clone())?;
+
+        let signed = ty.is_signed();
+        Ok(match &lit.expr {
+            Expr::IntLitExpr { value, .. } => biased(*value, false, signed),
+            Expr::UnaryExpr { expr: inner, .. } => match inner.expr {
+                Expr::IntLitExpr { value, .. } => biased(value, true, signed),
+                _ => unreachable!("only integer literals are negated in patterns"),
+            },
+            Expr::CharLitExpr(c) => biased(*c as u128, false, signed),
+            Expr::BoolLitExpr(b) => *b as u128,
+            _ => unreachable!("patterns only have integer, char and bool literals"),
+        })
+    }
+
+    fn check_if_else_expr(
+        &mut self,
+        true_expr: &Expression,
+        predicate: &Expression,
+        false_expr: &Expression,
+        expected: Option<&Ty>,
+    ) -> CheckResult<Ty> {
+        self.check_condition(predicate)?;
+
+        // an integer literal takes the type of the other branch
+        let (true_ty, false_ty) = if is_untyped_lit(true_expr) && !is_untyped_lit(false_expr) {
+            let false_ty = self.check_expr(false_expr, expected)?;
+            let true_ty = self.check_expr(true_expr, Some(&false_ty))?;
+            (true_ty, false_ty)
+        } else {
+            let true_ty = self.check_expr(true_expr, expected)?;
+            let false_ty = self.check_expr(false_expr, Some(&true_ty))?;
+            (true_ty, false_ty)
+        };
+
+        if true_ty != false_ty {
+            return Err(Box::new(MismatchedTypes {
+                expected: true_ty,
+                found: false_ty,
+                location: false_expr.span.clone(),
+            }));
+        }
+        Ok(true_ty)
+    }
+}
+
+/// Can the binary operation be applied to operands of this type?
+fn is_valid_operand(op: &BinOperation, ty: &Ty) -> bool {
+    use BinOperation::*;
+    if ty.is_int() {
+        !op.is_logical()
+    } else if ty.is_bool() {
+        matches!(
+            op,
+            And | Or | Xor | LogicalAnd | LogicalOr | CompEq | CompNe
+        )
+    } else if ty.is_float() {
+        op.is_comparison() || matches!(op, Add | Sub | Mul | Div | Rem)
+    } else if ty.is_pointer() || ty.is_enum() {
+        matches!(op, CompEq | CompNe)
+    } else {
+        false
+    }
+}
+
+fn invalid_place(expr: &Expression) -> Box<dyn Log> {
+    Box::new(SimpleLog {
+        level: LogLevel::Error,
+        msg: "invalid place expression".into(),
+        cursor_msg: Some("cannot be assigned or have its address taken".into()),
+        location: expr.span.clone(),
+    })
+}
+
+/// Is the expression a place, that can have its address taken, if it type
+/// checks?
+pub fn is_place_expr(expr: &Expression) -> bool {
+    match &expr.expr {
+        Expr::IdentifierExpr(_)
+        | Expr::MemberAccessExpr { .. }
+        | Expr::PointerMemberAccessExpr { .. }
+        | Expr::IndexExpr { .. }
+        | Expr::UnaryExpr {
+            op: UnaryOperation::Dereference,
+            ..
+        } => true,
+        Expr::ParenthesizedExpr(inner) => is_place_expr(inner),
+        _ => false,
+    }
+}
+
+/// Span of the name of the member in a member access, the expression before it
+/// may span several lines.
+fn member_span(expr: &Expression, member_name: &str) -> CodeSpan {
+    expr.span.end - member_name.len()..expr.span.end
+}
+
+/// Is the expression an integer literal, without any type?
+fn is_untyped_lit(expr: &Expression) -> bool {
+    match &expr.expr {
+        Expr::IntLitExpr { suffix, .. } => suffix.is_none(),
+        Expr::FloatLitExpr(_) => true,
+        Expr::ParenthesizedExpr(inner)
+        | Expr::UnaryExpr {
+            op: UnaryOperation::Negation,
+            expr: inner,
+        } => is_untyped_lit(inner),
+        Expr::BinaryExpr { lhs, op, rhs } if !op.is_comparison() => {
+            is_untyped_lit(lhs) && is_untyped_lit(rhs)
+        }
+        _ => false,
+    }
+}
+
+/// Is the expression a literal, so it can be used to initialize a global?
+pub fn is_literal(expr: &Expression) -> bool {
+    match &expr.expr {
+        Expr::IntLitExpr { .. }
+        | Expr::FloatLitExpr(_)
+        | Expr::CharLitExpr(_)
+        | Expr::BoolLitExpr(_)
+        | Expr::StrLitExpr(_) => true,
+        Expr::ParenthesizedExpr(inner) => is_literal(inner),
+        Expr::UnaryExpr {
+            op: UnaryOperation::Negation,
+            expr: inner,
+        } => matches!(inner.expr, Expr::IntLitExpr { .. } | Expr::FloatLitExpr(_)),
+        _ => false,
+    }
+}
+
+/// Is the expression the literal `true`, maybe parenthesized?
+fn is_true_lit(expr: &Expression) -> bool {
+    match &expr.expr {
+        Expr::BoolLitExpr(b) => *b,
+        Expr::ParenthesizedExpr(inner) => is_true_lit(inner),
+        _ => false,
+    }
+}
@@ -0,0 +1,289 @@
+//! Module responsible for the exhaustiveness checking of `match` expressions,
+//! and for finding their unreachable arms.
+//!
+//! It's the usefulness algorithm of "Warnings for pattern matching", by Luc
+//! Maranget. A pattern is useful after some rows of patterns if it matches a
+//! value that none of the rows matches: an arm is unreachable if its pattern
+//! isn't useful after the patterns of the previous arms, and a `match` is
+//! exhaustive if the wildcard isn't useful after all of them. The values the
+//! wildcard matches are then the examples of values not covered.
+
+use std::collections::HashMap;
+
+use crate::{
+    ty::Ty,
+    typeck::{Item, TypeDef},
+};
+
+/// Bit of the sign of a 128 bits integer, flipped to bias the signed values.
+const SIGN: u128 = 1 << 127;
+
+/// A pattern reduced to the values it matches, bindings are wildcards.
+#[derive(Debug, Clone)]
+pub enum Pat {
+    Wild,
+    /// A constructor of the type and the patterns of its fields.
+    Ctor(Ctor, Vec<Pat>),
+}
+
+/// A way to build a value of a type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ctor {
+    /// An inclusive range of values of an integer or a bool. The values are
+    /// biased, so the signed values are in the same order as the `u128`.
+    Range(u128, u128),
+    /// A variant of an enum, by its index.
+    Variant(usize),
+    /// The only constructor of a struct, its fields are in the order of the
+    /// declaration.
+    Struct,
+}
+
+impl Ctor {
+    /// Does the constructor match every value the other one matches?
+    fn covers(&self, other: &Ctor) -> bool {
+        match (self, other) {
+            (Ctor::Range(lo, hi), Ctor::Range(other_lo, other_hi)) => {
+                lo <= other_lo && other_hi <= hi
+            }
+            (Ctor::Variant(index), Ctor::Variant(other)) => index == other,
+            (Ctor::Struct, Ctor::Struct) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Returns the biased value of an integer literal of a type, `signed` or not,
+/// it must fit in the type.
+pub fn biased(value: u128, negative: bool, signed: bool) -> u128 {
+    let value = if negative {
+        value.wrapping_neg()
+    } else {
+        value
+    };
+    if signed {
+        value ^ SIGN
+    } else {
+        value
+    }
+}
+
+/// What the checks of the patterns need to know about the types.
+pub struct PatCx<'a> {
+    pub types: &'a HashMap<Ty, Item<TypeDef>>,
+    /// Width of a pointer of the target, in bits.
+    pub pointer_width: u32,
+}
+
+impl PatCx<'_> {
+    /// Returns the biased range of the values of the type, if they can be
+    /// matched by literals, it's the case of the integers and of the bools.
+    pub fn domain(&self, ty: &Ty) -> Option<(u128, u128)> {
+        if ty.is_bool() {
+            return Some((0, 1));
+        }
+        let bits = ty.int_bits(self.pointer_width)?;
+        if ty.is_signed() {
+            let half = 1 << (bits - 1);
+            Some((SIGN - half, SIGN + (half - 1)))
+        } else {
+            Some((0, u128::MAX >> (128 - bits)))
+        }
+    }
+
+    /// Returns the types of the fields of a constructor of the type.
+    fn field_tys(&self, ctor: &Ctor, ty: &Ty) -> Vec<Ty> {
+        match (ctor, self.types.get(ty).map(|item| &item.def)) {
+            (Ctor::Variant(index), Some(TypeDef::Enum(def))) => def.variants[*index].fields.clone(),
+            (Ctor::Struct, Some(TypeDef::Struct(def))) => {
+                def.fields.iter().map(|field| field.ty.clone()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the constructors of the type, only the ones matching values of
+    /// `within` if given, split so every constructor of `heads` covers them
+    /// entirely or not at all. `None` if the values of the type can't be
+    /// listed, like floats and pointers.
+    fn split(&self, ty: &Ty, within: Option<&Ctor>, heads: &[&Ctor]) -> Option<Vec<Ctor>> {
+        match self.types.get(ty).map(|item| &item.def) {
+            Some(TypeDef::Enum(def)) => {
+                return Some(match within {
+                    Some(ctor) => vec![ctor.clone()],
+                    None => (0..def.variants.len()).map(Ctor::Variant).collect(),
+                })
+            }
+            Some(TypeDef::Struct(_)) => return Some(vec![Ctor::Struct]),
+            None => {}
+        }
+
+        let (lo, hi) = match within {
+            Some(Ctor::Range(lo, hi)) => (*lo, *hi),
+            _ => self.domain(ty)?,
+        };
+        // the values where a range of the heads starts or ends
+        let mut cuts: Vec<u128> = heads
+            .iter()
+            .filter_map(|head| match head {
+                Ctor::Range(start, end) => Some([Some(*start), end.checked_add(1)]),
+                _ => None,
+            })
+            .flatten()
+            .flatten()
+            .filter(|&cut| lo < cut && cut <= hi)
+            .collect();
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        let mut ctors = Vec::with_capacity(cuts.len() + 1);
+        let mut start = lo;
+        for cut in cuts {
+            ctors.push(Ctor::Range(start, cut - 1));
+            start = cut;
+        }
+        ctors.push(Ctor::Range(start, hi));
+        Some(ctors)
+    }
+
+    /// Returns the values matched by the row `v` but by none of the `rows`,
+    /// as rows of patterns, it's empty if `v` isn't useful. The patterns of
+    /// the n-th column match values of the n-th type of `tys`.
+    pub fn usefulness(&self, rows: &[Vec<Pat>], v: &[Pat], tys: &[Ty]) -> Vec<Vec<Pat>> {
+        let Some((head, rest)) = v.split_first() else {
+            return if rows.is_empty() {
+                vec![Vec::new()]
+            } else {
+                Vec::new()
+            };
+        };
+        let ty = &tys[0];
+        let heads: Vec<&Ctor> = rows
+            .iter()
+            .filter_map(|row| match &row[0] {
+                Pat::Ctor(ctor, _) => Some(ctor),
+                Pat::Wild => None,
+            })
+            .collect();
+
+        let ctors = match head {
+            Pat::Ctor(ctor, _) => self.split(ty, Some(ctor), &heads),
+            Pat::Wild => {
+                // an enum without variants has no values to match
+                if self
+                    .split(ty, None, &[])
+                    .is_some_and(|ctors| ctors.is_empty())
+                {
+                    return Vec::new();
+                }
+                // only the rows starting with a wildcard may match the values
+                // that no constructor of the column matches
+                if heads.is_empty() {
+                    None
+                } else {
+                    self.split(ty, None, &heads)
+                }
+            }
+        };
+
+        let Some(ctors) = ctors else {
+            let rows: Vec<Vec<Pat>> = rows
+                .iter()
+                .filter(|row| matches!(row[0], Pat::Wild))
+                .map(|row| row[1..].to_vec())
+                .collect();
+            return self
+                .usefulness(&rows, rest, &tys[1..])
+                .into_iter()
+                .map(|witness| [vec![Pat::Wild], witness].concat())
+                .collect();
+        };
+
+        let mut witnesses = Vec::new();
+        for ctor in ctors {
+            let field_tys = self.field_tys(&ctor, ty);
+            let arity = field_tys.len();
+            let rows: Vec<Vec<Pat>> = rows
+                .iter()
+                .filter_map(|row| specialize(row, &ctor, arity))
+                .collect();
+            let v = specialize(v, &ctor, arity).unwrap();
+            let tys = [field_tys, tys[1..].to_vec()].concat();
+
+            for witness in self.usefulness(&rows, &v, &tys) {
+                let (fields, rest) = witness.split_at(arity);
+                let pat = Pat::Ctor(ctor.clone(), fields.to_vec());
+                witnesses.push([vec![pat], rest.to_vec()].concat());
+            }
+        }
+        witnesses
+    }
+
+    /// Formats the pattern matching values of the type, like in the source
+    /// code.
+    pub fn fmt_pat(&self, pat: &Pat, ty: &Ty) -> String {
+        let Pat::Ctor(ctor, fields) = pat else {
+            return "_".to_owned();
+        };
+        match (ctor, self.types.get(ty).map(|item| &item.def)) {
+            (Ctor::Range(lo, hi), _) if ty.is_bool() => match (lo, hi) {
+                (0, 0) => "false".to_owned(),
+                (1, 1) => "true".to_owned(),
+                _ => "_".to_owned(),
+            },
+            (Ctor::Range(lo, hi), _) => {
+                let fmt_int = |value: u128| {
+                    if ty.is_signed() {
+                        ((value ^ SIGN) as i128).to_string()
+                    } else {
+                        value.to_string()
+                    }
+                };
+                if Some((*lo, *hi)) == self.domain(ty) {
+                    "_".to_owned()
+                } else if lo == hi {
+                    fmt_int(*lo)
+                } else {
+                    format!("{}..={}", fmt_int(*lo), fmt_int(*hi))
+                }
+            }
+            (Ctor::Variant(index), Some(TypeDef::Enum(def))) => {
+                let variant = &def.variants[*index];
+                if variant.fields.is_empty() {
+                    return format!("{ty}.{}", variant.name);
+                }
+                let fields: Vec<_> = fields
+                    .iter()
+                    .zip(&variant.fields)
+                    .map(|(field, field_ty)| self.fmt_pat(field, field_ty))
+                    .collect();
+                format!("{ty}.{}({})", variant.name, fields.join(", "))
+            }
+            (Ctor::Struct, Some(TypeDef::Struct(def))) => {
+                let mut shown: Vec<_> = fields
+                    .iter()
+                    .zip(&def.fields)
+                    .filter(|(field, _)| matches!(field, Pat::Ctor(..)))
+                    .map(|(field, def)| format!("{}: {}", def.name, self.fmt_pat(field, &def.ty)))
+                    .collect();
+                if shown.len() < def.fields.len() {
+                    shown.push("..".to_owned());
+                }
+                format!("{ty} {{ {} }}", shown.join(", "))
+            }
+            _ => unreachable!("`{ctor:?}` isn't a constructor of `{ty}`"),
+        }
+    }
+}
+
+/// Returns the row without its first pattern, replaced by the patterns of its
+/// fields, if it matches the values of the constructor. A wildcard matches them
+/// all, with wildcards for fields.
+fn specialize(row: &[Pat], ctor: &Ctor, arity: usize) -> Option<Vec<Pat>> {
+    let fields = match &row[0] {
+        Pat::Wild => vec![Pat::Wild; arity],
+        Pat::Ctor(head, fields) if head.covers(ctor) => fields.clone(),
+        Pat::Ctor(..) => return None,
+    };
+    Some([fields, row[1..].to_vec()].concat())
+}
@@ -0,0 +1,101 @@
+//! Array and slice types, array literals, indexing and slicing.
+
+mod common;
+
+use common::{check, check_body};
+use zom_errors::test_util::errors;
+
+#[test]
+fn literals_and_indexes() {
+    let diags = check(
+        "package test
+struct Grid { cells: [4]u8 }
+fn first(s: []const u8) u8 { return s[0]; }
+fn f() u8 {
+    var arr: [3]u8 = [1, 2, 3];
+    arr[0] = arr[1] + 1;
+    var g = Grid { cells: [0, 0, 0, 0] };
+    g.cells[3] = first(arr[1..arr.len]);
+    var s: []u8 = g.cells[..2];
+    s[1] = 4;
+    return first(s);
+}
+",
+    );
+    assert!(diags.is_empty(), "{diags:?}");
+
+    // the length is a `usize`
+    let diags = check_body("var arr = [1, 2]; var n: i32 = arr.len;");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+}
+
+#[test]
+fn literal_types() {
+    let diags = check_body("var arr: [2]u8 = [1, 300];");
+    assert_eq!(errors(&diags), ["literal out of range for `u8`"]);
+
+    let diags = check_body("var arr: [3]i32 = [1, 2];");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+
+    let diags = check_body("var arr = [1, true];");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+
+    let diags = check_body("var arr = [];");
+    assert_eq!(errors(&diags), ["type annotations needed"]);
+
+    let diags = check_body("var arr: [0]i32 = [];");
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn lengths() {
+    let diags = check_body("var n: usize = 2; var arr: [n]i32;");
+    assert_eq!(
+        errors(&diags),
+        ["length of an array must be an integer literal"]
+    );
+
+    let diags = check_body("var arr: [2u8]i32;");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+
+    let diags = check_body("var arr: [2]void;");
+    assert_eq!(errors(&diags), ["`void` cannot be the type of a value"]);
+
+    let diags = check("package test\nstruct Node { kids: [2]Node }\n");
+    assert_eq!(
+        errors(&diags),
+        ["recursive type `Node` has an infinite size"]
+    );
+}
+
+#[test]
+fn indexes() {
+    let diags = check_body("var n = 1; var arr = [1, 2]; var x = arr[n];");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+
+    let diags = check_body("var n = 1; var x = n[0];");
+    assert_eq!(errors(&diags), ["cannot index into a value of type `i32`"]);
+
+    let diags = check_body("const arr = [1, 2]; arr[0] = 3;");
+    assert_eq!(errors(&diags), ["cannot assign to a constant"]);
+
+    let diags = check_body("var arr = [1, 2]; arr.len = 3;");
+    assert_eq!(errors(&diags), ["no field `len` on type `[2]i32`"]);
+}
+
+#[test]
+fn slices() {
+    // a slice of a constant can't be assigned
+    let diags = check_body("const arr = [1, 2]; var s: []i32 = arr[..];");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+
+    let diags = check_body("const arr = [1, 2]; var s = arr[1..]; s[0] = 3;");
+    assert_eq!(errors(&diags), ["cannot assign to a constant"]);
+
+    // a `[]T` is a `[]const T`
+    let diags = check_body("var arr = [1, 2]; var s: []const i32 = arr[..1];");
+    assert!(diags.is_empty(), "{diags:?}");
+
+    let diags = check_body("var s = [1, 2][..];");
+    assert_eq!(errors(&diags), ["cannot slice a temporary array"]);
+}
@@ -0,0 +1,34 @@
+//! Helpers shared by the tests of the semantic analysis.
+
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use zom_errors::prelude::*;
+use zom_errors::test_util::{diags, Diag};
+use zom_lexer::Lexer;
+use zom_parser::Parser;
+use zom_sema::typeck::TypeChecker;
+
+/// Type checks a source file, without imports, and returns its diagnostics.
+///
+/// Panic:
+///   If the source file can't be parsed.
+pub fn check(source: &str) -> Vec<Diag> {
+    let path = Path::new("test.zom");
+    let lctx = LogContext::new(source, path, LogOutput::default());
+    let (ast, lctx) = match Parser::new(Lexer::new(source, path, lctx)).parse() {
+        FinalRes::Ok(ast, lctx) => (ast, lctx),
+        FinalRes::Err(_) => panic!("couldn't parse {source:?}"),
+    };
+    let logs = match TypeChecker::new(lctx).check(&ast) {
+        FinalRes::Ok(_, lctx) => lctx.stream(),
+        FinalRes::Err(logs) => logs,
+    };
+    diags(&logs)
+}
+
+/// Type checks the statements, in the body of a `void` function.
+pub fn check_body(body: &str) -> Vec<Diag> {
+    check(&format!("package test\n\nfn f() void {{\n{body}\n}}\n"))
+}
@@ -0,0 +1,97 @@
+//! Enum declarations and variants.
+
+mod common;
+
+use common::check;
+use zom_errors::test_util::{errors, Diag};
+
+const SHAPE: &str = "package test\nenum Shape { Circle(u32), Rect(u32, u32), Empty }\n";
+
+/// Type checks the statements in a function, after the declaration of
+/// `Shape`.
+fn check_with_shape(body: &str) -> Vec<Diag> {
+    check(&format!("{SHAPE}fn f() void {{\n{body}\n}}\n"))
+}
+
+#[test]
+fn variants() {
+    let diags = check_with_shape(
+        "var s = Shape.Circle(1);
+         s = Shape.Rect(2, 3);
+         const e: Shape = Shape.Empty;",
+    );
+    assert!(diags.is_empty(), "{diags:?}");
+
+    let diags = check_with_shape("var s = Shape.Square;");
+    assert_eq!(errors(&diags), ["no variant `Square` in enum `Shape`"]);
+}
+
+#[test]
+fn payloads() {
+    let diags = check_with_shape("var s = Shape.Circle;");
+    assert_eq!(errors(&diags), ["variant `Shape.Circle` carries a payload"]);
+
+    let diags = check_with_shape("var s = Shape.Empty(1);");
+    assert_eq!(
+        errors(&diags),
+        ["variant `Shape.Empty` doesn't carry a payload"]
+    );
+
+    let diags = check_with_shape("var s = Shape.Rect(1);");
+    assert_eq!(
+        errors(&diags),
+        ["function `Shape.Rect` takes 2 arguments but 1 was supplied"]
+    );
+
+    let diags = check_with_shape("var s = Shape.Circle(true);");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+}
+
+#[test]
+fn discriminants() {
+    let diags = check("package test\nenum E: u8 { A = 1, B, C = 255 }\n");
+    assert!(diags.is_empty(), "{diags:?}");
+
+    let diags = check("package test\nenum E: i8 { A = -1, B = 0 }\n");
+    assert!(diags.is_empty(), "{diags:?}");
+
+    let diags = check("package test\nenum E { A = 1, B = 0, C }\n");
+    assert_eq!(errors(&diags), ["discriminant `1` is used more than once"]);
+    assert_eq!(diags[0].cursor_msg.as_deref(), Some("already used by `A`"));
+
+    let diags = check("package test\nenum E: u8 { A = 255, B }\n");
+    assert_eq!(errors(&diags), ["literal out of range for `u8`"]);
+
+    let diags = check("package test\nenum E: u8 { A = 1 + 1 }\n");
+    assert_eq!(
+        errors(&diags),
+        ["discriminant of a variant must be an integer literal"]
+    );
+}
+
+#[test]
+fn declarations() {
+    let diags = check("package test\nenum E: bool { A }\n");
+    assert_eq!(
+        errors(&diags),
+        ["invalid representation `bool` for an enum"]
+    );
+
+    let diags = check("package test\nenum E { A, A }\n");
+    assert_eq!(errors(&diags), ["variant `A` is already declared"]);
+
+    let diags = check("package test\nenum E { A }\nstruct E { a: i32 }\n");
+    assert_eq!(errors(&diags), ["type `E` is declared multiple times"]);
+}
+
+#[test]
+fn variants_are_values_of_their_enum() {
+    let diags = check(&format!(
+        "{SHAPE}enum Color {{ Red }}\nfn f() void {{ var s: Shape = Color.Red; }}\n"
+    ));
+    assert_eq!(errors(&diags), ["mismatched types"]);
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("expected `Shape`, found `Color`")
+    );
+}
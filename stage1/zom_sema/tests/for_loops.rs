@@ -0,0 +1,139 @@
+//! `for` loops over ranges of integers, and over the elements of arrays and
+//! slices.
+
+mod common;
+
+use common::{check, check_body};
+use zom_errors::test_util::errors;
+
+#[test]
+fn ranges() {
+    let diags = check(
+        "package test
+fn f(n: u64) u64 {
+    var sum: u64 = 0;
+    for (i in 0..n) { sum += i; }
+    for (i in 0u64..=10 by 2) { sum += i; }
+    outer: for (i in -5..5) {
+        if (i == 0) { continue :outer; }
+        break :outer;
+    }
+    return sum;
+}
+",
+    );
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn range_types() {
+    let diags = check_body("for (i in 0..true) {}");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+
+    let diags = check_body("var a: u8 = 0; var b: i64 = 1; for (i in a..b) {}");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+
+    let diags = check_body("for (b in false..true) {}");
+    assert_eq!(errors(&diags), ["`for` loop over a range of `bool`"]);
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("only ranges of integers can be iterated")
+    );
+
+    // the untyped literals take the type of the other end
+    let diags = check_body("var n: u8 = 3; for (i in 0..n) { var x: u8 = i; }");
+    assert!(diags.is_empty(), "{diags:?}");
+
+    let diags = check_body("for (i in 0..300u8) {}");
+    assert_eq!(errors(&diags), ["literal out of range for `u8`"]);
+}
+
+#[test]
+fn steps() {
+    let diags = check_body("for (i in 0..10 by 0) {}");
+    assert_eq!(errors(&diags), ["the step of a range must be positive"]);
+
+    let diags = check_body("for (i in 10..0 by -1) {}");
+    assert_eq!(errors(&diags), ["the step of a range must be positive"]);
+
+    let diags = check_body("var s: u16 = 2; for (i in 0u16..10 by s) {}");
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn loop_variable() {
+    let diags = check_body("for (i in 0..10) { i = 2; }");
+    assert_eq!(errors(&diags), ["cannot assign to a constant"]);
+
+    let diags = check_body("for (i in 0..10) {} var x = i;");
+    assert_eq!(errors(&diags), ["cannot find variable `i` in this scope"]);
+}
+
+#[test]
+fn arrays_and_slices() {
+    let diags = check(
+        "package test
+fn sum(s: []const i32) i32 {
+    var total = 0;
+    for (x in s) { total += x; }
+    return total;
+}
+fn f() i32 {
+    var arr: [4]i32 = [1, 2, 3, 4];
+    var total = sum(arr[1..]);
+    for (i, x in arr) {
+        if (i == arr.len - 1) { break; }
+        total += x;
+    }
+    for (x in [5, 6]) { total += x; }
+    return total;
+}
+",
+    );
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn elements() {
+    // the elements have the type of the elements of the array
+    let diags = check_body("var arr: [2]u8 = [1, 2]; for (x in arr) { var y: u8 = x; }");
+    assert!(diags.is_empty(), "{diags:?}");
+
+    let diags = check_body("var arr = [true, false]; for (x in arr) { var y: i32 = x; }");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+
+    let diags = check_body("var n = 3; for (x in n) {}");
+    assert_eq!(errors(&diags), ["`for` loop over a value of `i32`"]);
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("only ranges of integers, arrays and slices can be iterated")
+    );
+
+    let diags = check_body("var arr = [1, 2]; for (x in arr) { x = 2; }");
+    assert_eq!(errors(&diags), ["cannot assign to a constant"]);
+}
+
+#[test]
+fn index_binding() {
+    // the index is a `usize`
+    let diags = check_body("var arr = [1, 2]; for (i, x in arr) { var j: usize = i; }");
+    assert!(diags.is_empty(), "{diags:?}");
+
+    let diags = check_body("var arr = [1, 2]; for (i, x in arr[..]) { var j: i32 = i; }");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+
+    let diags = check_body("var arr = [1, 2]; for (i, x in arr) { i = 0; }");
+    assert_eq!(errors(&diags), ["cannot assign to a constant"]);
+
+    let diags = check_body("for (i, x in 0..10) {}");
+    assert_eq!(errors(&diags), ["`for` loop over a range with an index"]);
+
+    let diags = check_body("var arr = [1, 2]; for (x, x in arr) {}");
+    assert_eq!(
+        errors(&diags),
+        ["`x` is bound more than once in the `for` loop"]
+    );
+
+    let diags = check_body("var arr = [1, 2]; for (i, x in arr) {} var j = i;");
+    assert_eq!(errors(&diags), ["cannot find variable `i` in this scope"]);
+}
@@ -0,0 +1,289 @@
+//! Loading the packages of a program, and using the items of an imported
+//! package.
+
+mod common;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use zom_common::token::TokenType;
+use zom_errors::{
+    prelude::*,
+    test_util::{diags, errors, Diag},
+};
+use zom_sema::{
+    loader::{Loader, Program},
+    typeck::{ResolvedImport, TypeChecker, TypeckResults},
+};
+
+/// A source root in the temporary directory, removed when dropped.
+struct Root(PathBuf);
+
+impl Root {
+    /// Creates a source root with the files, by path relative to the root.
+    fn new(name: &str, files: &[(&str, &str)]) -> Root {
+        let dir = env::temp_dir().join(format!("zom-loader-{name}-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (path, source) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+        Root(dir)
+    }
+
+    /// Loads the file of the root, and the packages it imports.
+    fn load(&self, file: &str) -> Program {
+        Loader::new(vec![self.0.clone()], LogOutput::default())
+            .load(&[self.0.join(file)])
+            .unwrap()
+    }
+}
+
+impl Drop for Root {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Type checks the packages of the program, in order, and returns the
+/// diagnostics of all of them, like `zom check` does.
+fn check_program(program: &Program) -> Vec<Diag> {
+    let mut all = Vec::new();
+    let mut checked: Vec<Option<TypeckResults>> = Vec::new();
+    for (id, package) in program.packages.iter().enumerate() {
+        let imports: Option<Vec<ResolvedImport>> = package
+            .imports
+            .iter()
+            .map(|import| {
+                Some(ResolvedImport {
+                    name: import.name.clone(),
+                    item: import.item.clone(),
+                    interface: checked[import.package].as_ref()?.interface.clone(),
+                    span: import.span.clone(),
+                })
+            })
+            .collect();
+        let Some(imports) = imports else {
+            checked.push(None);
+            continue;
+        };
+        match TypeChecker::new(program.lctx(id))
+            .with_imports(imports)
+            .check(&package.ast)
+        {
+            FinalRes::Ok(results, lctx) => {
+                all.extend(diags(&lctx.stream()));
+                checked.push(Some(results));
+            }
+            FinalRes::Err(logs) => {
+                all.extend(diags(&logs));
+                checked.push(None);
+            }
+        }
+    }
+    all
+}
+
+#[test]
+fn imports() {
+    let root = Root::new(
+        "imports",
+        &[
+            (
+                "main.zom",
+                "package main\nimport geo\nimport std.fmt.twice as double\n\
+                 fn main() i32 { return geo.ORIGIN + double(geo.area(2, 3)); }\n",
+            ),
+            (
+                "geo.zom",
+                "package geo\nimport std.fmt\npub const ORIGIN: i32 = 0\n\
+                 pub fn area(w: i32, h: i32) i32 { return fmt.twice(w * h) / 2; }\n",
+            ),
+            (
+                "std/fmt.zom",
+                "package std.fmt\npub fn twice(x: i32) i32 { return x * 2; }\n",
+            ),
+        ],
+    );
+    let program = root.load("main.zom");
+    assert_eq!(program.failed(), 0);
+    let names: Vec<_> = program.packages.iter().map(|p| p.name()).collect();
+    // a package comes after the packages it imports, and is loaded once
+    assert_eq!(names, ["std.fmt", "geo", "main"]);
+
+    let diags = check_program(&program);
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn root_tokens() {
+    let root = Root::new(
+        "tokens",
+        &[
+            ("main.zom", "package main\nimport geo\n"),
+            ("geo.zom", "package geo\n"),
+        ],
+    );
+    let program = Loader::new(vec![root.0.clone()], LogOutput::default())
+        .keeping_tokens()
+        .load(&[root.0.join("main.zom")])
+        .unwrap();
+    // only the tokens of the files given to the loader are kept
+    assert!(program.packages[0].tokens.is_empty());
+    let tokens: Vec<_> = program.packages[1]
+        .tokens
+        .iter()
+        .map(|token| token.tt.clone())
+        .collect();
+    assert_eq!(
+        tokens,
+        [
+            TokenType::Package,
+            TokenType::Ident("main".to_owned()),
+            TokenType::Import,
+            TokenType::Ident("geo".to_owned()),
+            TokenType::EOF,
+        ]
+    );
+
+    let program = root.load("main.zom");
+    assert!(program.packages[1].tokens.is_empty());
+}
+
+#[test]
+fn import_cycles() {
+    let root = Root::new(
+        "cycles",
+        &[
+            ("main.zom", "package main\nimport a\nfn main() void {}\n"),
+            ("a.zom", "package a\nimport b\n"),
+            ("b.zom", "package b\nimport a\n"),
+        ],
+    );
+    let program = root.load("main.zom");
+    assert!(program.packages.is_empty());
+    assert_eq!(program.failed(), 3);
+    let diags = diags(program.errors());
+    assert_eq!(
+        errors(&diags),
+        ["import cycle between packages `a` -> `b` -> `a`"]
+    );
+
+    let root = Root::new("self-import", &[("a.zom", "package a\nimport a\n")]);
+    let program = root.load("a.zom");
+    assert_eq!(
+        errors(&diags_of(&program)),
+        ["import cycle between packages `a` -> `a`"]
+    );
+}
+
+#[test]
+fn missing_packages() {
+    let root = Root::new(
+        "missing",
+        &[
+            (
+                "main.zom",
+                "package main\nimport std.net\nfn main() void {}\n",
+            ),
+            ("std/fmt.zom", "package std.fmt\n"),
+        ],
+    );
+    let program = root.load("main.zom");
+    assert!(program.packages.is_empty());
+    let diags = diags_of(&program);
+    assert_eq!(errors(&diags), ["cannot find package `std.net`"]);
+    let expected_file = Path::new("std").join("net.zom");
+    assert_eq!(
+        diags[0].cursor_msg,
+        Some(format!(
+            "no `{}` in the source root(s)",
+            expected_file.display()
+        ))
+    );
+}
+
+#[test]
+fn package_clause_mismatch() {
+    let root = Root::new(
+        "mismatch",
+        &[
+            ("main.zom", "package main\nimport geo\nfn main() void {}\n"),
+            ("geo.zom", "package shapes\n"),
+        ],
+    );
+    let program = root.load("main.zom");
+    assert!(program.packages.is_empty());
+    assert_eq!(
+        errors(&diags_of(&program)),
+        ["file of package `geo` declares package `shapes`"]
+    );
+}
+
+#[test]
+fn private_items() {
+    let root = Root::new(
+        "private",
+        &[
+            (
+                "main.zom",
+                "package main\nimport geo\n\
+                 fn main() i32 { return geo.area(2, 3); }\n\
+                 fn sides() i32 { return geo.SIDES; }\n",
+            ),
+            (
+                "geo.zom",
+                "package geo\nconst SIDES: i32 = 4\nfn area(w: i32, h: i32) i32 { return w * h; }\n",
+            ),
+        ],
+    );
+    let program = root.load("main.zom");
+    assert_eq!(program.failed(), 0);
+    let diags = check_program(&program);
+    assert_eq!(
+        errors(&diags),
+        [
+            "function `area` of package `geo` is private",
+            "global `SIDES` of package `geo` is private"
+        ]
+    );
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("only `pub` items can be used by other packages")
+    );
+
+    let root = Root::new(
+        "private-import",
+        &[
+            (
+                "main.zom",
+                "package main\nimport geo.area\nimport geo.Shape\n\
+                 fn main() i32 { return area(2, 3); }\n\
+                 fn sides(shape: Shape) i32 { return shape.sides; }\n",
+            ),
+            (
+                "geo.zom",
+                "package geo\nfn area(w: i32, h: i32) i32 { return w * h; }\n\
+                 struct Shape { pub sides: i32 }\n",
+            ),
+        ],
+    );
+    // the private items are still declared, and aren't reported again
+    let diags = check_program(&root.load("main.zom"));
+    assert_eq!(
+        errors(&diags),
+        [
+            "struct `Shape` of package `geo` is private",
+            "function `area` of package `geo` is private"
+        ]
+    );
+}
+
+/// Returns the errors of the files that couldn't be loaded.
+fn diags_of(program: &Program) -> Vec<Diag> {
+    diags(program.errors())
+}
@@ -0,0 +1,122 @@
+//! `impl` blocks, methods and their receivers.
+
+mod common;
+
+use common::check;
+use zom_errors::test_util::{errors, Diag};
+
+const COUNTER: &str = "package test
+struct Counter { n: i32 }
+impl Counter {
+    fn new() Counter { return Counter { n: 0 }; }
+    fn bump(*self) void { self->n += 1; }
+    fn get(*const self) i32 { return self->n; }
+    fn twice(self) i32 { return self.n * 2; }
+}
+";
+
+/// Type checks the statements in a function, after the declaration of
+/// `Counter` and its `impl` block.
+fn check_with_counter(body: &str) -> Vec<Diag> {
+    check(&format!("{COUNTER}fn f() void {{\n{body}\n}}\n"))
+}
+
+#[test]
+fn method_calls() {
+    let diags = check_with_counter(
+        "var c = Counter.new();
+         c.bump();
+         var n: i32 = c.get() + c.twice();
+         const p = &c;
+         p->bump();
+         n = p->get() + p->twice();
+         n = Counter.get(&c);
+         n = Counter { n: 1 }.twice();
+         n = Counter.new().get();",
+    );
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn pointer_receiver_on_a_constant() {
+    let diags = check_with_counter("const c = Counter.new(); c.bump();");
+    assert_eq!(
+        errors(&diags),
+        ["cannot call `Counter.bump`, taking `*self`, on a constant"]
+    );
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("`Counter.bump` may modify it")
+    );
+
+    // only reading it is fine
+    let diags = check_with_counter("const c = Counter.new(); var n = c.get() + c.twice();");
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn pointer_receiver_through_a_const_pointer() {
+    let diags =
+        check_with_counter("var c = Counter.new(); const p: *const Counter = &c; p->bump();");
+    assert_eq!(
+        errors(&diags),
+        ["cannot call `Counter.bump`, taking `*self`, on a `*const Counter`"]
+    );
+
+    let diags = check_with_counter(
+        "var c = Counter.new(); const p: *const Counter = &c; var n = p->get() + p->twice();",
+    );
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn arrow_on_a_value() {
+    let diags = check_with_counter("var c = Counter.new(); c->bump();");
+    assert_eq!(
+        errors(&diags),
+        ["cannot apply operator `->` to type `Counter`"]
+    );
+}
+
+#[test]
+fn calling_a_non_method_through_a_dot() {
+    let diags = check_with_counter("var c = Counter.new(); var d = c.new();");
+    assert_eq!(errors(&diags), ["`Counter.new` isn't a method"]);
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("it doesn't take `self`, consider calling it as `Counter.new(..)`")
+    );
+}
+
+#[test]
+fn unknown_methods() {
+    let diags = check_with_counter("var c = Counter.new(); c.reset();");
+    assert_eq!(
+        errors(&diags),
+        ["no function or method `reset` for type `Counter`"]
+    );
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("not found in the `impl` blocks of `Counter`")
+    );
+}
+
+#[test]
+fn impl_blocks() {
+    let diags = check("package test\nimpl i32 { fn zero() i32 { return 0; } }\n");
+    assert_eq!(errors(&diags), ["cannot define an `impl` block for `i32`"]);
+
+    let diags = check("package test\nstruct S { a: i32 }\npub impl S {}\n");
+    assert_eq!(errors(&diags), ["an `impl` block can't be `pub`"]);
+
+    let diags = check(
+        "package test\nstruct S { a: i32 }\nimpl S { fn f() void {} }\nimpl S { fn f() void {} }\n",
+    );
+    assert_eq!(errors(&diags), ["function `S.f` is defined multiple times"]);
+
+    let diags = check("package test\nfn f(self) void {}\n");
+    assert_eq!(
+        errors(&diags),
+        ["`self` receiver outside of an `impl` block"]
+    );
+}
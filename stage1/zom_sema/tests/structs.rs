@@ -0,0 +1,89 @@
+//! Struct declarations, literals and field access.
+
+mod common;
+
+use common::check;
+use zom_errors::test_util::{errors, Diag};
+
+const POINT: &str = "package test\nstruct Point { x: i32, y: i32 }\n";
+
+/// Type checks the statements in a function, after the declaration of `Point`.
+fn check_with_point(body: &str) -> Vec<Diag> {
+    check(&format!("{POINT}fn f() void {{\n{body}\n}}\n"))
+}
+
+#[test]
+fn literals_and_fields() {
+    let diags = check_with_point(
+        "var p = Point { y: 2, x: 1 };
+         p.x = p.y + 1;
+         const q: Point = Point { x: p.x, y: 0 };
+         var r: *Point = &p;
+         r->y = q.x;",
+    );
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn missing_fields() {
+    let diags = check_with_point("var p = Point { x: 1 };");
+    assert_eq!(
+        errors(&diags),
+        ["missing field(s) `y` in the literal of `Point`"]
+    );
+
+    let diags = check_with_point("var p = Point {};");
+    assert_eq!(
+        errors(&diags),
+        ["missing field(s) `x`, `y` in the literal of `Point`"]
+    );
+}
+
+#[test]
+fn fields_initialized_twice() {
+    let diags = check_with_point("var p = Point { x: 1, y: 2, x: 3 };");
+    assert_eq!(errors(&diags), ["field `x` is initialized more than once"]);
+}
+
+#[test]
+fn unknown_fields() {
+    let diags = check_with_point("var p = Point { x: 1, y: 2, z: 3 };");
+    assert_eq!(errors(&diags), ["no field `z` on type `Point`"]);
+
+    let diags = check_with_point("var p = Point { x: 1, y: 2 }; var z = p.z;");
+    assert_eq!(errors(&diags), ["no field `z` on type `Point`"]);
+}
+
+#[test]
+fn field_types() {
+    let diags = check_with_point("var p = Point { x: true, y: 2 };");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("expected `i32`, found `bool`")
+    );
+}
+
+#[test]
+fn literal_of_a_non_struct() {
+    let diags = check("package test\nenum E { A }\nfn f() void { var e = E {}; }\n");
+    assert_eq!(errors(&diags), ["`E` is not a struct"]);
+}
+
+#[test]
+fn fields_of_constants() {
+    let diags = check_with_point("const p = Point { x: 1, y: 2 }; p.x = 3;");
+    assert_eq!(errors(&diags), ["cannot assign to a constant"]);
+}
+
+#[test]
+fn declarations() {
+    let diags = check("package test\nstruct S { a: i32, a: u8 }\n");
+    assert_eq!(errors(&diags), ["field `a` is already declared"]);
+
+    let diags = check("package test\nstruct S { next: S }\n");
+    assert_eq!(errors(&diags), ["recursive type `S` has an infinite size"]);
+
+    let diags = check("package test\nstruct S { next: *S }\n");
+    assert!(diags.is_empty(), "{diags:?}");
+}
@@ -0,0 +1,138 @@
+//! Type checking of expressions, statements and functions.
+
+mod common;
+
+use common::{check, check_body};
+use zom_errors::test_util::errors;
+
+#[test]
+fn well_typed() {
+    let diags = check(
+        "package test
+
+fn add(a: i32, b: i32) i32 {
+    return a + b;
+}
+
+fn main() i32 {
+    var x: i32 = add(1, 2);
+    x = x * 2;
+    const big: u64 = 1 << 40;
+    return x;
+}
+",
+    );
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn mismatched_types() {
+    let diags = check_body("var x: i32 = true;");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("expected `i32`, found `bool`")
+    );
+
+    let diags = check("package test\nfn f() u8 { return 1 == 1; }\n");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+
+    let diags = check_body("var a: i32 = 1; var b: i64 = 2; var c = a + b;");
+    assert_eq!(errors(&diags), ["mismatched types"]);
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("expected `i32`, found `i64`")
+    );
+}
+
+#[test]
+fn untyped_literal_out_of_range() {
+    let diags = check_body("var x: u8 = 256;");
+    assert_eq!(errors(&diags), ["literal out of range for `u8`"]);
+}
+
+#[test]
+fn undefined_names() {
+    let diags = check_body("var x = y + 1;");
+    assert_eq!(errors(&diags), ["cannot find variable `y` in this scope"]);
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("not found in this scope")
+    );
+
+    let diags = check_body("g();");
+    assert_eq!(errors(&diags), ["cannot find function `g` in this scope"]);
+
+    let diags = check("package test\nfn f(x: Foo) void {}\n");
+    assert_eq!(errors(&diags), ["cannot find type `Foo` in this scope"]);
+
+    // a variable isn't visible outside of its block
+    let diags = check_body("{ var x = 1; } x = 2;");
+    assert_eq!(errors(&diags), ["cannot find variable `x` in this scope"]);
+}
+
+#[test]
+fn assignment_to_constants() {
+    let diags = check_body("const x: i32 = 1; x = 2;");
+    assert_eq!(errors(&diags), ["cannot assign to a constant"]);
+
+    let diags = check("package test\nconst LIMIT: i32 = 1\nfn f() void { LIMIT = 2; }\n");
+    assert_eq!(errors(&diags), ["cannot assign to a constant"]);
+
+    let diags = check_body("const x: i32 = 1; x += 2;");
+    assert_eq!(errors(&diags), ["cannot assign to a constant"]);
+
+    let diags = check("package test\nfn f(a: i32) void { a = 2; }\n");
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn main_signatures() {
+    for main in [
+        "fn main() void {}",
+        "fn main() i32 { return 0; }",
+        "fn main() u8 { return 0; }",
+        "fn main(argc: i32, argv: **u8) i32 { return argc; }",
+    ] {
+        let diags = check(&format!("package test\n{main}\n"));
+        assert!(diags.is_empty(), "{main}: {diags:?}");
+    }
+
+    for main in [
+        "fn main() bool { return true; }",
+        "fn main(x: i32) void {}",
+        "fn main(argc: i32, argv: *u8) void {}",
+        "fn main(argc: i64, argv: **u8) void {}",
+    ] {
+        let diags = check(&format!("package test\n{main}\n"));
+        assert_eq!(
+            errors(&diags),
+            ["invalid signature for the `main` function"],
+            "{main}"
+        );
+    }
+}
+
+#[test]
+fn missing_return() {
+    let diags = check("package test\nfn f(x: bool) i32 { if (x) { return 1; } }\n");
+    assert_eq!(
+        errors(&diags),
+        ["function `f` may end without returning a value"]
+    );
+}
+
+#[test]
+fn wrong_argument_count() {
+    let diags = check("package test\nfn g(a: i32) void {}\nfn f() void { g(1, 2); }\n");
+    assert_eq!(
+        errors(&diags),
+        ["function `g` takes 1 argument but 2 were supplied"]
+    );
+}
+
+#[test]
+fn break_outside_of_a_loop() {
+    let diags = check_body("break;");
+    assert_eq!(errors(&diags), ["`break` outside of a loop"]);
+}
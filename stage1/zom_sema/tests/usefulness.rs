@@ -0,0 +1,144 @@
+//! Exhaustiveness of the matches and unreachable arms.
+
+mod common;
+
+use common::check;
+use zom_errors::test_util::{errors, warnings, Diag};
+
+/// Type checks a function matching on `x`, of type `ty`, with the arms, after
+/// the declarations.
+fn check_match(decls: &str, ty: &str, arms: &str) -> Vec<Diag> {
+    check(&format!(
+        "package test\n{decls}\nfn f(x: {ty}) i32 {{\n    return match x {{\n{arms}\n    }};\n}}\n"
+    ))
+}
+
+const COLOR: &str = "enum Color { Red, Green, Blue }";
+const SHAPE: &str = "enum Shape { Circle(u32), Rect(u32, u32), Empty }";
+const POINT: &str = "struct Point { x: bool, y: bool }";
+
+#[test]
+fn exhaustive() {
+    let diags = check_match(
+        COLOR,
+        "Color",
+        "Color.Red => 1, Color.Green => 2, Color.Blue => 3,",
+    );
+    assert!(diags.is_empty(), "{diags:?}");
+
+    let diags = check_match("", "u8", "0 => 1, 1..=254 => 2, 255 => 3,");
+    assert!(diags.is_empty(), "{diags:?}");
+
+    let diags = check_match("", "bool", "true => 1, false => 0,");
+    assert!(diags.is_empty(), "{diags:?}");
+}
+
+#[test]
+fn missing_variants() {
+    let diags = check_match(COLOR, "Color", "Color.Red => 1, Color.Green => 2,");
+    assert_eq!(
+        errors(&diags),
+        ["non-exhaustive patterns: `Color.Blue` not covered"]
+    );
+
+    let diags = check_match(COLOR, "Color", "Color.Green => 2,");
+    assert_eq!(
+        errors(&diags),
+        ["non-exhaustive patterns: `Color.Red` and `Color.Blue` not covered"]
+    );
+
+    let diags = check_match(
+        SHAPE,
+        "Shape",
+        "Shape.Circle(_) => 1, Shape.Rect(0, _) => 2, Shape.Empty => 3,",
+    );
+    assert_eq!(
+        errors(&diags),
+        ["non-exhaustive patterns: `Shape.Rect(1..=4294967295, _)` not covered"]
+    );
+}
+
+#[test]
+fn integer_range_gaps() {
+    let diags = check_match("", "u8", "0..=9 => 1, 11..=255 => 2,");
+    assert_eq!(
+        errors(&diags),
+        ["non-exhaustive patterns: `10` not covered"]
+    );
+
+    let diags = check_match("", "i8", "-128..0 => 1, 1..=127 => 2,");
+    assert_eq!(errors(&diags), ["non-exhaustive patterns: `0` not covered"]);
+
+    let diags = check_match("", "u8", "0..100 => 1, 200 => 2,");
+    assert_eq!(
+        errors(&diags),
+        ["non-exhaustive patterns: `100..=199` and `201..=255` not covered"]
+    );
+
+    let diags = check_match("", "i32", "0 => 1,");
+    assert_eq!(
+        errors(&diags),
+        ["non-exhaustive patterns: `-2147483648..=-1` and `1..=2147483647` not covered"]
+    );
+}
+
+#[test]
+fn nested_struct_patterns() {
+    let diags = check_match(
+        POINT,
+        "Point",
+        "Point { x: true, .. } => 1, Point { x: false, y: true } => 2,",
+    );
+    assert_eq!(
+        errors(&diags),
+        ["non-exhaustive patterns: `Point { x: false, y: false }` not covered"]
+    );
+
+    let diags = check_match(
+        &format!("{POINT}\nstruct Line {{ a: Point, b: Point }}"),
+        "Line",
+        "Line { a: Point { x: true, y: _ }, b: _ } => 1,
+         Line { a: Point { x: false, y }, b: Point { x, y: true } } => 2,",
+    );
+    assert_eq!(
+        errors(&diags),
+        ["non-exhaustive patterns: `Line { a: Point { x: false, .. }, b: Point { y: false, .. } }` not covered"]
+    );
+}
+
+#[test]
+fn guards_are_not_exhaustive() {
+    let diags = check_match("", "bool", "true => 1, false if 1 == 1 => 0,");
+    assert_eq!(
+        errors(&diags),
+        ["non-exhaustive patterns: `false` not covered"]
+    );
+}
+
+#[test]
+fn unreachable_arms() {
+    let diags = check_match(COLOR, "Color", "_ => 1, Color.Red => 2,");
+    assert!(errors(&diags).is_empty(), "{diags:?}");
+    assert_eq!(warnings(&diags), ["unreachable pattern"]);
+    assert_eq!(
+        diags[0].cursor_msg.as_deref(),
+        Some("every value it matches is matched by the previous arms")
+    );
+
+    let diags = check_match("", "u8", "0..=5 => 1, 3 => 2, 6..=255 => 3, 255 => 4,");
+    assert_eq!(
+        warnings(&diags),
+        ["unreachable pattern", "unreachable pattern"]
+    );
+
+    let diags = check_match(
+        POINT,
+        "Point",
+        "Point { x: true, .. } => 1, Point { x: false, .. } => 2, Point { x: _, y: true } => 3,",
+    );
+    assert_eq!(warnings(&diags), ["unreachable pattern"]);
+
+    // an arm with a guard may not match, the next arms are still reachable
+    let diags = check_match("", "bool", "true if 1 == 1 => 1, true => 2, false => 3,");
+    assert!(diags.is_empty(), "{diags:?}");
+}